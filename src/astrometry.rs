@@ -0,0 +1,5 @@
+pub mod aberration;
+pub mod deflection;
+pub mod light_time;
+pub mod refraction;
+pub mod star;
@@ -0,0 +1,55 @@
+use crate::base::Result;
+use crate::base::consts::{C_LIGHT, DAY, OMEGA_EARTH};
+use crate::base::linalg::{Norm, Vec3D};
+use crate::ephemeris::{Body, Ephemeris};
+use crate::frames::topocentric::observer_position;
+use crate::observer::Observer;
+use crate::time::JulianDate;
+
+///
+/// classical: Direction corrected for the aberration to the first order in
+/// v/c for the unit direction and the velocity of the observer [AU/d]
+///
+pub fn classical(u: Vec3D, velocity: Vec3D) -> Result<Vec3D> {
+    let beta = (velocity / C_LIGHT)?;
+    let p = u + beta - u * u.dot(beta);
+
+    p / p.norm()
+}
+
+///
+/// relativistic: Direction corrected for the aberration by the Lorentz
+/// transformation for the unit direction and the velocity of the observer
+/// [AU/d]
+///
+pub fn relativistic(u: Vec3D, velocity: Vec3D) -> Result<Vec3D> {
+    let beta = (velocity / C_LIGHT)?;
+    let gamma_inv = (1.0 - beta.dot(beta)).sqrt();
+    let pb = u.dot(beta);
+    let p = (u * gamma_inv + beta * (1.0 + pb / (1.0 + gamma_inv))) *
+        (1.0 / (1.0 + pb));
+
+    p / p.norm()
+}
+
+///
+/// annual_velocity: Barycentric velocity of the centre of the Earth referred
+/// to the ICRS for the TT date [AU/d]
+///
+pub fn annual_velocity<E>(ephemeris: &E, jd: JulianDate) -> Result<Vec3D>
+    where E: Ephemeris + ?Sized
+{
+    let (_, velocity) = ephemeris.state(Body::Earth, jd)?;
+    Ok(velocity)
+}
+
+///
+/// diurnal_velocity: Geocentric velocity of the observer due to the
+/// rotation of the Earth referred to the true equator and equinox of the TT
+/// date [AU/d]
+///
+pub fn diurnal_velocity(observer: &Observer, jd: JulianDate) -> Vec3D {
+    let omega = Vec3D::unit_z() * (OMEGA_EARTH * DAY);
+    omega.cross(observer_position(observer, jd))
+}
+
@@ -0,0 +1,70 @@
+use crate::base::Result;
+use crate::base::consts::C_LIGHT;
+use crate::base::linalg::{Norm, Vec3D};
+use crate::ephemeris::{Body, Ephemeris};
+use crate::time::JulianDate;
+
+///
+/// Schwarzschild radius of the Sun [AU]
+///
+const SCHWARZSCHILD_RADIUS: f64 = 1.97412574336e-8;
+
+///
+/// Lower limit of q.(q + e) avoiding the singularity behind the deflector
+///
+const LIMIT: f64 = 1e-9;
+
+///
+/// deflection: Direction of the source deflected by a single body (IERS
+/// Conventions 2010, eq. 7.67) for the unit direction p from the observer to
+/// the source, the unit direction q from the body to the source, the unit
+/// direction e from the body to the observer, the distance of the observer
+/// from the body [AU] and the mass of the body [solar masses]
+///
+pub fn deflection(p: Vec3D, q: Vec3D, e: Vec3D, distance: f64,
+                  mass: f64) -> Result<Vec3D> {
+    let w = mass * SCHWARZSCHILD_RADIUS / distance /
+        q.dot(q + e).max(LIMIT);
+    let p = p + p.cross(e.cross(q)) * w;
+
+    p / p.norm()
+}
+
+///
+/// light_deflection: Direction of the source seen by the observer deflected
+/// by the Sun and the other deflectors (e.g. Jupiter and Saturn) for the
+/// barycentric position of the observer referred to the ICRS [AU], the unit
+/// direction of the source and its distance from the observer [AU] (None for
+/// a star) at the TT date. The deflectors are taken at the time when the
+/// light passes them.
+///
+pub fn light_deflection<E>(ephemeris: &E, observer: Vec3D, direction: Vec3D,
+                           distance: Option<f64>, deflectors: &[Body],
+                           jd: JulianDate) -> Result<Vec3D>
+    where E: Ephemeris + ?Sized
+{
+    let p = (direction / direction.norm())?;
+    let source = distance.map(|d| observer + p * d);
+
+    let mut u = p;
+    let others = deflectors.iter().filter(|&&body| body != Body::Sun);
+    for &body in others.chain([Body::Sun].iter()) {
+        let position = ephemeris.position(body, jd)?;
+        let tau = (observer - position).norm() / C_LIGHT;
+        let position = ephemeris.position(body, jd - tau)?;
+
+        let e = observer - position;
+        let em = e.norm();
+        let q = match source {
+            Some(source) => {
+                let q = source - position;
+                (q / q.norm())?
+            },
+            None => p
+        };
+
+        u = deflection(u, q, (e / em)?, em, 1.0 / body.mass_ratio())?;
+    }
+
+    Ok(u)
+}
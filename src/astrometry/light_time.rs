@@ -0,0 +1,78 @@
+use crate::base::Result;
+use crate::base::consts::C_LIGHT;
+use crate::base::error::Error;
+use crate::base::linalg::{Norm, Vec3D};
+use crate::ephemeris::{Body, Ephemeris};
+use crate::time::JulianDate;
+
+///
+/// Accuracy of the light time [d]
+///
+const ACCURACY: f64 = 1e-12;
+
+///
+/// Maximum number of iterations
+///
+const MAX_ITERATIONS: usize = 10;
+
+///
+/// LightTime: Astrometric position of a body relative to an observer
+/// referred to the ICRS [AU], the light time [d] and the number of the
+/// iterations of the light-time equation
+///
+#[derive(Debug, Copy, Clone)]
+pub struct LightTime {
+    position: Vec3D,
+    light_time: f64,
+    iterations: usize
+}
+
+impl LightTime {
+    ///
+    /// new: Solves the light-time equation |r(t - tau) - r0| = c tau by the
+    /// Newton's method for the barycentric position of the observer r0
+    /// referred to the ICRS [AU] at the TDB date of the reception t
+    ///
+    pub fn new<E>(ephemeris: &E, observer: Vec3D, body: Body,
+                  jd: JulianDate) -> Result<LightTime>
+        where E: Ephemeris + ?Sized
+    {
+        let mut tau = 0.0;
+
+        for iterations in 1..=MAX_ITERATIONS {
+            let (r, v) = ephemeris.state(body, jd - tau)?;
+            let position = r - observer;
+            let distance = position.norm();
+
+            let u = (position / distance)?;
+            let step = (distance - C_LIGHT * tau) / (C_LIGHT + u.dot(v));
+            tau += step;
+
+            if step.abs() < ACCURACY {
+                let (r, _) = ephemeris.state(body, jd - tau)?;
+                return Ok(LightTime {
+                    position: r - observer,
+                    light_time: tau,
+                    iterations
+                });
+            }
+        }
+
+        Err(Error::ConvergenceError)
+    }
+
+    pub fn position(&self) -> Vec3D {
+        self.position
+    }
+
+    ///
+    /// light_time: Light time [d]
+    ///
+    pub fn light_time(&self) -> f64 {
+        self.light_time
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+}
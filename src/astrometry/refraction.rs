@@ -0,0 +1,381 @@
+use crate::base::angle::Angle;
+use crate::base::consts::{DEG, RAD};
+
+///
+/// Universal gas constant [J/(kmol K)] and molecular weights of the dry air
+/// and of the water vapour [kg/kmol]
+///
+const GAS_CONSTANT: f64 = 8314.32;
+const DRY_AIR: f64 = 28.9644;
+const WATER_VAPOUR: f64 = 18.0152;
+
+///
+/// Radius of the Earth of the model [m]
+///
+const RADIUS: f64 = 6378120.0;
+
+///
+/// Exponent of the temperature dependence of the water vapour pressure
+///
+const DELTA: f64 = 18.36;
+
+///
+/// Heights of the tropopause and of the upper limit of the stratosphere [m]
+///
+const TROPOPAUSE: f64 = 11000.0;
+const STRATOSPHERE: f64 = 80000.0;
+
+///
+/// Largest zenith distance of the ray tracing [rad]
+///
+const MAX_ZENITH_DISTANCE: f64 = 93.0 * RAD;
+
+///
+/// Lowest altitude of the Bennett's and the Saemundsson's formulas [deg],
+/// the formulas diverge below -4.4 deg and -5.11 deg
+///
+const MIN_ALTITUDE: f64 = -1.0;
+
+///
+/// Accuracy of the ray-traced refraction [rad] and the maximum number of
+/// the integration intervals
+///
+const ACCURACY: f64 = 1e-10;
+const MAX_INTERVALS: usize = 16384;
+
+///
+/// Atmosphere: Conditions at the observer: the pressure [hPa], the
+/// temperature [deg C], the relative humidity (0 to 1), the wavelength of
+/// the light [um], the lapse rate of the troposphere [K/m], the height above
+/// the sea level [m] and the latitude. The default atmosphere has 1010 hPa
+/// and 10 deg C, the reference conditions of the Bennett's and the
+/// Saemundsson's formulas.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Atmosphere {
+    pressure: f64,
+    temperature: f64,
+    humidity: f64,
+    wavelength: f64,
+    lapse_rate: f64,
+    height: f64,
+    latitude: Angle
+}
+
+impl Default for Atmosphere {
+    fn default() -> Atmosphere {
+        Atmosphere::new(1010.0, 10.0)
+    }
+}
+
+///
+/// Model atmosphere giving the refractive index and r dn/dr at the radius r
+/// in the troposphere (polytropic with the constant lapse rate) or in the
+/// stratosphere (isothermal), beta is g M / R and gamma the polytropic
+/// exponent beta / lapse rate
+///
+struct Model {
+    r0: f64,
+    t0: f64,
+    lapse_rate: f64,
+    beta: f64,
+    gamma: f64,
+    c1: f64,
+    c2: f64,
+    c3: f64,
+    c4: f64,
+    rt: f64,
+    tt: f64,
+    nt: f64
+}
+
+impl Model {
+    fn troposphere(&self, r: f64) -> (f64, f64) {
+        let t = (self.t0 - self.lapse_rate * (r - self.r0))
+            .clamp(100.0, 320.0);
+        let tt0 = t / self.t0;
+        let tt0gm2 = tt0.powf(self.gamma - 2.0);
+        let tt0dm2 = tt0.powf(DELTA - 2.0);
+
+        (
+            1.0 + (self.c1 * tt0gm2 - self.c2 * tt0dm2) * tt0,
+            r * (-self.c3 * tt0gm2 + self.c4 * tt0dm2)
+        )
+    }
+
+    fn stratosphere(&self, r: f64) -> (f64, f64) {
+        let b = self.beta / self.tt;
+        let w = (self.nt - 1.0) * (-b * (r - self.rt)).exp();
+
+        (1.0 + w, -r * b * w)
+    }
+
+    fn at(&self, troposphere: bool, r: f64) -> (f64, f64) {
+        if troposphere {
+            self.troposphere(r)
+        } else {
+            self.stratosphere(r)
+        }
+    }
+
+    ///
+    /// Simpson's integration of the refraction from the start (zenith
+    /// distance, radius, integrand) to the end (zenith distance, integrand)
+    /// with the doubling of the number of the intervals
+    ///
+    fn integrate(&self, troposphere: bool, k: f64,
+                 (z0, r0, f0): (f64, f64, f64),
+                 (z1, f1): (f64, f64)) -> f64 {
+        let range = z1 - z0;
+        let mut intervals = 8;
+        let mut even = 0.0;
+        let mut odd = 0.0;
+        let mut previous = 0.0;
+        let mut stride = 1;
+
+        loop {
+            let h = range / intervals as f64;
+            let mut r = r0;
+
+            for i in (1..intervals).step_by(stride) {
+                let sz = (z0 + h * i as f64).sin();
+                if sz > 1e-20 {
+                    let w = k / sz;
+                    let mut dr = f64::MAX;
+                    for _ in 0..4 {
+                        if dr.abs() <= 1.0 {
+                            break;
+                        }
+                        let (n, rdndr) = self.at(troposphere, r);
+                        dr = (r * n - w) / (n + rdndr);
+                        r -= dr;
+                    }
+                }
+
+                let f = integrand(self.at(troposphere, r));
+                if stride == 1 && i % 2 == 0 {
+                    even += f;
+                } else {
+                    odd += f;
+                }
+            }
+
+            let refraction = h * (f0 + 4.0 * odd + 2.0 * even + f1) / 3.0;
+            if (refraction - previous).abs() <= ACCURACY / 2.0 ||
+                intervals >= MAX_INTERVALS {
+                return refraction;
+            }
+
+            previous = refraction;
+            intervals *= 2;
+            even += odd;
+            odd = 0.0;
+            stride = 2;
+        }
+    }
+}
+
+///
+/// Integrand of the refraction integral over the zenith distance
+///
+fn integrand((n, rdndr): (f64, f64)) -> f64 {
+    rdndr / (n + rdndr)
+}
+
+impl Atmosphere {
+    ///
+    /// new: Dry atmosphere with the pressure [hPa] and the temperature [deg
+    /// C] for the yellow light at the sea level
+    ///
+    pub fn new(pressure: f64, temperature: f64) -> Atmosphere {
+        Atmosphere {
+            pressure,
+            temperature,
+            humidity: 0.0,
+            wavelength: 0.574,
+            lapse_rate: 0.0065,
+            height: 0.0,
+            latitude: Angle::from_ad(45.0)
+        }
+    }
+
+    pub fn with_humidity(mut self, humidity: f64) -> Self {
+        self.humidity = humidity;
+        self
+    }
+
+    pub fn with_wavelength(mut self, wavelength: f64) -> Self {
+        self.wavelength = wavelength;
+        self
+    }
+
+    pub fn with_lapse_rate(mut self, lapse_rate: f64) -> Self {
+        self.lapse_rate = lapse_rate;
+        self
+    }
+
+    pub fn with_height(mut self, height: f64) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn with_latitude(mut self, latitude: Angle) -> Self {
+        self.latitude = latitude;
+        self
+    }
+
+    pub fn pressure(&self) -> f64 {
+        self.pressure
+    }
+
+    pub fn temperature(&self) -> f64 {
+        self.temperature
+    }
+
+    pub fn humidity(&self) -> f64 {
+        self.humidity
+    }
+
+    pub fn wavelength(&self) -> f64 {
+        self.wavelength
+    }
+
+    pub fn lapse_rate(&self) -> f64 {
+        self.lapse_rate
+    }
+
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    pub fn latitude(&self) -> Angle {
+        self.latitude
+    }
+
+    ///
+    /// Scaling of the simple formulas by the pressure and the temperature
+    ///
+    fn scale(&self) -> f64 {
+        self.pressure / 1010.0 * 283.0 / (273.0 + self.temperature)
+    }
+
+    ///
+    /// bennett: Refraction for the apparent altitude (Bennett, 1982), about
+    /// 0.07' accurate above the horizon. The altitude is limited to -1 deg.
+    ///
+    pub fn bennett(&self, altitude: Angle) -> Angle {
+        let h: f64 = altitude.into();
+        let h = (h * DEG).clamp(MIN_ALTITUDE, 90.0);
+
+        Angle::from_am(self.scale() / ((h + 7.31 / (h + 4.4)) * RAD).tan())
+    }
+
+    ///
+    /// saemundsson: Refraction for the true altitude (Saemundsson, 1986),
+    /// consistent with the Bennett's formula within 0.1'. The altitude is
+    /// limited to -1 deg.
+    ///
+    pub fn saemundsson(&self, altitude: Angle) -> Angle {
+        let h: f64 = altitude.into();
+        let h = (h * DEG).clamp(MIN_ALTITUDE, 90.0);
+
+        Angle::from_am(
+            1.02 * self.scale() / ((h + 10.3 / (h + 5.11)) * RAD).tan()
+        )
+    }
+
+    ///
+    /// ray_traced: Refraction for the apparent altitude by the numerical
+    /// integration through the model atmosphere of Hohenkerk and Sinclair
+    /// (1985): a polytropic troposphere with the water vapour up to 11 km
+    /// and an isothermal stratosphere up to 80 km. The altitude is limited
+    /// to -3 deg.
+    ///
+    pub fn ray_traced(&self, altitude: Angle) -> Angle {
+        let altitude: f64 = altitude.into();
+        let z0 = (std::f64::consts::FRAC_PI_2 - altitude)
+            .min(MAX_ZENITH_DISTANCE);
+
+        let model = self.model();
+        let (n0, rdndr0) = model.troposphere(model.r0);
+        let k = n0 * model.r0 * z0.sin();
+
+        let zenith_distance = |(n, _): (f64, f64), r: f64| {
+            let s = k / (r * n);
+            s.atan2((1.0 - s * s).max(0.0).sqrt())
+        };
+
+        let tropopause = model.troposphere(model.rt);
+        let zt = zenith_distance(tropopause, model.rt);
+        let rs = RADIUS + STRATOSPHERE;
+        let top = model.stratosphere(rs);
+        let zs = zenith_distance(top, rs);
+        let zts = zenith_distance(model.stratosphere(model.rt), model.rt);
+
+        let troposphere = model.integrate(
+            true, k, (z0, model.r0, integrand((n0, rdndr0))),
+            (zt, integrand(tropopause))
+        );
+        let stratosphere = model.integrate(
+            false, k,
+            (zts, model.rt, integrand(model.stratosphere(model.rt))),
+            (zs, integrand(top))
+        );
+
+        Angle::from(troposphere + stratosphere)
+    }
+
+    fn model(&self) -> Model {
+        let height = self.height.clamp(-1000.0, STRATOSPHERE);
+        let t0 = (self.temperature + 273.15).clamp(100.0, 500.0);
+        let pressure = self.pressure.clamp(0.0, 10000.0);
+        let humidity = self.humidity.clamp(0.0, 1.0);
+        let wavelength = self.wavelength.max(0.1);
+        let lapse_rate = self.lapse_rate.abs().clamp(0.001, 0.01);
+        let latitude: f64 = self.latitude.into();
+
+        let w2 = 1.0 / (wavelength * wavelength);
+        let g = 9.784 * (1.0 - 0.0026 * (2.0 * latitude).cos() -
+            0.00000028 * height);
+        let a = (287.6155 + (1.62887 + 0.01360 * w2) * w2) * 273.15e-6 /
+            1013.25;
+        let beta = g * DRY_AIR / GAS_CONSTANT;
+        let gamma = beta / lapse_rate;
+
+        let tdc = t0 - 273.15;
+        let saturation = 10.0f64.powf(
+            (0.7859 + 0.03477 * tdc) / (1.0 + 0.00412 * tdc)
+        ) * (1.0 + pressure * (4.5e-6 + 6e-10 * tdc * tdc));
+        let vapour = if pressure > 0.0 {
+            humidity * saturation /
+                (1.0 - (1.0 - humidity) * saturation / pressure)
+        } else {
+            0.0
+        };
+        let w = vapour * (1.0 - WATER_VAPOUR / DRY_AIR) * gamma /
+            (DELTA - gamma);
+
+        let c1 = a * (pressure + w) / t0;
+        let c2 = (a * w + 11.2684e-6 * vapour) / t0;
+        let r0 = RADIUS + height;
+
+        let mut model = Model {
+            r0,
+            t0,
+            lapse_rate,
+            beta,
+            gamma,
+            c1,
+            c2,
+            c3: (gamma - 1.0) * lapse_rate * c1 / t0,
+            c4: (DELTA - 1.0) * lapse_rate * c2 / t0,
+            rt: RADIUS + TROPOPAUSE.max(height),
+            tt: 0.0,
+            nt: 0.0
+        };
+        model.tt = (t0 - lapse_rate * (model.rt - r0)).clamp(100.0, 320.0);
+        model.nt = model.troposphere(model.rt).0;
+
+        model
+    }
+}
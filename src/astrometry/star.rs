@@ -0,0 +1,413 @@
+use std::f64::consts::FRAC_PI_2;
+
+use crate::base::Real;
+use crate::base::Result;
+use crate::base::angle::Angle;
+use crate::base::consts::{ARCS, AU, C_LIGHT, DAY, JULIAN_YEAR, PI2};
+use crate::base::error::Error;
+use crate::base::linalg::{CartesianVec3D, Mat3D, Norm, SphericalVec3D, Vec3D};
+use crate::ephemeris::{Body, Ephemeris};
+use crate::frames::nutation::nutation_matrix;
+use crate::frames::precession::{frame_bias_matrix, precession_matrix};
+use crate::frames::sidereal::gast;
+use crate::observer::Observer;
+use crate::time::JulianDate;
+
+use super::aberration::{annual_velocity, classical, diurnal_velocity,
+                        relativistic};
+use super::deflection::light_deflection;
+use super::refraction::Atmosphere;
+
+///
+/// Milliarcsecond [rad]
+///
+const MAS: f64 = 1e-3 / ARCS;
+
+///
+/// Parallax assumed for the stars without a parallax [mas]
+///
+const MIN_PARALLAX: f64 = 1e-4;
+
+///
+/// Accuracy and maximum number of the iterations of the refraction for the
+/// true altitude [rad]
+///
+const REFRACTION_ACCURACY: f64 = 1e-10;
+const REFRACTION_ITERATIONS: usize = 10;
+
+///
+/// Star: Catalogue place of a star referred to the ICRS at the epoch (TDB,
+/// J2000 by default): the right ascension and the declination, the proper
+/// motions in the right ascension (multiplied by cos(declination)) and in
+/// the declination [mas/yr], the parallax [mas] and the radial velocity
+/// [km/s]
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Star {
+    right_ascension: Angle,
+    declination: Angle,
+    pm_ra: f64,
+    pm_dec: f64,
+    parallax: f64,
+    radial_velocity: f64,
+    epoch: JulianDate
+}
+
+impl Star {
+    pub fn new(right_ascension: Angle, declination: Angle) -> Star {
+        Star {
+            right_ascension,
+            declination,
+            pm_ra: 0.0,
+            pm_dec: 0.0,
+            parallax: 0.0,
+            radial_velocity: 0.0,
+            epoch: JulianDate::j2000()
+        }
+    }
+
+    ///
+    /// from_state: Star from its barycentric position [AU] and velocity
+    /// [AU/d] referred to the ICRS at the epoch
+    ///
+    pub fn from_state(r: Vec3D, v: Vec3D, epoch: JulianDate) -> Result<Star> {
+        let distance = r.norm();
+        if distance == 0.0 {
+            return Err(Error::ZeroDivisionError);
+        }
+
+        let s: SphericalVec3D = r.into();
+        let (sa, ca) = s.phi().sin_cos();
+        let (sd, cd) = s.theta().sin_cos();
+        let east = Vec3D::cartesian(-sa, ca, 0.0);
+        let north = Vec3D::cartesian(-sd * ca, -sd * sa, cd);
+        let u = (r / distance)?;
+
+        // Angular velocities [rad/d] to the proper motions [mas/yr]
+        let scale = JULIAN_YEAR / (distance * MAS);
+
+        Ok(Star {
+            right_ascension: Angle::from(s.phi().fmod(PI2)),
+            declination: Angle::from(s.theta()),
+            pm_ra: v.dot(east) * scale,
+            pm_dec: v.dot(north) * scale,
+            parallax: 1.0 / (distance * MAS),
+            radial_velocity: v.dot(u) * AU / DAY,
+            epoch
+        })
+    }
+
+    pub fn with_epoch(mut self, epoch: JulianDate) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    pub fn with_proper_motion(mut self, pm_ra: f64, pm_dec: f64) -> Self {
+        self.pm_ra = pm_ra;
+        self.pm_dec = pm_dec;
+        self
+    }
+
+    pub fn with_parallax(mut self, parallax: f64) -> Self {
+        self.parallax = parallax;
+        self
+    }
+
+    pub fn with_radial_velocity(mut self, radial_velocity: f64) -> Self {
+        self.radial_velocity = radial_velocity;
+        self
+    }
+
+    pub fn right_ascension(&self) -> Angle {
+        self.right_ascension
+    }
+
+    pub fn declination(&self) -> Angle {
+        self.declination
+    }
+
+    pub fn pm_ra(&self) -> f64 {
+        self.pm_ra
+    }
+
+    pub fn pm_dec(&self) -> f64 {
+        self.pm_dec
+    }
+
+    pub fn parallax(&self) -> f64 {
+        self.parallax
+    }
+
+    pub fn radial_velocity(&self) -> f64 {
+        self.radial_velocity
+    }
+
+    pub fn epoch(&self) -> JulianDate {
+        self.epoch
+    }
+
+    ///
+    /// direction: Unit vector of the catalogue place
+    ///
+    pub fn direction(&self) -> Vec3D {
+        let alpha: f64 = self.right_ascension.into();
+        let delta: f64 = self.declination.into();
+
+        Vec3D::unit(alpha, delta).unwrap_or_default()
+    }
+
+    ///
+    /// state: Barycentric position [AU] and velocity [AU/d] of the star
+    /// referred to the ICRS at the epoch. The stars without a parallax are
+    /// placed at a large distance.
+    ///
+    pub fn state(&self) -> (Vec3D, Vec3D) {
+        let alpha: f64 = self.right_ascension.into();
+        let delta: f64 = self.declination.into();
+        let (sa, ca) = alpha.sin_cos();
+        let (sd, cd) = delta.sin_cos();
+
+        let distance = 1.0 / (self.parallax.max(MIN_PARALLAX) * MAS);
+        let (mu_a, mu_d) = (
+            self.pm_ra * MAS / JULIAN_YEAR,
+            self.pm_dec * MAS / JULIAN_YEAR
+        );
+
+        let u = self.direction();
+        let transverse = Vec3D::cartesian(
+            -mu_a * sa - mu_d * sd * ca,
+            mu_a * ca - mu_d * sd * sa,
+            mu_d * cd
+        ) * distance;
+
+        (
+            u * distance,
+            transverse + u * (self.radial_velocity * DAY / AU)
+        )
+    }
+
+    ///
+    /// propagate: Star at the epoch by the uniform rectilinear space motion
+    /// including the perspective acceleration and the change of the
+    /// parallax. The catalogue places refer to the light arriving at the
+    /// epochs, so the light times at both epochs are accounted for when the
+    /// parallax is known.
+    ///
+    pub fn propagate(&self, epoch: JulianDate) -> Result<Star> {
+        let (r, v) = self.state();
+        let dt = epoch - self.epoch;
+
+        let mut star = if self.parallax > 0.0 {
+            // Light time at the first epoch and the place at the emission
+            let tl1 = r.norm() / C_LIGHT;
+            let r1 = r + v * (dt + tl1);
+
+            // Light time at the second epoch
+            let rdv = r1.dot(v);
+            let c2mv2 = C_LIGHT * C_LIGHT - v.dot(v);
+            if c2mv2 <= 0.0 {
+                return Err(Error::ConvergenceError);
+            }
+            let tl2 = (-rdv + (rdv * rdv + c2mv2 * r1.dot(r1)).sqrt()) / c2mv2;
+
+            Star::from_state(r + v * (dt + tl1 - tl2), v, epoch)?
+        } else {
+            Star::from_state(r + v * dt, v, epoch)?
+        };
+
+        if self.parallax <= 0.0 {
+            star.parallax = self.parallax;
+            star.radial_velocity = self.radial_velocity;
+        }
+
+        Ok(star)
+    }
+
+    ///
+    /// astrometric: Direction of the star seen from the barycentric position
+    /// of the observer [AU] at the TDB date corrected for the space motion
+    /// and for the parallax referred to the ICRS
+    ///
+    pub fn astrometric(&self, observer: Vec3D,
+                       jd: JulianDate) -> Result<Vec3D> {
+        let (r, _) = self.propagate(jd)?.state();
+        let p = r - observer;
+
+        p / p.norm()
+    }
+}
+
+///
+/// Places: Intermediate places of a star: the astrometric place (ICRS), the
+/// geocentric apparent place and the topocentric place (true equator and
+/// equinox of the date) as unit vectors and the observed azimuth (from the
+/// north towards the east) and altitude
+///
+#[derive(Debug, Copy, Clone)]
+pub struct Places {
+    astrometric: Vec3D,
+    apparent: Vec3D,
+    topocentric: Vec3D,
+    azimuth: Angle,
+    altitude: Angle
+}
+
+impl Places {
+    pub fn astrometric(&self) -> Vec3D {
+        self.astrometric
+    }
+
+    pub fn apparent(&self) -> Vec3D {
+        self.apparent
+    }
+
+    pub fn topocentric(&self) -> Vec3D {
+        self.topocentric
+    }
+
+    pub fn azimuth(&self) -> Angle {
+        self.azimuth
+    }
+
+    pub fn altitude(&self) -> Angle {
+        self.altitude
+    }
+}
+
+///
+/// Reduction: Reduction of the catalogue places to the observed places for
+/// an observer at the TT date. The polar motion is zero, UT1 follows from
+/// TT - UT of the time module and the refraction is neglected unless they
+/// are given.
+///
+pub struct Reduction<'a, E> where E: Ephemeris + ?Sized {
+    ephemeris: &'a E,
+    observer: Observer,
+    jd: JulianDate,
+    ut1: Option<JulianDate>,
+    polar_motion: Mat3D,
+    atmosphere: Option<Atmosphere>
+}
+
+impl<'a, E> Reduction<'a, E> where E: Ephemeris + ?Sized {
+    pub fn new(ephemeris: &'a E, observer: Observer,
+               jd: JulianDate) -> Reduction<'a, E> {
+        Reduction {
+            ephemeris,
+            observer,
+            jd,
+            ut1: None,
+            polar_motion: Mat3D::identity(),
+            atmosphere: None
+        }
+    }
+
+    ///
+    /// with_polar_motion: Sets the rotation from the terrestrial frame of
+    /// the date to the ITRS
+    ///
+    pub fn with_polar_motion(mut self, polar_motion: Mat3D) -> Self {
+        self.polar_motion = polar_motion;
+        self
+    }
+
+    ///
+    /// with_ut1: Sets UT1 of the date, e.g. from the Earth orientation
+    /// parameters
+    ///
+    pub fn with_ut1(mut self, ut1: JulianDate) -> Self {
+        self.ut1 = Some(ut1);
+        self
+    }
+
+    pub fn with_atmosphere(mut self, atmosphere: Atmosphere) -> Self {
+        self.atmosphere = Some(atmosphere);
+        self
+    }
+
+    ///
+    /// places: Applies the space motion, the parallax, the light deflection,
+    /// the annual aberration, the precession and the nutation, the diurnal
+    /// aberration, the rotation of the Earth, the polar motion and the
+    /// refraction in this order
+    ///
+    pub fn places(&self, star: &Star) -> Result<Places> {
+        let jd = self.jd;
+        let earth = self.ephemeris.position(Body::Earth, jd)?;
+
+        let astrometric = star.astrometric(earth, jd)?;
+        let deflected = light_deflection(
+            self.ephemeris, earth, astrometric, None, &[], jd
+        )?;
+        let aberrated = relativistic(
+            deflected, annual_velocity(self.ephemeris, jd)?
+        )?;
+        let apparent = nutation_matrix(jd) * precession_matrix(jd) *
+            frame_bias_matrix() * aberrated;
+        let topocentric = classical(
+            apparent, diurnal_velocity(&self.observer, jd)
+        )?;
+
+        let ut1 = self.ut1.unwrap_or_else(|| jd.tt_to_ut());
+        let terrestrial = self.polar_motion * Mat3D::r_z(gast(ut1)) *
+            topocentric;
+        let (azimuth, altitude) = self.horizontal(terrestrial);
+        let altitude = match self.atmosphere {
+            Some(ref atmosphere) => refract(atmosphere, altitude),
+            None => altitude
+        };
+
+        Ok(Places {
+            astrometric,
+            apparent,
+            topocentric,
+            azimuth: Angle::from(azimuth),
+            altitude: Angle::from(altitude)
+        })
+    }
+
+    ///
+    /// observed: Observed azimuth and altitude of the star
+    ///
+    pub fn observed(&self, star: &Star) -> Result<(Angle, Angle)> {
+        let places = self.places(star)?;
+        Ok((places.azimuth, places.altitude))
+    }
+
+    ///
+    /// Azimuth and altitude of the direction in the Earth-fixed frame [rad]
+    ///
+    fn horizontal(&self, r: Vec3D) -> (f64, f64) {
+        let longitude: f64 = self.observer.longitude().into();
+        let latitude: f64 = self.observer.latitude().into();
+
+        let h: CartesianVec3D = (Mat3D::r_y(FRAC_PI_2 - latitude) *
+            Mat3D::r_z(longitude) * r).into();
+
+        (
+            h.y().atan2(-h.x()).fmod(PI2),
+            h.z().atan2(h.x().hypot(h.y()))
+        )
+    }
+}
+
+///
+/// Observed altitude for the true altitude by the iteration of the
+/// ray-traced refraction [rad]
+///
+fn refract(atmosphere: &Atmosphere, altitude: f64) -> f64 {
+    let mut observed = altitude;
+
+    for _ in 0..REFRACTION_ITERATIONS {
+        let r: f64 = atmosphere.ray_traced(Angle::from(observed)).into();
+        let next = altitude + r;
+        let done = (next - observed).abs() < REFRACTION_ACCURACY;
+        observed = next;
+
+        if done {
+            break;
+        }
+    }
+
+    observed
+}
@@ -1,7 +1,10 @@
 pub mod angle;
 pub mod consts;
 pub mod linalg;
+pub mod matrix;
+pub mod quaternion;
 pub mod error;
+pub mod roots;
 
 use std::convert::From;
 use std::default::Default;
@@ -12,7 +15,7 @@ use num_traits::float::Float;
 use crate::base::error::Error;
 
 
-type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = std::result::Result<T, Error>;
 
 
 pub trait Real<T = Self> where T: Float
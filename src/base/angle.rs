@@ -2,7 +2,8 @@ use std::cmp::Ordering;
 use std::convert;
 use std::f64::consts::PI;
 
-use crate::base::consts::{ARCS, DEG, PI2, RAD};
+use crate::base::Real;
+use crate::base::consts::{ARCS, AU, DEG, PI2, RAD};
 
 const ARCM: f64 = 60.0 * 180.0 / PI;
 
@@ -1649,6 +1650,21 @@ impl Angle {
     }
 }
 
+///
+/// wrap: Reduces the angle [rad] to the interval [-pi, pi)
+///
+pub fn wrap(angle: f64) -> f64 {
+    (angle + PI).fmod(PI2) - PI
+}
+
+///
+/// angular_radius: Angular radius of a sphere with the radius [km] at the
+/// distance [AU] of its centre [rad]
+///
+pub fn angular_radius(radius: f64, distance: f64) -> f64 {
+    (radius / (distance * AU)).asin()
+}
+
 
 #[cfg(test)]
 mod tests {
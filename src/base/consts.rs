@@ -4,3 +4,76 @@ pub const PI2: f64 = 2.0 * PI;
 pub const RAD: f64 = PI / 180.0;
 pub const DEG: f64 = 180.0 / PI;
 pub const ARCS: f64 = 3600.0 * 180.0 / PI;
+
+///
+/// Julian date of the standard epoch J2000
+///
+pub const JD_J2000: f64 = 2451545.0;
+
+///
+/// Difference between Julian date and modified Julian date
+///
+pub const MJD_OFFSET: f64 = 2400000.5;
+
+///
+/// Days per Julian century
+///
+pub const JULIAN_CENTURY: f64 = 36525.0;
+
+///
+/// Days per Julian year
+///
+pub const JULIAN_YEAR: f64 = 365.25;
+
+///
+/// Julian date of the Besselian epoch B1900 and the days per tropical year
+/// of the Besselian epochs (Lieske 1979)
+///
+pub const JD_B1900: f64 = 2415020.31352;
+pub const TROPICAL_YEAR: f64 = 365.242198781;
+
+///
+/// Astronomical unit [km]
+///
+pub const AU: f64 = 149597870.7;
+
+///
+/// Speed of light [AU/d]
+///
+pub const C_LIGHT: f64 = 173.1446326846693;
+
+///
+/// Equatorial radius of the Earth [km]
+///
+pub const R_EARTH: f64 = 6378.137;
+
+///
+/// Heliocentric gravitational constant, the square of the Gaussian
+/// gravitational constant [AU^3/d^2]
+///
+pub const GM_SUN: f64 = 0.01720209895 * 0.01720209895;
+
+///
+/// Radius of the Sun [km]
+///
+pub const R_SUN: f64 = 696000.0;
+
+///
+/// Radius of the Moon [km]
+///
+pub const R_MOON: f64 = 1737.4;
+
+///
+/// Flattening of the Earth (WGS84)
+///
+pub const F_EARTH: f64 = 1.0 / 298.257223563;
+
+///
+/// Angular velocity of the rotation of the Earth [rad/s]
+///
+pub const OMEGA_EARTH: f64 = 7.292115e-5;
+
+///
+/// Length of the day [s]
+///
+pub const DAY: f64 = 86400.0;
@@ -1,4 +1,4 @@
-use std::{error, fmt};
+use std::{error, fmt, io};
 
 #[derive(Debug, Clone)]
 pub struct AttributeInfo<T> {
@@ -12,6 +12,17 @@ pub enum Error {
     ConversionError,
     SingularMatrixError,
     CannotCreateVec3DError(AttributeInfo<f64>),
+    NotBracketedError,
+    ConvergenceError,
+    InvalidBodyError,
+    IoError(io::Error),
+    ParseError(usize),
+    OutOfRangeError,
+    DimensionMismatchError,
+    NotPositiveDefiniteError,
+    TagMismatchError,
+    IllConditionedMatrixError(f64),
+    InvalidArgumentError(&'static str),
 }
 
 impl error::Error for Error {}
@@ -36,10 +47,53 @@ impl fmt::Display for Error {
                     err.value
                 )
             }
+            Error::NotBracketedError => {
+                write!(f, "The root is not bracketed by the interval")
+            }
+            Error::ConvergenceError => {
+                write!(f, "The iterative method did not converge")
+            }
+            Error::InvalidBodyError => {
+                write!(f, "The body is not supported by the operation")
+            }
+            Error::IoError(ref err) => {
+                write!(f, "Cannot read the data: {}", err)
+            }
+            Error::ParseError(line) => {
+                write!(f, "Cannot parse the data at the line {}", line)
+            }
+            Error::OutOfRangeError => {
+                write!(f, "The date is outside the range of the data")
+            }
+            Error::DimensionMismatchError => {
+                write!(f, "The dimensions of the matrices do not match")
+            }
+            Error::NotPositiveDefiniteError => {
+                write!(f, "The matrix is not positive definite")
+            }
+            Error::TagMismatchError => {
+                write!(f, "The epochs or the frames of the values differ")
+            }
+            Error::IllConditionedMatrixError(condition) => {
+                write!(
+                    f,
+                    "The matrix is ill-conditioned (condition number {:e})",
+                    condition
+                )
+            }
+            Error::InvalidArgumentError(argument) => {
+                write!(f, "The value of the argument {} is invalid", argument)
+            }
         }
     }
 }
 
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::IoError(err)
+    }
+}
+
 impl Error {
     pub fn new_attribute_info<T>(attribute: &'static str,
                                  value: T) -> AttributeInfo<T> {
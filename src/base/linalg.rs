@@ -6,6 +6,7 @@ use std::ops;
 use std::ops::{Add, Mul, Sub};
 
 use crate::base::{Real, Result};
+use crate::base::angle::Angle;
 use crate::base::consts::PI2;
 use crate::base::error::Error;
 
@@ -414,8 +415,7 @@ impl ops::Div<Mat3D> for Vec3D {
     type Output = Result<Vec3D>;
 
     fn div(self, rhs: Mat3D) -> Self::Output {
-        let inverted = rhs.inv()?;
-        Ok(inverted.mul(self))
+        Ok(rhs.solve(self)?.0)
     }
 }
 
@@ -573,6 +573,13 @@ impl Vec3D {
             lhs.x * rhs.y - lhs.y * rhs.x
         )
     }
+
+    ///
+    /// separation: Angle between the directions of the vectors [rad]
+    ///
+    pub fn separation(self, rhs: Self) -> f64 {
+        self.cross(rhs).norm().atan2(self.dot(rhs))
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -878,7 +885,19 @@ impl Mat3D {
                 self.0[1][1] * self.0[2][0])
     }
 
+    ///
+    /// inv: Inverse of the matrix, which fails if the condition number
+    /// exceeds MAX_CONDITION
+    ///
     pub fn inv(&self) -> Result<Mat3D> {
+        self.inv_with_threshold(MAX_CONDITION)
+    }
+
+    ///
+    /// inv_with_threshold: Inverse of the matrix, which fails if the
+    /// condition number exceeds max_condition
+    ///
+    pub fn inv_with_threshold(&self, max_condition: f64) -> Result<Mat3D> {
         let det = self.det();
         if det == 0.0 {
             return Err(Error::SingularMatrixError);
@@ -888,27 +907,28 @@ impl Mat3D {
             self.0[r1][c1] * self.0[r2][c2] - self.0[r1][c2] * self.0[r2][c1]
         };
 
-        Ok(
-            Mat3D(
+        let inverse = Mat3D(
+            [
                 [
-                    [
-                         d2(1, 1, 2, 2) / det,
-                        -d2(0, 1, 2, 2) / det,
-                         d2(0, 1, 1, 2) / det
-                    ],
-                    [
-                        -d2(1, 0, 2, 2) / det,
-                         d2(0, 0, 2, 2) / det,
-                        -d2(0, 0, 1, 2) / det
-                    ],
-                    [
-                         d2(1, 0, 2, 1) / det,
-                        -d2(0, 0, 2, 1) / det,
-                         d2(0, 0, 1, 1) / det
-                    ],
-                ]
-            )
-        )
+                     d2(1, 1, 2, 2) / det,
+                    -d2(0, 1, 2, 2) / det,
+                     d2(0, 1, 1, 2) / det
+                ],
+                [
+                    -d2(1, 0, 2, 2) / det,
+                     d2(0, 0, 2, 2) / det,
+                    -d2(0, 0, 1, 2) / det
+                ],
+                [
+                     d2(1, 0, 2, 1) / det,
+                    -d2(0, 0, 2, 1) / det,
+                     d2(0, 0, 1, 1) / det
+                ],
+            ]
+        );
+        self.check_condition(&inverse, max_condition)?;
+
+        Ok(inverse)
     }
 
     pub fn iter(&self) -> Mat3DIterator {
@@ -919,6 +939,559 @@ impl Mat3D {
     }
 }
 
+///
+/// Threshold of the sine or the cosine of the middle Euler angle below which
+/// a rotation is taken as gimbal locked
+///
+const GIMBAL_LOCK_EPS: f64 = 1e-12;
+
+///
+/// EulerSequence: Axes of the three successive elementary rotations, the
+/// six proper Euler sequences (the first and the last axes are the same) and
+/// the six Tait-Bryan sequences
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EulerSequence {
+    XYX,
+    XZX,
+    YXY,
+    YZY,
+    ZXZ,
+    ZYZ,
+    XYZ,
+    XZY,
+    YXZ,
+    YZX,
+    ZXY,
+    ZYX
+}
+
+impl EulerSequence {
+    pub const ALL: [EulerSequence; 12] = [
+        EulerSequence::XYX,
+        EulerSequence::XZX,
+        EulerSequence::YXY,
+        EulerSequence::YZY,
+        EulerSequence::ZXZ,
+        EulerSequence::ZYZ,
+        EulerSequence::XYZ,
+        EulerSequence::XZY,
+        EulerSequence::YXZ,
+        EulerSequence::YZX,
+        EulerSequence::ZXY,
+        EulerSequence::ZYX
+    ];
+
+    ///
+    /// axes: Indices of the axes of the rotations in the order of application
+    ///
+    pub fn axes(&self) -> (usize, usize, usize) {
+        match *self {
+            EulerSequence::XYX => (0, 1, 0),
+            EulerSequence::XZX => (0, 2, 0),
+            EulerSequence::YXY => (1, 0, 1),
+            EulerSequence::YZY => (1, 2, 1),
+            EulerSequence::ZXZ => (2, 0, 2),
+            EulerSequence::ZYZ => (2, 1, 2),
+            EulerSequence::XYZ => (0, 1, 2),
+            EulerSequence::XZY => (0, 2, 1),
+            EulerSequence::YXZ => (1, 0, 2),
+            EulerSequence::YZX => (1, 2, 0),
+            EulerSequence::ZXY => (2, 0, 1),
+            EulerSequence::ZYX => (2, 1, 0)
+        }
+    }
+
+    ///
+    /// is_proper: Whether the first and the last axes are the same
+    ///
+    pub fn is_proper(&self) -> bool {
+        let (i, _, k) = self.axes();
+        i == k
+    }
+}
+
+impl Mat3D {
+    ///
+    /// r_axis: Elementary rotation about the axis of the index
+    ///
+    fn r_axis(axis: usize, angle: f64) -> Mat3D {
+        match axis {
+            0 => Mat3D::r_x(angle),
+            1 => Mat3D::r_y(angle),
+            _ => Mat3D::r_z(angle)
+        }
+    }
+
+    ///
+    /// from_axis_angle: Rotation by the angle about the axis (Rodrigues'
+    /// formula) in the convention of r_x, r_y and r_z
+    ///
+    pub fn from_axis_angle(axis: Vec3D, angle: Angle) -> Result<Mat3D> {
+        let n: CartesianVec3D = (axis / axis.norm())?.into();
+        let angle: f64 = angle.into();
+        let (s, c) = angle.sin_cos();
+        let n = [n.x, n.y, n.z];
+        let mut result = Mat3D::zeros();
+
+        for i in 0..3 {
+            for j in 0..3 {
+                result.0[i][j] = (1.0 - c) * n[i] * n[j];
+            }
+            result.0[i][i] += c;
+        }
+
+        result.0[0][1] += s * n[2];
+        result.0[1][0] -= s * n[2];
+        result.0[2][0] += s * n[1];
+        result.0[0][2] -= s * n[1];
+        result.0[1][2] += s * n[0];
+        result.0[2][1] -= s * n[0];
+
+        Ok(result)
+    }
+
+    ///
+    /// axis_angle: Axis and angle in [0, pi] of the rotation matrix in the
+    /// convention of from_axis_angle. The axis is z for the identity.
+    ///
+    pub fn axis_angle(&self) -> (Vec3D, Angle) {
+        let m = &self.0;
+        let v = [m[1][2] - m[2][1], m[2][0] - m[0][2], m[0][1] - m[1][0]];
+        let s = v[0].hypot(v[1]).hypot(v[2]) / 2.0;
+        let c = (self.tr() - 1.0) / 2.0;
+        let angle = s.atan2(c);
+
+        if s == 0.0 && c > 0.0 {
+            return (Vec3D::unit_z(), Angle::from(0.0));
+        }
+
+        let n = if c < 0.0 {
+            // Near pi the axis follows from the symmetric part, the sign
+            // from the antisymmetric one
+            let k = (0..3).fold(0, |k, i| {
+                if m[i][i] > m[k][k] { i } else { k }
+            });
+            let mut n = [0.0; 3];
+            for (i, value) in n.iter_mut().enumerate() {
+                *value = (m[i][k] + m[k][i]) / 2.0;
+            }
+            n[k] -= c;
+            let sign = if v[k] < 0.0 { -1.0 } else { 1.0 };
+            let norm = n[0].hypot(n[1]).hypot(n[2]);
+            [sign * n[0] / norm, sign * n[1] / norm, sign * n[2] / norm]
+        } else {
+            let norm = 2.0 * s;
+            [v[0] / norm, v[1] / norm, v[2] / norm]
+        };
+
+        (Vec3D::cartesian(n[0], n[1], n[2]), Angle::from(angle))
+    }
+
+    ///
+    /// from_euler: Product of the elementary rotations r_i(a1), r_j(a2) and
+    /// r_k(a3) of the sequence ijk, the first rotation is applied first
+    ///
+    pub fn from_euler(sequence: EulerSequence, a1: Angle, a2: Angle,
+                      a3: Angle) -> Mat3D {
+        let (i, j, k) = sequence.axes();
+
+        Mat3D::r_axis(k, a3.into()) * Mat3D::r_axis(j, a2.into()) *
+            Mat3D::r_axis(i, a1.into())
+    }
+
+    ///
+    /// euler_angles: Angles of the rotation matrix in the sequence, the
+    /// inverse of from_euler. The middle angle is in [0, pi] for the proper
+    /// sequences and in [-pi/2, pi/2] for the Tait-Bryan sequences, the
+    /// other angles are in (-pi, pi]. In the gimbal lock the last angle is
+    /// zero.
+    ///
+    pub fn euler_angles(&self, sequence: EulerSequence) -> (Angle, Angle,
+                                                               Angle) {
+        let (i, j, _) = sequence.axes();
+        let k = 3 - i - j;
+        let r = self.t().0;
+
+        // Parity of the permutation ijk
+        let e = if (j + 3 - i) % 3 == 1 { 1.0 } else { -1.0 };
+
+        let (a1, a2, a3, lock) = if sequence.is_proper() {
+            let s = r[i][j].hypot(r[i][k]);
+            let a2 = s.atan2(r[i][i]);
+            (r[j][i].atan2(-e * r[k][i]), a2, r[i][j].atan2(e * r[i][k]),
+             s < GIMBAL_LOCK_EPS)
+        } else {
+            let c = r[i][i].hypot(r[i][j]);
+            let a2 = (e * r[i][k]).atan2(c);
+            ((-e * r[j][k]).atan2(r[k][k]), a2, (-e * r[i][j]).atan2(r[i][i]),
+             c < GIMBAL_LOCK_EPS)
+        };
+
+        if lock {
+            let a1 = (e * r[k][j]).atan2(r[j][j]);
+            (Angle::from(a1), Angle::from(a2), Angle::from(0.0))
+        } else {
+            (Angle::from(a1), Angle::from(a2), Angle::from(a3))
+        }
+    }
+}
+
+///
+/// Maximal number of the sweeps of the Jacobi methods
+///
+const MAX_SWEEPS: usize = 50;
+
+///
+/// Relative accuracy of the Jacobi methods
+///
+const JACOBI_EPS: f64 = 1e-15;
+
+impl Mat3D {
+    ///
+    /// Rotation in the plane of the axes p and q which annihilates the
+    /// element pq of the symmetric matrix with the diagonal elements pp, qq
+    ///
+    fn jacobi_rotation(p: usize, q: usize, pp: f64, qq: f64,
+                       pq: f64) -> Mat3D {
+        let theta = (qq - pp) / (2.0 * pq);
+        let t = theta.signum() / (theta.abs() + theta.hypot(1.0));
+        let c = 1.0 / t.hypot(1.0);
+        let mut result = Mat3D::identity();
+
+        result.0[p][p] = c;
+        result.0[q][q] = c;
+        result.0[p][q] = t * c;
+        result.0[q][p] = -t * c;
+
+        result
+    }
+
+    ///
+    /// Columns of the matrix permuted by the order of the values, which are
+    /// sorted in descending order
+    ///
+    fn sort_columns(&self, values: [f64; 3]) -> ([f64; 3], Mat3D) {
+        let mut order = [0, 1, 2];
+        order.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+        let mut columns = Mat3D::zeros();
+
+        for (j, &k) in order.iter().enumerate() {
+            for i in 0..3 {
+                columns.0[i][j] = self.0[i][k];
+            }
+        }
+
+        ([values[order[0]], values[order[1]], values[order[2]]], columns)
+    }
+
+    fn column(&self, j: usize) -> Vec3D {
+        Vec3D::cartesian(self.0[0][j], self.0[1][j], self.0[2][j])
+    }
+
+    ///
+    /// symmetric_eigen: Eigenvalues in descending order and the eigenvectors
+    /// as the columns of a rotation matrix of the symmetric part of the
+    /// matrix by the cyclic Jacobi method
+    ///
+    pub fn symmetric_eigen(&self) -> Result<([f64; 3], Mat3D)> {
+        let mut a = (*self + self.t()) * 0.5;
+        let mut v = Mat3D::identity();
+        let scale = a.norm();
+
+        for _ in 0..MAX_SWEEPS {
+            let off = a.0[0][1].hypot(a.0[0][2]).hypot(a.0[1][2]);
+            if off <= JACOBI_EPS * scale {
+                let values = [a.0[0][0], a.0[1][1], a.0[2][2]];
+                let (values, mut vectors) = v.sort_columns(values);
+                if vectors.det() < 0.0 {
+                    for i in 0..3 {
+                        vectors.0[i][2] = -vectors.0[i][2];
+                    }
+                }
+                return Ok((values, vectors));
+            }
+
+            for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+                if a.0[p][q] != 0.0 {
+                    let j = Mat3D::jacobi_rotation(
+                        p, q, a.0[p][p], a.0[q][q], a.0[p][q]
+                    );
+                    a = j.t() * a * j;
+                    v *= j;
+                }
+            }
+        }
+
+        Err(Error::ConvergenceError)
+    }
+
+    ///
+    /// svd: Singular value decomposition A = U diag(s) V^T by the one-sided
+    /// Jacobi method with the singular values in descending order and the
+    /// orthogonal matrices U and V
+    ///
+    pub fn svd(&self) -> Result<(Mat3D, [f64; 3], Mat3D)> {
+        let mut u = *self;
+        let mut v = Mat3D::identity();
+
+        let mut converged = false;
+        for _ in 0..MAX_SWEEPS {
+            converged = true;
+
+            for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+                let (up, uq) = (u.column(p), u.column(q));
+                let (alpha, beta, gamma) = (up.dot(up), uq.dot(uq), up.dot(uq));
+                if gamma.abs() <= JACOBI_EPS * (alpha * beta).sqrt() {
+                    continue;
+                }
+
+                converged = false;
+                let j = Mat3D::jacobi_rotation(p, q, alpha, beta, gamma);
+                u *= j;
+                v *= j;
+            }
+
+            if converged {
+                break;
+            }
+        }
+        if !converged {
+            return Err(Error::ConvergenceError);
+        }
+
+        let norms = [0, 1, 2].map(|j| u.column(j).norm());
+        let (s, u) = u.sort_columns(norms);
+        let (_, v) = v.sort_columns(norms);
+
+        // Orthonormal columns of U, completed for the null singular values
+        let tolerance = JACOBI_EPS * 8.0 * s[0];
+        let mut columns = [Vec3D::zero(); 3];
+        for j in 0..3 {
+            columns[j] = if s[j] > tolerance {
+                (u.column(j) / s[j])?
+            } else if j == 0 {
+                Vec3D::unit_x()
+            } else if j == 1 {
+                let c: CartesianVec3D = columns[0].into();
+                let other = if c.x.abs() < 0.9 {
+                    Vec3D::unit_x()
+                } else {
+                    Vec3D::unit_y()
+                };
+                let w = columns[0].cross(other);
+                (w / w.norm())?
+            } else {
+                columns[0].cross(columns[1])
+            };
+        }
+        let u = Mat3D::from_columns(columns[0], columns[1], columns[2]);
+        let s = s.map(|value| if value > tolerance { value } else { 0.0 });
+
+        Ok((u, s, v))
+    }
+
+    ///
+    /// polar: Polar decomposition A = R S with the orthogonal matrix R and
+    /// the symmetric positive semi-definite matrix S
+    ///
+    pub fn polar(&self) -> Result<(Mat3D, Mat3D)> {
+        let (u, s, v) = self.svd()?;
+        let diagonal = Mat3D::from_rows(
+            Vec3D::cartesian(s[0], 0.0, 0.0),
+            Vec3D::cartesian(0.0, s[1], 0.0),
+            Vec3D::cartesian(0.0, 0.0, s[2])
+        );
+
+        Ok((u * v.t(), v * diagonal * v.t()))
+    }
+
+    ///
+    /// orthonormalize: Rotation matrix nearest to the matrix in the
+    /// Frobenius norm, which cleans up the rounding errors accumulated by
+    /// long products of rotations
+    ///
+    pub fn orthonormalize(&self) -> Result<Mat3D> {
+        let (u, _, v) = self.svd()?;
+        let mut u = u;
+        if (u * v.t()).det() < 0.0 {
+            for i in 0..3 {
+                u.0[i][2] = -u.0[i][2];
+            }
+        }
+
+        Ok(u * v.t())
+    }
+
+    ///
+    /// kabsch: Rotation R minimizing the weighted sum of |R a - b|^2 over
+    /// the pairs of the vectors a and b (Kabsch's algorithm). The vectors
+    /// are not centred, so that the directions of two catalogue frames can
+    /// be fitted directly. The weights are one if they are not given and
+    /// must not be negative.
+    ///
+    pub fn kabsch(from: &[Vec3D], to: &[Vec3D],
+                  weights: Option<&[f64]>) -> Result<Mat3D> {
+        let n = from.len();
+        if n == 0 || to.len() != n || weights.is_some_and(|w| w.len() != n) {
+            return Err(Error::DimensionMismatchError);
+        }
+        if weights.is_some_and(|w| w.iter().any(|&w| w < 0.0)) {
+            return Err(Error::InvalidArgumentError("weights"));
+        }
+
+        let mut b = Mat3D::zeros();
+        for (i, (&a, &c)) in from.iter().zip(to.iter()).enumerate() {
+            let w = weights.map_or(1.0, |w| w[i]);
+            let a: CartesianVec3D = a.into();
+            let c: CartesianVec3D = c.into();
+            let (a, c) = ([a.x, a.y, a.z], [c.x, c.y, c.z]);
+
+            for (row, cj) in b.0.iter_mut().zip(c) {
+                for (value, ak) in row.iter_mut().zip(a) {
+                    *value += w * cj * ak;
+                }
+            }
+        }
+
+        b.orthonormalize()
+    }
+}
+
+///
+/// MAX_CONDITION: Default bound of the condition number of the solved and
+/// inverted matrices, about four significant digits of the solution are
+/// left at the bound
+///
+pub const MAX_CONDITION: f64 = 1e12;
+
+impl Mat3D {
+    ///
+    /// LU decomposition with partial pivoting: the unit lower and the upper
+    /// triangular factors packed in one matrix and the permutation of the
+    /// rows
+    ///
+    fn pivoted_lu(&self) -> Result<(Mat3D, [usize; 3])> {
+        let mut lu = *self;
+        let mut pivots = [0, 1, 2];
+
+        for k in 0..3 {
+            let p = (k..3).fold(k, |p, i| {
+                if lu.0[i][k].abs() > lu.0[p][k].abs() { i } else { p }
+            });
+            if lu.0[p][k] == 0.0 {
+                return Err(Error::SingularMatrixError);
+            }
+            lu.0.swap(k, p);
+            pivots.swap(k, p);
+
+            let pivot_row = lu.0[k];
+            for row in lu.0[(k + 1)..].iter_mut() {
+                row[k] /= pivot_row[k];
+                for j in (k + 1)..3 {
+                    row[j] -= row[k] * pivot_row[j];
+                }
+            }
+        }
+
+        Ok((lu, pivots))
+    }
+
+    ///
+    /// Solution of A x = b from the pivoted LU decomposition of A
+    ///
+    fn lu_solve(lu: &Mat3D, pivots: &[usize; 3], b: [f64; 3]) -> [f64; 3] {
+        let mut x = [b[pivots[0]], b[pivots[1]], b[pivots[2]]];
+
+        for i in 1..3 {
+            for j in 0..i {
+                x[i] -= lu.0[i][j] * x[j];
+            }
+        }
+
+        for i in (0..3).rev() {
+            for j in (i + 1)..3 {
+                x[i] -= lu.0[i][j] * x[j];
+            }
+            x[i] /= lu.0[i][i];
+        }
+
+        x
+    }
+
+    fn lu_inverse(lu: &Mat3D, pivots: &[usize; 3]) -> Mat3D {
+        Mat3D(
+            [
+                Mat3D::lu_solve(lu, pivots, [1.0, 0.0, 0.0]),
+                Mat3D::lu_solve(lu, pivots, [0.0, 1.0, 0.0]),
+                Mat3D::lu_solve(lu, pivots, [0.0, 0.0, 1.0])
+            ]
+        ).t()
+    }
+
+    ///
+    /// Maximal absolute column sum
+    ///
+    fn norm_1(&self) -> f64 {
+        (0..3)
+            .map(|j| self.0.iter().map(|row| row[j].abs()).sum::<f64>())
+            .fold(0.0, f64::max)
+    }
+
+    ///
+    /// Condition number of the matrix from its inverse, which fails above
+    /// max_condition
+    ///
+    fn check_condition(&self, inverse: &Mat3D,
+                       max_condition: f64) -> Result<f64> {
+        let condition = self.norm_1() * inverse.norm_1();
+        if condition.is_nan() || condition > max_condition {
+            return Err(Error::IllConditionedMatrixError(condition));
+        }
+
+        Ok(condition)
+    }
+
+    ///
+    /// condition: Condition number of the matrix in the 1-norm,
+    /// ||A|| ||A^-1||, infinite for the singular matrices
+    ///
+    pub fn condition(&self) -> f64 {
+        match self.pivoted_lu() {
+            Ok((lu, pivots)) => {
+                self.norm_1() * Mat3D::lu_inverse(&lu, &pivots).norm_1()
+            },
+            Err(_) => f64::INFINITY
+        }
+    }
+
+    ///
+    /// solve: Solution x of A x = b by the LU decomposition with partial
+    /// pivoting and the condition number of A, which fails if the
+    /// condition number exceeds MAX_CONDITION
+    ///
+    pub fn solve(&self, b: Vec3D) -> Result<(Vec3D, f64)> {
+        self.solve_with_threshold(b, MAX_CONDITION)
+    }
+
+    ///
+    /// solve_with_threshold: Solution x of A x = b and the condition number
+    /// of A, which fails if the condition number exceeds max_condition
+    ///
+    pub fn solve_with_threshold(&self, b: Vec3D,
+                                max_condition: f64) -> Result<(Vec3D, f64)> {
+        let (lu, pivots) = self.pivoted_lu()?;
+        let condition = self.check_condition(
+            &Mat3D::lu_inverse(&lu, &pivots), max_condition
+        )?;
+
+        let b: CartesianVec3D = b.into();
+        let x = Mat3D::lu_solve(&lu, &pivots, [b.x, b.y, b.z]);
+
+        Ok((Vec3D::cartesian(x[0], x[1], x[2]), condition))
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct Mat3DIterator<'a> {
     matrix: &'a Mat3D,
@@ -0,0 +1,712 @@
+use std::convert::TryFrom;
+use std::ops;
+
+use crate::base::Result;
+use crate::base::error::Error;
+use crate::base::linalg::{CartesianVec3D, Mat3D, Norm, Vec3D};
+
+///
+/// Matrix: Dense matrix of any size stored by rows
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>
+}
+
+impl Norm for Matrix {
+    fn norm(&self) -> f64 {
+        self.data.iter().map(|v| v * v).sum::<f64>().sqrt()
+    }
+}
+
+impl Matrix {
+    pub fn zeros(rows: usize, cols: usize) -> Matrix {
+        Matrix {
+            rows,
+            cols,
+            data: vec![0.0; rows * cols]
+        }
+    }
+
+    pub fn identity(n: usize) -> Matrix {
+        let mut result = Matrix::zeros(n, n);
+
+        for i in 0..n {
+            result[(i, i)] = 1.0;
+        }
+
+        result
+    }
+
+    ///
+    /// from_vec: Matrix from its elements stored by rows
+    ///
+    pub fn from_vec(rows: usize, cols: usize,
+                    data: Vec<f64>) -> Result<Matrix> {
+        if data.len() != rows * cols {
+            return Err(Error::DimensionMismatchError);
+        }
+
+        Ok(Matrix { rows, cols, data })
+    }
+
+    pub fn from_rows(rows: &[Vec<f64>]) -> Result<Matrix> {
+        let cols = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != cols) {
+            return Err(Error::DimensionMismatchError);
+        }
+
+        Matrix::from_vec(rows.len(), cols, rows.concat())
+    }
+
+    ///
+    /// column_vector: Matrix with a single column
+    ///
+    pub fn column_vector(values: &[f64]) -> Matrix {
+        Matrix {
+            rows: values.len(),
+            cols: 1,
+            data: values.to_vec()
+        }
+    }
+
+    ///
+    /// diagonal: Square matrix with the values on the diagonal
+    ///
+    pub fn diagonal(values: &[f64]) -> Matrix {
+        let mut result = Matrix::zeros(values.len(), values.len());
+
+        for (i, &value) in values.iter().enumerate() {
+            result[(i, i)] = value;
+        }
+
+        result
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn row(&self, i: usize) -> &[f64] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn column(&self, j: usize) -> Vec<f64> {
+        (0..self.rows).map(|i| self[(i, j)]).collect()
+    }
+
+    pub fn t(&self) -> Matrix {
+        let mut result = Matrix::zeros(self.cols, self.rows);
+
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                result[(j, i)] = self[(i, j)];
+            }
+        }
+
+        result
+    }
+
+    ///
+    /// mul_vec: Product of the matrix and the column vector
+    ///
+    pub fn mul_vec(&self, v: &[f64]) -> Result<Vec<f64>> {
+        if v.len() != self.cols {
+            return Err(Error::DimensionMismatchError);
+        }
+
+        Ok(
+            (0..self.rows)
+                .map(|i| self.row(i).iter().zip(v).map(|(a, b)| a * b).sum())
+                .collect()
+        )
+    }
+
+    pub fn lu(&self) -> Result<Lu> {
+        Lu::new(self)
+    }
+
+    pub fn qr(&self) -> Result<Qr> {
+        Qr::new(self)
+    }
+
+    pub fn cholesky(&self) -> Result<Cholesky> {
+        Cholesky::new(self)
+    }
+
+    ///
+    /// solve: Solution of the square system A x = b
+    ///
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>> {
+        self.lu()?.solve(b)
+    }
+
+    pub fn det(&self) -> Result<f64> {
+        match self.lu() {
+            Ok(lu) => Ok(lu.det()),
+            Err(Error::SingularMatrixError) => Ok(0.0),
+            Err(err) => Err(err)
+        }
+    }
+
+    pub fn inv(&self) -> Result<Matrix> {
+        self.lu()?.inv()
+    }
+
+    fn zip_with<F>(&self, rhs: &Matrix, f: F) -> Result<Matrix>
+        where F: Fn(f64, f64) -> f64
+    {
+        if self.rows != rhs.rows || self.cols != rhs.cols {
+            return Err(Error::DimensionMismatchError);
+        }
+
+        Ok(Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().zip(&rhs.data)
+                .map(|(&a, &b)| f(a, b))
+                .collect()
+        })
+    }
+}
+
+impl ops::Index<(usize, usize)> for Matrix {
+    type Output = f64;
+
+    fn index(&self, (i, j): (usize, usize)) -> &f64 {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl ops::IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f64 {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+impl ops::Neg for Matrix {
+    type Output = Self;
+
+    fn neg(self) -> Matrix {
+        self * -1.0
+    }
+}
+
+impl ops::Add for &Matrix {
+    type Output = Result<Matrix>;
+
+    fn add(self, rhs: &Matrix) -> Self::Output {
+        self.zip_with(rhs, |a, b| a + b)
+    }
+}
+
+impl ops::Sub for &Matrix {
+    type Output = Result<Matrix>;
+
+    fn sub(self, rhs: &Matrix) -> Self::Output {
+        self.zip_with(rhs, |a, b| a - b)
+    }
+}
+
+impl ops::Mul<f64> for Matrix {
+    type Output = Self;
+
+    fn mul(mut self, rhs: f64) -> Matrix {
+        self.data.iter_mut().for_each(|v| *v *= rhs);
+        self
+    }
+}
+
+impl ops::Mul for &Matrix {
+    type Output = Result<Matrix>;
+
+    fn mul(self, rhs: &Matrix) -> Self::Output {
+        if self.cols != rhs.rows {
+            return Err(Error::DimensionMismatchError);
+        }
+
+        let mut result = Matrix::zeros(self.rows, rhs.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self[(i, k)];
+                for j in 0..rhs.cols {
+                    result[(i, j)] += a * rhs[(k, j)];
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl From<Mat3D> for Matrix {
+    fn from(m: Mat3D) -> Self {
+        Matrix {
+            rows: 3,
+            cols: 3,
+            data: m.iter().collect()
+        }
+    }
+}
+
+///
+/// Column vector of the components
+///
+impl From<Vec3D> for Matrix {
+    fn from(v: Vec3D) -> Self {
+        let v: CartesianVec3D = v.into();
+        Matrix::column_vector(&[v.x(), v.y(), v.z()])
+    }
+}
+
+impl TryFrom<&Matrix> for Mat3D {
+    type Error = Error;
+
+    fn try_from(m: &Matrix) -> Result<Mat3D> {
+        if m.rows != 3 || m.cols != 3 {
+            return Err(Error::DimensionMismatchError);
+        }
+
+        let row = |i: usize| Vec3D::cartesian(m[(i, 0)], m[(i, 1)], m[(i, 2)]);
+        Ok(Mat3D::from_rows(row(0), row(1), row(2)))
+    }
+}
+
+///
+/// Vector of a matrix with a single column or row of three elements
+///
+impl TryFrom<&Matrix> for Vec3D {
+    type Error = Error;
+
+    fn try_from(m: &Matrix) -> Result<Vec3D> {
+        if m.data.len() != 3 || (m.rows != 1 && m.cols != 1) {
+            return Err(Error::DimensionMismatchError);
+        }
+
+        Ok(Vec3D::cartesian(m.data[0], m.data[1], m.data[2]))
+    }
+}
+
+///
+/// Lu: LU decomposition P A = L U of a square matrix with partial pivoting
+///
+#[derive(Debug, Clone)]
+pub struct Lu {
+    lu: Matrix,
+    pivots: Vec<usize>,
+    sign: f64
+}
+
+impl Lu {
+    fn new(a: &Matrix) -> Result<Lu> {
+        if !a.is_square() {
+            return Err(Error::DimensionMismatchError);
+        }
+
+        let n = a.rows;
+        let mut lu = a.clone();
+        let mut pivots = (0..n).collect::<Vec<_>>();
+        let mut sign = 1.0;
+
+        for k in 0..n {
+            let p = (k..n)
+                .max_by(|&i, &j| lu[(i, k)].abs().total_cmp(&lu[(j, k)].abs()))
+                .unwrap_or(k);
+            if lu[(p, k)] == 0.0 {
+                return Err(Error::SingularMatrixError);
+            }
+
+            if p != k {
+                for j in 0..n {
+                    lu.data.swap(p * n + j, k * n + j);
+                }
+                pivots.swap(p, k);
+                sign = -sign;
+            }
+
+            for i in k + 1..n {
+                let factor = lu[(i, k)] / lu[(k, k)];
+                lu[(i, k)] = factor;
+                for j in k + 1..n {
+                    lu[(i, j)] -= factor * lu[(k, j)];
+                }
+            }
+        }
+
+        Ok(Lu { lu, pivots, sign })
+    }
+
+    pub fn det(&self) -> f64 {
+        (0..self.lu.rows).fold(self.sign, |d, i| d * self.lu[(i, i)])
+    }
+
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>> {
+        let n = self.lu.rows;
+        if b.len() != n {
+            return Err(Error::DimensionMismatchError);
+        }
+
+        let mut x = self.pivots.iter().map(|&p| b[p]).collect::<Vec<_>>();
+        for i in 0..n {
+            for k in 0..i {
+                x[i] -= self.lu[(i, k)] * x[k];
+            }
+        }
+        for i in (0..n).rev() {
+            for k in i + 1..n {
+                x[i] -= self.lu[(i, k)] * x[k];
+            }
+            x[i] /= self.lu[(i, i)];
+        }
+
+        Ok(x)
+    }
+
+    pub fn inv(&self) -> Result<Matrix> {
+        let n = self.lu.rows;
+        let mut result = Matrix::zeros(n, n);
+
+        for j in 0..n {
+            let mut e = vec![0.0; n];
+            e[j] = 1.0;
+            for (i, value) in self.solve(&e)?.into_iter().enumerate() {
+                result[(i, j)] = value;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+///
+/// Qr: Householder QR decomposition A = Q R of a matrix with at least as
+/// many rows as columns
+///
+#[derive(Debug, Clone)]
+pub struct Qr {
+    qr: Matrix,
+    diagonal: Vec<f64>
+}
+
+impl Qr {
+    fn new(a: &Matrix) -> Result<Qr> {
+        let (m, n) = (a.rows, a.cols);
+        if m < n {
+            return Err(Error::DimensionMismatchError);
+        }
+
+        let mut qr = a.clone();
+        let mut diagonal = vec![0.0; n];
+
+        for k in 0..n {
+            let mut norm = (k..m).fold(0.0, |s: f64, i| s.hypot(qr[(i, k)]));
+            if norm != 0.0 {
+                if qr[(k, k)] < 0.0 {
+                    norm = -norm;
+                }
+                for i in k..m {
+                    qr[(i, k)] /= norm;
+                }
+                qr[(k, k)] += 1.0;
+
+                for j in k + 1..n {
+                    let s = (k..m).map(|i| qr[(i, k)] * qr[(i, j)])
+                        .sum::<f64>() / -qr[(k, k)];
+                    for i in k..m {
+                        let v = qr[(i, k)];
+                        qr[(i, j)] += s * v;
+                    }
+                }
+            }
+            diagonal[k] = -norm;
+        }
+
+        Ok(Qr { qr, diagonal })
+    }
+
+    ///
+    /// is_full_rank: Whether the columns are linearly independent to the
+    /// rounding errors
+    ///
+    pub fn is_full_rank(&self) -> bool {
+        let max = self.diagonal.iter().fold(0.0, |m: f64, d| m.max(d.abs()));
+        let tolerance = f64::EPSILON * max * self.qr.rows as f64;
+
+        max > 0.0 && self.diagonal.iter().all(|d| d.abs() > tolerance)
+    }
+
+    ///
+    /// r: Upper triangular factor [n x n]
+    ///
+    pub fn r(&self) -> Matrix {
+        let n = self.qr.cols;
+        let mut result = Matrix::zeros(n, n);
+
+        for i in 0..n {
+            result[(i, i)] = self.diagonal[i];
+            for j in i + 1..n {
+                result[(i, j)] = self.qr[(i, j)];
+            }
+        }
+
+        result
+    }
+
+    ///
+    /// q: Factor with the orthonormal columns [m x n]
+    ///
+    pub fn q(&self) -> Matrix {
+        let (m, n) = (self.qr.rows, self.qr.cols);
+        let mut result = Matrix::zeros(m, n);
+
+        for k in (0..n).rev() {
+            result[(k, k)] = 1.0;
+            for j in k..n {
+                if self.qr[(k, k)] != 0.0 {
+                    let s = (k..m).map(|i| self.qr[(i, k)] * result[(i, j)])
+                        .sum::<f64>() / -self.qr[(k, k)];
+                    for i in k..m {
+                        result[(i, j)] += s * self.qr[(i, k)];
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    ///
+    /// solve: Least squares solution minimizing |A x - b|
+    ///
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>> {
+        let (m, n) = (self.qr.rows, self.qr.cols);
+        if b.len() != m {
+            return Err(Error::DimensionMismatchError);
+        }
+        if !self.is_full_rank() {
+            return Err(Error::SingularMatrixError);
+        }
+
+        // Q^T b
+        let mut y = b.to_vec();
+        for k in 0..n {
+            let s = (k..m).map(|i| self.qr[(i, k)] * y[i])
+                .sum::<f64>() / -self.qr[(k, k)];
+            for (i, value) in y.iter_mut().enumerate().skip(k) {
+                *value += s * self.qr[(i, k)];
+            }
+        }
+
+        y.truncate(n);
+        for i in (0..n).rev() {
+            for k in i + 1..n {
+                y[i] -= self.qr[(i, k)] * y[k];
+            }
+            y[i] /= self.diagonal[i];
+        }
+
+        Ok(y)
+    }
+
+    ///
+    /// Inverse of R
+    ///
+    fn r_inv(&self) -> Result<Matrix> {
+        if !self.is_full_rank() {
+            return Err(Error::SingularMatrixError);
+        }
+
+        let n = self.qr.cols;
+        let r = self.r();
+        let mut result = Matrix::zeros(n, n);
+        for j in 0..n {
+            result[(j, j)] = 1.0 / r[(j, j)];
+            for i in (0..j).rev() {
+                let s = (i + 1..=j).map(|k| r[(i, k)] * result[(k, j)])
+                    .sum::<f64>();
+                result[(i, j)] = -s / r[(i, i)];
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+///
+/// Cholesky: Decomposition A = L L^T of a symmetric positive definite
+/// matrix, only the lower triangle of A is used
+///
+#[derive(Debug, Clone)]
+pub struct Cholesky {
+    l: Matrix
+}
+
+impl Cholesky {
+    fn new(a: &Matrix) -> Result<Cholesky> {
+        if !a.is_square() {
+            return Err(Error::DimensionMismatchError);
+        }
+
+        let n = a.rows;
+        let mut l = Matrix::zeros(n, n);
+        for j in 0..n {
+            let d = a[(j, j)] - (0..j).map(|k| l[(j, k)].powi(2)).sum::<f64>();
+            if d <= 0.0 {
+                return Err(Error::NotPositiveDefiniteError);
+            }
+            l[(j, j)] = d.sqrt();
+
+            for i in j + 1..n {
+                let s = (0..j).map(|k| l[(i, k)] * l[(j, k)]).sum::<f64>();
+                l[(i, j)] = (a[(i, j)] - s) / l[(j, j)];
+            }
+        }
+
+        Ok(Cholesky { l })
+    }
+
+    pub fn l(&self) -> &Matrix {
+        &self.l
+    }
+
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>> {
+        let n = self.l.rows;
+        if b.len() != n {
+            return Err(Error::DimensionMismatchError);
+        }
+
+        let mut x = b.to_vec();
+        for i in 0..n {
+            for k in 0..i {
+                x[i] -= self.l[(i, k)] * x[k];
+            }
+            x[i] /= self.l[(i, i)];
+        }
+        for i in (0..n).rev() {
+            for k in i + 1..n {
+                x[i] -= self.l[(k, i)] * x[k];
+            }
+            x[i] /= self.l[(i, i)];
+        }
+
+        Ok(x)
+    }
+}
+
+///
+/// LeastSquares: Weighted linear least squares solution of the
+/// overdetermined system A x = b by the QR decomposition. The weights are
+/// the inverse variances of the observations, so that the covariance of
+/// the solution is (A^T W A)^-1. The weights must not be negative, a zero
+/// weight drops the observation from the fit.
+///
+#[derive(Debug, Clone)]
+pub struct LeastSquares {
+    solution: Vec<f64>,
+    covariance: Matrix,
+    residuals: Vec<f64>,
+    chi2: f64,
+    observations: usize
+}
+
+impl LeastSquares {
+    pub fn new(a: &Matrix, b: &[f64],
+               weights: Option<&[f64]>) -> Result<LeastSquares> {
+        let (m, n) = (a.rows, a.cols);
+        if b.len() != m || weights.is_some_and(|w| w.len() != m) {
+            return Err(Error::DimensionMismatchError);
+        }
+        if weights.is_some_and(|w| w.iter().any(|&w| w < 0.0)) {
+            return Err(Error::InvalidArgumentError("weights"));
+        }
+
+        let scale = (0..m)
+            .map(|i| weights.map_or(1.0, |w| w[i].sqrt()))
+            .collect::<Vec<_>>();
+        let mut aw = a.clone();
+        for i in 0..m {
+            for j in 0..n {
+                aw[(i, j)] *= scale[i];
+            }
+        }
+        let bw = b.iter().zip(&scale).map(|(b, s)| b * s).collect::<Vec<_>>();
+
+        let qr = aw.qr()?;
+        let solution = qr.solve(&bw)?;
+        let r_inv = qr.r_inv()?;
+        let covariance = (&r_inv * &r_inv.t())?;
+
+        let residuals = a.mul_vec(&solution)?.iter().zip(b)
+            .map(|(ax, b)| b - ax)
+            .collect::<Vec<_>>();
+        let chi2 = residuals.iter().zip(&scale)
+            .map(|(r, s)| (r * s).powi(2))
+            .sum();
+
+        let observations = scale.iter().filter(|&&s| s > 0.0).count();
+
+        Ok(LeastSquares {
+            solution, covariance, residuals, chi2, observations
+        })
+    }
+
+    pub fn solution(&self) -> &[f64] {
+        &self.solution
+    }
+
+    ///
+    /// covariance: Formal covariance of the solution from the weights
+    ///
+    pub fn covariance(&self) -> &Matrix {
+        &self.covariance
+    }
+
+    ///
+    /// residuals: Observed minus computed values b - A x
+    ///
+    pub fn residuals(&self) -> &[f64] {
+        &self.residuals
+    }
+
+    ///
+    /// chi2: Weighted sum of the squared residuals
+    ///
+    pub fn chi2(&self) -> f64 {
+        self.chi2
+    }
+
+    ///
+    /// unit_weight_variance: chi2 per degree of freedom, the factor which
+    /// scales the formal covariance to the scatter of the residuals. None
+    /// when there are no degrees of freedom.
+    ///
+    pub fn unit_weight_variance(&self) -> Option<f64> {
+        let dof = self.observations.checked_sub(self.solution.len())?;
+        if dof == 0 {
+            return None;
+        }
+
+        Some(self.chi2 / dof as f64)
+    }
+
+    ///
+    /// sigma: Formal standard errors of the solution
+    ///
+    pub fn sigma(&self) -> Vec<f64> {
+        (0..self.solution.len())
+            .map(|i| self.covariance[(i, i)].sqrt())
+            .collect()
+    }
+}
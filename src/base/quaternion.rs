@@ -0,0 +1,311 @@
+use std::ops;
+
+use crate::base::Result;
+use crate::base::angle::Angle;
+use crate::base::error::Error;
+use crate::base::linalg::{CartesianVec3D, Mat3D, Norm, Vec3D};
+
+///
+/// Threshold of the cosine of the half angle above which SLERP falls back to
+/// the normalized linear interpolation
+///
+const SLERP_THRESHOLD: f64 = 0.9995;
+
+///
+/// Quaternion: w + xi + yj + zk. The unit quaternions represent rotations:
+/// q v q* rotates the vector v like the matrix Mat3D::from(q), and the
+/// product q1 q2 rotates by q2 first, like the product of the matrices. The
+/// axis and the angle follow the frame rotations of Mat3D::from_axis_angle.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Quaternion {
+    w: f64,
+    x: f64,
+    y: f64,
+    z: f64
+}
+
+impl Norm for Quaternion {
+    fn norm(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+}
+
+impl Quaternion {
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Quaternion {
+        Quaternion { w, x, y, z }
+    }
+
+    pub fn identity() -> Quaternion {
+        Quaternion::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    ///
+    /// from_vector: Pure quaternion of the vector
+    ///
+    pub fn from_vector(v: Vec3D) -> Quaternion {
+        let v: CartesianVec3D = v.into();
+        Quaternion::new(0.0, v.x(), v.y(), v.z())
+    }
+
+    ///
+    /// from_axis_angle: Rotation by the angle about the axis in the
+    /// convention of Mat3D::from_axis_angle, which gives the same matrix
+    ///
+    pub fn from_axis_angle(axis: Vec3D, angle: Angle) -> Result<Quaternion> {
+        let axis: CartesianVec3D = (axis / axis.norm())?.into();
+        let angle: f64 = angle.into();
+        let (s, c) = (angle / 2.0).sin_cos();
+
+        Ok(Quaternion::new(c, -s * axis.x(), -s * axis.y(), -s * axis.z()))
+    }
+
+    pub fn w(&self) -> f64 {
+        self.w
+    }
+
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+
+    pub fn z(&self) -> f64 {
+        self.z
+    }
+
+    ///
+    /// vector: Vector part of the quaternion
+    ///
+    pub fn vector(&self) -> Vec3D {
+        Vec3D::cartesian(self.x, self.y, self.z)
+    }
+
+    pub fn dot(&self, rhs: &Quaternion) -> f64 {
+        self.w * rhs.w + self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
+    pub fn conjugate(&self) -> Quaternion {
+        Quaternion::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    pub fn inv(&self) -> Result<Quaternion> {
+        let n2 = self.dot(self);
+        if n2 == 0.0 {
+            return Err(Error::ZeroDivisionError);
+        }
+
+        Ok(self.conjugate() * (1.0 / n2))
+    }
+
+    pub fn normalize(&self) -> Result<Quaternion> {
+        let n = self.norm();
+        if n == 0.0 {
+            return Err(Error::ZeroDivisionError);
+        }
+
+        Ok(*self * (1.0 / n))
+    }
+
+    ///
+    /// axis_angle: Axis and angle in [0, pi] of the unit quaternion in the
+    /// convention of from_axis_angle. The axis is z for the identity.
+    ///
+    pub fn axis_angle(&self) -> (Vec3D, Angle) {
+        let q = if self.w < 0.0 { -*self } else { *self };
+        let s = q.vector().norm();
+        if s == 0.0 {
+            return (Vec3D::unit_z(), Angle::from(0.0));
+        }
+
+        (q.vector() * (-1.0 / s), Angle::from(2.0 * s.atan2(q.w)))
+    }
+
+    ///
+    /// rotate: Rotation of the vector by the unit quaternion
+    ///
+    pub fn rotate(&self, v: Vec3D) -> Vec3D {
+        let u = self.vector();
+        let t = u.cross(v) * 2.0;
+
+        v + t * self.w + u.cross(t)
+    }
+
+    ///
+    /// slerp: Spherical linear interpolation between the unit quaternions
+    /// for the fraction t in [0, 1] along the shortest arc
+    ///
+    pub fn slerp(&self, other: &Quaternion, t: f64) -> Quaternion {
+        let mut other = *other;
+        let mut c = self.dot(&other);
+        if c < 0.0 {
+            other = -other;
+            c = -c;
+        }
+
+        if c > SLERP_THRESHOLD {
+            let q = *self * (1.0 - t) + other * t;
+            return q * (1.0 / q.norm());
+        }
+
+        let theta = c.acos();
+        let s = theta.sin();
+
+        *self * (((1.0 - t) * theta).sin() / s) +
+            other * ((t * theta).sin() / s)
+    }
+}
+
+///
+/// Rotation matrix of the unit quaternion
+///
+impl From<Quaternion> for Mat3D {
+    fn from(q: Quaternion) -> Self {
+        let Quaternion { w, x, y, z } = q;
+
+        Mat3D::from_rows(
+            Vec3D::cartesian(
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y)
+            ),
+            Vec3D::cartesian(
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x)
+            ),
+            Vec3D::cartesian(
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y)
+            )
+        )
+    }
+}
+
+///
+/// Unit quaternion of the rotation matrix by Shepperd's method, with a
+/// non-negative scalar part
+///
+impl From<Mat3D> for Quaternion {
+    fn from(m: Mat3D) -> Self {
+        let m = m.iter().collect::<Vec<f64>>();
+        let at = |i: usize, j: usize| m[3 * i + j];
+        let tr = at(0, 0) + at(1, 1) + at(2, 2);
+
+        // The largest of the four components is computed from the diagonal
+        let q = if tr >= at(0, 0) && tr >= at(1, 1) && tr >= at(2, 2) {
+            let w = (1.0 + tr).sqrt() / 2.0;
+            let f = 0.25 / w;
+            Quaternion::new(
+                w,
+                (at(2, 1) - at(1, 2)) * f,
+                (at(0, 2) - at(2, 0)) * f,
+                (at(1, 0) - at(0, 1)) * f
+            )
+        } else if at(0, 0) >= at(1, 1) && at(0, 0) >= at(2, 2) {
+            let x = (1.0 + 2.0 * at(0, 0) - tr).sqrt() / 2.0;
+            let f = 0.25 / x;
+            Quaternion::new(
+                (at(2, 1) - at(1, 2)) * f,
+                x,
+                (at(0, 1) + at(1, 0)) * f,
+                (at(0, 2) + at(2, 0)) * f
+            )
+        } else if at(1, 1) >= at(2, 2) {
+            let y = (1.0 + 2.0 * at(1, 1) - tr).sqrt() / 2.0;
+            let f = 0.25 / y;
+            Quaternion::new(
+                (at(0, 2) - at(2, 0)) * f,
+                (at(0, 1) + at(1, 0)) * f,
+                y,
+                (at(1, 2) + at(2, 1)) * f
+            )
+        } else {
+            let z = (1.0 + 2.0 * at(2, 2) - tr).sqrt() / 2.0;
+            let f = 0.25 / z;
+            Quaternion::new(
+                (at(1, 0) - at(0, 1)) * f,
+                (at(0, 2) + at(2, 0)) * f,
+                (at(1, 2) + at(2, 1)) * f,
+                z
+            )
+        };
+
+        if q.w < 0.0 { -q } else { q }
+    }
+}
+
+impl ops::Neg for Quaternion {
+    type Output = Self;
+
+    fn neg(self) -> Quaternion {
+        Quaternion::new(-self.w, -self.x, -self.y, -self.z)
+    }
+}
+
+impl ops::Add for Quaternion {
+    type Output = Self;
+
+    fn add(self, rhs: Quaternion) -> Quaternion {
+        Quaternion::new(
+            self.w + rhs.w, self.x + rhs.x, self.y + rhs.y, self.z + rhs.z
+        )
+    }
+}
+
+impl ops::Sub for Quaternion {
+    type Output = Self;
+
+    fn sub(self, rhs: Quaternion) -> Quaternion {
+        self + (-rhs)
+    }
+}
+
+impl ops::Mul<f64> for Quaternion {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Quaternion {
+        Quaternion::new(self.w * rhs, self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl ops::Mul<Quaternion> for f64 {
+    type Output = Quaternion;
+
+    fn mul(self, rhs: Quaternion) -> Quaternion {
+        rhs * self
+    }
+}
+
+///
+/// Hamilton product
+///
+impl ops::Mul for Quaternion {
+    type Output = Self;
+
+    fn mul(self, rhs: Quaternion) -> Quaternion {
+        Quaternion::new(
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w
+        )
+    }
+}
+
+impl ops::MulAssign for Quaternion {
+    fn mul_assign(&mut self, rhs: Quaternion) {
+        *self = *self * rhs;
+    }
+}
+
+impl ops::Mul<Vec3D> for Quaternion {
+    type Output = Vec3D;
+
+    fn mul(self, rhs: Vec3D) -> Vec3D {
+        self.rotate(rhs)
+    }
+}
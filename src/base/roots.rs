@@ -0,0 +1,88 @@
+use crate::base::Result;
+use crate::base::error::Error;
+
+const MAX_ITERATIONS: usize = 100;
+
+const GOLDEN_RATIO: f64 = 0.618_033_988_749_894_8;
+
+///
+/// pegasus: Finds a root of the function f(x) inside the interval
+/// [lower, upper] with the Pegasus method (modified regula falsi). The
+/// function must change its sign at the ends of the interval. Errors of the
+/// function evaluation are passed to the caller.
+///
+pub fn pegasus<F>(f: F, lower: f64, upper: f64, accuracy: f64) -> Result<f64>
+    where F: Fn(f64) -> Result<f64>
+{
+    let mut x1 = lower;
+    let mut x2 = upper;
+    let mut f1 = f(x1)?;
+    let mut f2 = f(x2)?;
+
+    if f1 == 0.0 {
+        return Ok(x1);
+    }
+    if f2 == 0.0 {
+        return Ok(x2);
+    }
+    if f1 * f2 > 0.0 {
+        return Err(Error::NotBracketedError);
+    }
+
+    for _ in 0..MAX_ITERATIONS {
+        let x3 = x2 - f2 / ((f2 - f1) / (x2 - x1));
+        let f3 = f(x3)?;
+
+        if f3 * f2 <= 0.0 {
+            x1 = x2;
+            f1 = f2;
+        } else {
+            f1 *= f2 / (f2 + f3);
+        }
+
+        x2 = x3;
+        f2 = f3;
+
+        if f2 == 0.0 || (x2 - x1).abs() <= accuracy {
+            return Ok(if f1.abs() < f2.abs() { x1 } else { x2 });
+        }
+    }
+
+    Err(Error::ConvergenceError)
+}
+
+///
+/// golden_section: Finds a minimum of the unimodal function f(x) inside the
+/// interval [lower, upper]. Returns the argument and the value of the minimum.
+///
+pub fn golden_section<F>(f: F, lower: f64, upper: f64,
+                         accuracy: f64) -> Result<(f64, f64)>
+    where F: Fn(f64) -> Result<f64>
+{
+    let mut a = lower;
+    let mut b = upper;
+
+    let mut x1 = b - GOLDEN_RATIO * (b - a);
+    let mut x2 = a + GOLDEN_RATIO * (b - a);
+    let mut f1 = f(x1)?;
+    let mut f2 = f(x2)?;
+
+    while (b - a).abs() > accuracy {
+        if f1 < f2 {
+            b = x2;
+            x2 = x1;
+            f2 = f1;
+            x1 = b - GOLDEN_RATIO * (b - a);
+            f1 = f(x1)?;
+        } else {
+            a = x1;
+            x1 = x2;
+            f1 = f2;
+            x2 = a + GOLDEN_RATIO * (b - a);
+            f2 = f(x2)?;
+        }
+    }
+
+    let x = 0.5 * (a + b);
+    Ok((x, f(x)?))
+}
@@ -0,0 +1,97 @@
+pub mod lunar;
+pub mod occultation;
+pub mod solar;
+pub mod transit;
+
+use crate::base::Real;
+use crate::base::angle::Angle;
+use crate::base::consts::{F_EARTH, PI2, R_EARTH};
+use crate::base::linalg::{CartesianVec3D, Mat3D, Norm, Vec3D};
+use crate::observer::Observer;
+
+///
+/// Earth-fixed geocentric position of the observer referred to the
+/// Greenwich meridian [Earth radii]
+///
+fn earth_fixed(observer: &Observer) -> Vec3D {
+    observer.geocentric() * (1.0 / R_EARTH)
+}
+
+///
+/// Geodetic longitude and latitude of an Earth-fixed point on the surface
+/// of the Earth
+///
+fn geodetic(r: Vec3D) -> (Angle, Angle) {
+    let r: CartesianVec3D = r.into();
+    let ba = 1.0 - F_EARTH;
+
+    (
+        Angle::from(r.y().atan2(r.x())),
+        Angle::from(r.z().atan2(ba * ba * r.x().hypot(r.y())))
+    )
+}
+
+///
+/// Intersection of the line p + s g (Earth-fixed, in Earth radii) with the
+/// surface of the Earth: the discriminant (negative when the line misses
+/// the Earth) and the parameter s of the intersection nearer to the
+/// direction g
+///
+fn intersection(p: Vec3D, g: Vec3D) -> (f64, f64) {
+    let scale = Mat3D::from_rows(
+        Vec3D::unit_x(),
+        Vec3D::unit_y(),
+        Vec3D::unit_z() * (1.0 / (1.0 - F_EARTH))
+    );
+    let (sp, sg) = (scale * p, scale * g);
+
+    let a = sg.dot(sg);
+    let b = sp.dot(sg);
+    let discriminant = b * b - a * (sp.dot(sp) - 1.0);
+
+    (discriminant, (-b + discriminant.max(0.0).sqrt()) / a)
+}
+
+///
+/// Position angle of the point from the centre measured from the north
+/// towards the east [rad]
+///
+fn position_angle(centre: Vec3D, point: Vec3D) -> f64 {
+    let north = Vec3D::unit_z() - centre * (centre.dot(Vec3D::unit_z()) /
+        centre.dot(centre));
+    let east = Vec3D::unit_z().cross(centre);
+
+    (east.dot(point) / east.norm()).atan2(north.dot(point) / north.norm())
+        .fmod(PI2)
+}
+
+///
+/// Geodetic zenith of the observer in the Earth-fixed frame
+///
+fn zenith(observer: &Observer) -> Vec3D {
+    let longitude: f64 = observer.longitude().into();
+    let latitude: f64 = observer.latitude().into();
+
+    Vec3D::unit(longitude, latitude).unwrap_or_default()
+}
+
+///
+/// Area of the intersection of two disks with the radii r1, r2 and the
+/// distance d of the centres divided by the area of the first disk
+///
+fn covered_fraction(r1: f64, r2: f64, d: f64) -> f64 {
+    use std::f64::consts::PI;
+
+    if d >= r1 + r2 {
+        0.0
+    } else if d <= (r1 - r2).abs() {
+        (r2.min(r1) / r1).powi(2)
+    } else {
+        let a1 = ((d * d + r1 * r1 - r2 * r2) / (2.0 * d * r1)).acos();
+        let a2 = ((d * d + r2 * r2 - r1 * r1) / (2.0 * d * r2)).acos();
+        let lens = r1 * r1 * (a1 - a1.sin() * a1.cos()) +
+            r2 * r2 * (a2 - a2.sin() * a2.cos());
+
+        lens / (PI * r1 * r1)
+    }
+}
@@ -0,0 +1,264 @@
+use crate::base::Result;
+use crate::base::angle::angular_radius;
+use crate::base::consts::R_EARTH;
+use crate::base::linalg::Norm;
+use crate::base::roots::{golden_section, pegasus};
+use crate::ephemeris::{apparent, Body, Ephemeris};
+use crate::events::{Category, EventIterator, EventKind, Phase};
+use crate::time::JulianDate;
+
+
+///
+/// Ratio of the mean radius of the Earth at the latitude 45 deg to the
+/// equatorial radius
+///
+const MEAN_RADIUS: f64 = 0.998340;
+
+///
+/// Half-width of the search interval around the full moon [d]
+///
+const SPAN: f64 = 0.25;
+
+///
+/// Accuracy of the times [d]
+///
+const ACCURACY: f64 = 1e-7;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LunarEclipseType {
+    Penumbral,
+    Partial,
+    Total
+}
+
+///
+/// ShadowRule: Enlargement of the Earth's shadow by the atmosphere. Danjon's
+/// rule enlarges the radius of the Earth by 1/85, Chauvenet's rule enlarges
+/// the radii of the shadow by 1/50.
+///
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum ShadowRule {
+    #[default]
+    Danjon,
+    Chauvenet
+}
+
+impl ShadowRule {
+    ///
+    /// radii: Angular radii of the penumbra and of the umbra for the
+    /// horizontal parallaxes of the Moon and the Sun and the semidiameter of
+    /// the Sun [rad]
+    ///
+    pub fn radii(&self, parallax_moon: f64, parallax_sun: f64,
+                 semidiameter_sun: f64) -> (f64, f64) {
+        let earth = MEAN_RADIUS * parallax_moon;
+
+        match *self {
+            ShadowRule::Danjon => {
+                let earth = earth * (1.0 + 1.0 / 85.0);
+                (
+                    earth + parallax_sun + semidiameter_sun,
+                    earth + parallax_sun - semidiameter_sun
+                )
+            },
+            ShadowRule::Chauvenet => (
+                1.02 * (earth + parallax_sun + semidiameter_sun),
+                1.02 * (earth + parallax_sun - semidiameter_sun)
+            )
+        }
+    }
+}
+
+///
+/// Distance of the Moon from the shadow axis, radii of the penumbra and the
+/// umbra and semidiameter of the Moon [rad]
+///
+struct Shadow {
+    distance: f64,
+    penumbra: f64,
+    umbra: f64,
+    moon: f64
+}
+
+fn shadow<E>(ephemeris: &E, rule: ShadowRule, jd: JulianDate) -> Result<Shadow>
+    where E: Ephemeris + ?Sized
+{
+    let sun = apparent(ephemeris, Body::Sun, jd)?;
+    let moon = apparent(ephemeris, Body::Moon, jd)?;
+
+    let (penumbra, umbra) = rule.radii(
+        angular_radius(R_EARTH, moon.norm()),
+        angular_radius(R_EARTH, sun.norm()),
+        angular_radius(Body::Sun.radius(), sun.norm())
+    );
+
+    Ok(Shadow {
+        distance: (-sun).separation(moon),
+        penumbra,
+        umbra,
+        moon: angular_radius(Body::Moon.radius(), moon.norm())
+    })
+}
+
+///
+/// LunarEclipse: Circumstances of a lunar eclipse: the type, the TT dates of
+/// the contacts with the penumbra (P1, P4) and the umbra (U1 to U4) and of
+/// the greatest eclipse and the umbral and penumbral magnitudes. The
+/// magnitudes are the fractions of the lunar diameter immersed in the
+/// shadows at the greatest eclipse, the umbral magnitude is negative for a
+/// penumbral eclipse.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LunarEclipse {
+    kind: LunarEclipseType,
+    p1: JulianDate,
+    u1: Option<JulianDate>,
+    u2: Option<JulianDate>,
+    greatest: JulianDate,
+    u3: Option<JulianDate>,
+    u4: Option<JulianDate>,
+    p4: JulianDate,
+    umbral_magnitude: f64,
+    penumbral_magnitude: f64
+}
+
+impl LunarEclipse {
+    ///
+    /// new: Computes the eclipse at the full moon near the TT date, None
+    /// when the Moon misses the penumbra
+    ///
+    pub fn new<E>(ephemeris: &E, jd: JulianDate,
+                  rule: ShadowRule) -> Result<Option<LunarEclipse>>
+        where E: Ephemeris + ?Sized
+    {
+        let at = |x: f64| shadow(ephemeris, rule, JulianDate::new(x));
+
+        let (t, _) = golden_section(
+            |x| Ok(at(x)?.distance), jd.jd() - SPAN, jd.jd() + SPAN, ACCURACY
+        )?;
+        let greatest = at(t)?;
+
+        let penumbral_magnitude = (greatest.penumbra + greatest.moon -
+            greatest.distance) / (2.0 * greatest.moon);
+        let umbral_magnitude = (greatest.umbra + greatest.moon -
+            greatest.distance) / (2.0 * greatest.moon);
+
+        if penumbral_magnitude <= 0.0 {
+            return Ok(None);
+        }
+
+        let contacts = |radius: &dyn Fn(&Shadow) -> f64| -> Result<_> {
+            let f = |x: f64| {
+                let s = at(x)?;
+                Ok(s.distance - radius(&s))
+            };
+            Ok((
+                JulianDate::new(pegasus(f, t - SPAN, t, ACCURACY)?),
+                JulianDate::new(pegasus(f, t, t + SPAN, ACCURACY)?)
+            ))
+        };
+
+        let (p1, p4) = contacts(&|s| s.penumbra + s.moon)?;
+        let (u1, u4) = if umbral_magnitude > 0.0 {
+            let (u1, u4) = contacts(&|s| s.umbra + s.moon)?;
+            (Some(u1), Some(u4))
+        } else {
+            (None, None)
+        };
+        let (u2, u3) = if umbral_magnitude > 1.0 {
+            let (u2, u3) = contacts(&|s| s.umbra - s.moon)?;
+            (Some(u2), Some(u3))
+        } else {
+            (None, None)
+        };
+
+        let kind = if umbral_magnitude > 1.0 {
+            LunarEclipseType::Total
+        } else if umbral_magnitude > 0.0 {
+            LunarEclipseType::Partial
+        } else {
+            LunarEclipseType::Penumbral
+        };
+
+        Ok(Some(LunarEclipse {
+            kind,
+            p1,
+            u1,
+            u2,
+            greatest: JulianDate::new(t),
+            u3,
+            u4,
+            p4,
+            umbral_magnitude,
+            penumbral_magnitude
+        }))
+    }
+
+    pub fn kind(&self) -> LunarEclipseType {
+        self.kind
+    }
+
+    pub fn p1(&self) -> JulianDate {
+        self.p1
+    }
+
+    pub fn u1(&self) -> Option<JulianDate> {
+        self.u1
+    }
+
+    pub fn u2(&self) -> Option<JulianDate> {
+        self.u2
+    }
+
+    pub fn greatest(&self) -> JulianDate {
+        self.greatest
+    }
+
+    pub fn u3(&self) -> Option<JulianDate> {
+        self.u3
+    }
+
+    pub fn u4(&self) -> Option<JulianDate> {
+        self.u4
+    }
+
+    pub fn p4(&self) -> JulianDate {
+        self.p4
+    }
+
+    pub fn umbral_magnitude(&self) -> f64 {
+        self.umbral_magnitude
+    }
+
+    pub fn penumbral_magnitude(&self) -> f64 {
+        self.penumbral_magnitude
+    }
+}
+
+///
+/// lunar_eclipses: Lunar eclipses with the greatest eclipse between the TT
+/// dates
+///
+pub fn lunar_eclipses<E>(ephemeris: &E, start: JulianDate, end: JulianDate,
+                         rule: ShadowRule) -> Result<Vec<LunarEclipse>>
+    where E: Ephemeris + ?Sized
+{
+    let mut eclipses = Vec::new();
+
+    let phases = EventIterator::new(ephemeris, start - SPAN, end + SPAN)
+        .with_categories(&[Category::Phases]);
+    for event in phases {
+        let event = event?;
+        if event.kind() != EventKind::Phase(Phase::FullMoon) {
+            continue;
+        }
+
+        if let Some(eclipse) = LunarEclipse::new(ephemeris, event.jd(), rule)? {
+            if eclipse.greatest >= start && eclipse.greatest < end {
+                eclipses.push(eclipse);
+            }
+        }
+    }
+
+    Ok(eclipses)
+}
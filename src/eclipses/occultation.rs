@@ -0,0 +1,426 @@
+use std::f64::consts::FRAC_PI_2;
+
+use crate::astrometry::aberration::{annual_velocity, classical};
+use crate::base::Result;
+use crate::base::angle::{angular_radius, wrap, Angle};
+use crate::base::consts::{AU, R_EARTH, R_MOON};
+use crate::base::error::Error;
+use crate::base::linalg::{CartesianVec3D, Mat3D, Norm, Vec3D};
+use crate::base::roots::{golden_section, pegasus};
+use crate::ephemeris::{apparent, Body, Ephemeris};
+use crate::frames::nutation::nutation_matrix;
+use crate::frames::precession::{frame_bias_matrix, precession_matrix};
+use crate::frames::sidereal::gast;
+use crate::observer::Observer;
+use crate::time::JulianDate;
+
+use super::{earth_fixed, geodetic, intersection, position_angle, zenith};
+
+///
+/// Step of the search for the close approaches [d]
+///
+const SEARCH_STEP: f64 = 1.0 / 24.0;
+
+///
+/// Longest time from the greatest occultation to a contact [d]
+///
+const MAX_HALF_DURATION: f64 = 0.25;
+
+///
+/// Accuracy of the times [d]
+///
+const ACCURACY: f64 = 1e-7;
+
+///
+/// Step of the numerical differentiation [d]
+///
+const DIFFERENTIATION_STEP: f64 = 1e-4;
+
+///
+/// Iterations of the grazing limit points
+///
+const GRAZE_ITERATIONS: usize = 3;
+
+///
+/// Target: Occulted body, a star is given by the unit vector of its
+/// direction referred to the ICRS
+///
+#[derive(Debug, Copy, Clone)]
+pub enum Target {
+    Star(Vec3D),
+    Planet(Body)
+}
+
+///
+/// Contact: TT date of the disappearance or the reappearance with the
+/// position angle of the point of contact on the lunar limb (from the north
+/// towards the east), the cusp angle and the altitude of the Moon. The cusp
+/// angle is measured from the nearer cusp, it is positive on the dark limb
+/// and negative on the bright limb.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Contact {
+    jd: JulianDate,
+    position_angle: Angle,
+    cusp_angle: Angle,
+    altitude: Angle
+}
+
+impl Contact {
+    pub fn jd(&self) -> JulianDate {
+        self.jd
+    }
+
+    pub fn position_angle(&self) -> Angle {
+        self.position_angle
+    }
+
+    pub fn cusp_angle(&self) -> Angle {
+        self.cusp_angle
+    }
+
+    pub fn altitude(&self) -> Angle {
+        self.altitude
+    }
+}
+
+///
+/// Occultation: Disappearance and reappearance of the target (the centre
+/// of a planet) behind the limb of the Moon for an observer
+///
+#[derive(Debug, Copy, Clone)]
+pub struct Occultation {
+    target: Target,
+    disappearance: Contact,
+    reappearance: Contact
+}
+
+impl Occultation {
+    pub fn target(&self) -> Target {
+        self.target
+    }
+
+    pub fn disappearance(&self) -> Contact {
+        self.disappearance
+    }
+
+    pub fn reappearance(&self) -> Contact {
+        self.reappearance
+    }
+}
+
+///
+/// GrazePoint: Point of a grazing limit line at the TT date
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GrazePoint {
+    jd: JulianDate,
+    longitude: Angle,
+    latitude: Angle
+}
+
+impl GrazePoint {
+    pub fn jd(&self) -> JulianDate {
+        self.jd
+    }
+
+    pub fn longitude(&self) -> Angle {
+        self.longitude
+    }
+
+    pub fn latitude(&self) -> Angle {
+        self.latitude
+    }
+}
+
+///
+/// Geocentric apparent positions of the Moon and of the target referred to
+/// the true equator and equinox of the date [AU] and the rotation from the
+/// true equator to the Earth-fixed frame
+///
+struct Geometry {
+    moon: Vec3D,
+    target: Vec3D,
+    rotation: Mat3D
+}
+
+fn geometry<E>(ephemeris: &E, target: Target,
+               jd: JulianDate) -> Result<Geometry>
+    where E: Ephemeris + ?Sized
+{
+    let target = match target {
+        Target::Star(direction) => {
+            let velocity = annual_velocity(ephemeris, jd)?;
+            let u = classical((direction / direction.norm())?, velocity)?;
+
+            nutation_matrix(jd) * precession_matrix(jd) *
+                frame_bias_matrix() * u
+        },
+        Target::Planet(body) => apparent(ephemeris, body, jd)?
+    };
+
+    Ok(Geometry {
+        moon: apparent(ephemeris, Body::Moon, jd)?,
+        target,
+        rotation: Mat3D::r_z(gast(jd.tt_to_ut()))
+    })
+}
+
+///
+/// Topocentric positions of the Moon and of the target for an Earth-fixed
+/// point [AU]
+///
+fn topocentric(geometry: &Geometry, target: Target,
+               r: Vec3D) -> (Vec3D, Vec3D) {
+    let observer = geometry.rotation.t() * r * (R_EARTH / AU);
+    let target = match target {
+        Target::Star(_) => geometry.target,
+        Target::Planet(_) => geometry.target - observer
+    };
+
+    (geometry.moon - observer, target)
+}
+
+///
+/// Topocentric distance of the target from the lunar limb [rad]
+///
+fn limb_distance(geometry: &Geometry, target: Target, r: Vec3D) -> f64 {
+    let (moon, target) = topocentric(geometry, target, r);
+    moon.separation(target) - angular_radius(R_MOON, moon.norm())
+}
+
+fn contact<E>(ephemeris: &E, target: Target, observer: &Observer,
+              jd: JulianDate) -> Result<Contact>
+    where E: Ephemeris + ?Sized
+{
+    let r = earth_fixed(observer);
+    let g = geometry(ephemeris, target, jd)?;
+    let (moon, point) = topocentric(&g, target, r);
+    let sun = apparent(ephemeris, Body::Sun, jd)?;
+
+    let p = position_angle(moon, point);
+    let limb = wrap(p - position_angle(moon, sun)).abs();
+    let up = g.rotation.t() * zenith(observer);
+
+    Ok(Contact {
+        jd,
+        position_angle: Angle::from(p),
+        cusp_angle: Angle::from(limb - FRAC_PI_2),
+        altitude: Angle::from((moon.dot(up) / moon.norm()).asin())
+    })
+}
+
+///
+/// Time of the contact before (negative step) or after the greatest
+/// occultation at x, the bracket is widened by the step until the target
+/// is outside the lunar limb
+///
+fn contact_time<F>(f: &F, x: f64, step: f64) -> Result<f64>
+    where F: Fn(f64) -> Result<f64>
+{
+    let mut inner = x;
+
+    loop {
+        let outer = inner + step;
+        if f(outer)? >= 0.0 {
+            let (lower, upper) = if step < 0.0 {
+                (outer, inner)
+            } else {
+                (inner, outer)
+            };
+            return pegasus(f, lower, upper, ACCURACY);
+        }
+        if (outer - x).abs() >= MAX_HALF_DURATION {
+            return Err(Error::NotBracketedError);
+        }
+
+        inner = outer;
+    }
+}
+
+///
+/// occultations: Occultations of the target by the Moon for the observer
+/// with the greatest occultation between the TT dates. The occultations are
+/// found regardless of the altitudes of the Moon and of the Sun, those with
+/// a contact which cannot be bracketed (close to a graze) are skipped.
+///
+pub fn occultations<E>(ephemeris: &E, observer: &Observer, target: Target,
+                       start: JulianDate,
+                       end: JulianDate) -> Result<Vec<Occultation>>
+    where E: Ephemeris + ?Sized
+{
+    if let Target::Planet(Body::Moon) | Target::Planet(Body::Earth) = target {
+        return Err(Error::InvalidBodyError);
+    }
+
+    let r = earth_fixed(observer);
+    let f = |x: f64| {
+        let g = geometry(ephemeris, target, JulianDate::new(x))?;
+        Ok(limb_distance(&g, target, r))
+    };
+
+    // The scan is padded by one step so that the minima close to the ends
+    // are bracketed
+    let (first, last) = (start - SEARCH_STEP, end + SEARCH_STEP);
+    let steps = ((last - first) / SEARCH_STEP).ceil().max(2.0) as usize;
+    let step = (last - first) / steps as f64;
+    let t = |k: usize| first.jd() + step * k as f64;
+
+    let mut occultations = Vec::new();
+    let mut previous = f(t(0))?;
+    let mut current = f(t(1))?;
+
+    for k in 1..steps {
+        let next = f(t(k + 1))?;
+
+        if current <= previous && current < next {
+            let (x, distance) =
+                golden_section(f, t(k - 1), t(k + 1), ACCURACY)?;
+
+            if distance < 0.0 && x >= start.jd() && x < end.jd() {
+                let contacts = match (contact_time(&f, x, -step),
+                                      contact_time(&f, x, step)) {
+                    (Ok(first), Ok(last)) => Some((first, last)),
+                    (Err(Error::NotBracketedError), _) |
+                    (_, Err(Error::NotBracketedError)) => None,
+                    (Err(err), _) | (_, Err(err)) => return Err(err)
+                };
+
+                if let Some((first, last)) = contacts {
+                    occultations.push(Occultation {
+                        target,
+                        disappearance: contact(
+                            ephemeris, target, observer, JulianDate::new(first)
+                        )?,
+                        reappearance: contact(
+                            ephemeris, target, observer, JulianDate::new(last)
+                        )?
+                    });
+                }
+            }
+        }
+
+        previous = current;
+        current = next;
+    }
+
+    Ok(occultations)
+}
+
+///
+/// Fundamental frame of the occultation: rotation from the Earth-fixed
+/// frame to the frame with the z-axis towards the target and the
+/// coordinates of the centre of the Moon in the fundamental frame [Earth
+/// radii]
+///
+fn fundamental(geometry: &Geometry) -> (Mat3D, f64, f64, f64) {
+    let ez = (geometry.target / geometry.target.norm()).unwrap_or_default();
+    let ex = Vec3D::unit_z().cross(ez);
+    let ex = (ex / ex.norm()).unwrap_or_default();
+    let ey = ez.cross(ex);
+
+    let frame = Mat3D::from_rows(ex, ey, ez);
+    let moon = geometry.moon * (AU / R_EARTH);
+
+    (
+        frame * geometry.rotation.t(),
+        ex.dot(moon),
+        ey.dot(moon),
+        ez.dot(moon)
+    )
+}
+
+///
+/// Point of a grazing limit line at the TT date, the sign selects the
+/// northern (+1) or the southern (-1) limit
+///
+fn graze_point<E>(ephemeris: &E, target: Target, jd: JulianDate,
+                  sign: f64) -> Result<Option<GrazePoint>>
+    where E: Ephemeris + ?Sized
+{
+    let h = DIFFERENTIATION_STEP;
+    let g0 = geometry(ephemeris, target, jd - h)?;
+    let g = geometry(ephemeris, target, jd)?;
+    let g1 = geometry(ephemeris, target, jd + h)?;
+
+    let (m0, x0, y0, _) = fundamental(&g0);
+    let (m, x, y, z) = fundamental(&g);
+    let (m1, x1, y1, _) = fundamental(&g1);
+    let k = R_MOON / R_EARTH;
+
+    // The Moon must be between the Earth and the target
+    if z < 0.0 {
+        return Ok(None);
+    }
+
+    // Start with the point on the fundamental plane below the Moon
+    let mut r = m.t() * Vec3D::cartesian(x, y, 0.0);
+    let axis = m.t() * Vec3D::unit_z();
+    let mut point = None;
+
+    for _ in 0..GRAZE_ITERATIONS {
+        let rho0: CartesianVec3D = (m0 * r).into();
+        let rho1: CartesianVec3D = (m1 * r).into();
+        let u = (x1 - x0) - (rho1.x() - rho0.x());
+        let v = (y1 - y0) - (rho1.y() - rho0.y());
+        let speed = u.hypot(v);
+
+        // Normal of the relative motion pointing towards the north
+        let (nx, ny) = if u >= 0.0 { (-v, u) } else { (v, -u) };
+        let (nx, ny) = (nx / speed, ny / speed);
+
+        let xi = x + sign * k * nx;
+        let eta = y + sign * k * ny;
+        let p = m.t() * Vec3D::cartesian(xi, eta, 0.0);
+
+        let (discriminant, zeta) = intersection(p, axis);
+        if discriminant < 0.0 {
+            return Ok(None);
+        }
+
+        r = p + axis * zeta;
+        point = Some(r);
+    }
+
+    // The target must be above the horizon of the point
+    Ok(point.and_then(|r| {
+        let (longitude, latitude) = geodetic(r);
+        let up = Vec3D::unit(longitude.into(), latitude.into())
+            .unwrap_or_default();
+
+        if up.dot(axis) < 0.0 {
+            None
+        } else {
+            Some(GrazePoint { jd, longitude, latitude })
+        }
+    }))
+}
+
+///
+/// grazing_limits: Northern and southern grazing limit lines of the
+/// occultation on the surface of the Earth between the TT dates with the
+/// step [d]. The limits are computed for the height zero and for the
+/// direction of the centre of the target, only the points where the target
+/// is above the horizon are given. The step must be positive.
+///
+pub fn grazing_limits<E>(ephemeris: &E, target: Target, start: JulianDate,
+                         end: JulianDate, step: f64)
+    -> Result<(Vec<GrazePoint>, Vec<GrazePoint>)>
+    where E: Ephemeris + ?Sized
+{
+    if step <= 0.0 {
+        return Err(Error::InvalidArgumentError("step"));
+    }
+
+    let steps = ((end - start) / step).floor() as usize;
+
+    let mut north = Vec::new();
+    let mut south = Vec::new();
+    for k in 0..=steps {
+        let jd = start + step * k as f64;
+        north.extend(graze_point(ephemeris, target, jd, 1.0)?);
+        south.extend(graze_point(ephemeris, target, jd, -1.0)?);
+    }
+
+    Ok((north, south))
+}
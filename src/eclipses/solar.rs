@@ -0,0 +1,709 @@
+use std::f64::consts::FRAC_PI_2;
+
+use crate::base::Real;
+use crate::base::Result;
+use crate::base::angle::{wrap, Angle};
+use crate::base::consts::{AU, F_EARTH, PI2, R_EARTH, R_SUN};
+use crate::base::error::Error;
+use crate::base::linalg::{CartesianVec3D, Mat3D, Norm, SphericalVec3D, Vec3D};
+use crate::base::roots::{golden_section, pegasus};
+use crate::ephemeris::{apparent, Body, Ephemeris};
+use crate::frames::sidereal::gast;
+use crate::observer::Observer;
+use crate::time::JulianDate;
+
+use super::{covered_fraction, earth_fixed, geodetic, intersection, zenith};
+
+///
+/// Ratio of the radius of the Moon to the equatorial radius of the Earth
+/// for the penumbra and for the umbra
+///
+const K_PENUMBRA: f64 = 0.2725076;
+const K_UMBRA: f64 = 0.272281;
+
+///
+/// Times of the tabulation of the elements relative to the reference
+/// epoch [h]
+///
+const NODES: [f64; 4] = [-3.0, -1.0, 1.0, 3.0];
+
+///
+/// Half-width of the search interval for the circumstances [h]
+///
+const SPAN: f64 = 3.0;
+
+///
+/// Accuracy of the times [h]
+///
+const ACCURACY: f64 = 1e-6;
+
+///
+/// Step of the numerical differentiation [h]
+///
+const STEP: f64 = 1e-3;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SolarEclipseType {
+    Partial,
+    Annular,
+    Total,
+    Hybrid
+}
+
+///
+/// Cubic polynomial in the time from the reference epoch [h]
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Polynomial([f64; 4]);
+
+impl Polynomial {
+    ///
+    /// Interpolates the values tabulated at the nodes
+    ///
+    fn fit(f: [f64; 4]) -> Polynomial {
+        let (e1, e3) = ((f[1] + f[2]) / 2.0, (f[0] + f[3]) / 2.0);
+        let (o1, o3) = ((f[2] - f[1]) / 2.0, (f[3] - f[0]) / 2.0);
+        let c2 = (e3 - e1) / 8.0;
+        let c3 = (o3 - 3.0 * o1) / 24.0;
+
+        Polynomial([e1 - c2, o1 - c3, c2, c3])
+    }
+
+    fn value(&self, t: f64) -> f64 {
+        let c = &self.0;
+        c[0] + t * (c[1] + t * (c[2] + t * c[3]))
+    }
+}
+
+///
+/// Instantaneous Besselian elements
+///
+struct Instant {
+    x: f64,
+    y: f64,
+    d: f64,
+    mu: f64,
+    l1: f64,
+    l2: f64,
+    tan_f1: f64,
+    tan_f2: f64
+}
+
+fn instant<E>(ephemeris: &E, jd: JulianDate) -> Result<Instant>
+    where E: Ephemeris + ?Sized
+{
+    let scale = AU / R_EARTH;
+    let sun = apparent(ephemeris, Body::Sun, jd)? * scale;
+    let moon = apparent(ephemeris, Body::Moon, jd)? * scale;
+
+    let g = sun - moon;
+    let distance = g.norm();
+    let axis: SphericalVec3D = g.into();
+    let (a, d) = (axis.phi(), axis.theta());
+
+    let ez = (g / distance)?;
+    let ex = Vec3D::cartesian(-a.sin(), a.cos(), 0.0);
+    let ey = ez.cross(ex);
+
+    let sin_f1 = (R_SUN / R_EARTH + K_PENUMBRA) / distance;
+    let sin_f2 = (R_SUN / R_EARTH - K_UMBRA) / distance;
+    let tan_f1 = sin_f1 / (1.0 - sin_f1 * sin_f1).sqrt();
+    let tan_f2 = sin_f2 / (1.0 - sin_f2 * sin_f2).sqrt();
+
+    let z = ez.dot(moon);
+
+    Ok(Instant {
+        x: ex.dot(moon),
+        y: ey.dot(moon),
+        d,
+        mu: (gast(jd.tt_to_ut()) - a).fmod(PI2),
+        l1: (z + K_PENUMBRA / sin_f1) * tan_f1,
+        l2: (z - K_UMBRA / sin_f2) * tan_f2,
+        tan_f1,
+        tan_f2
+    })
+}
+
+///
+/// BesselianElements: Besselian elements of a solar eclipse given by the
+/// cubic polynomials in the time from the reference epoch (the whole TT
+/// hour nearest to the given date). The coordinates x, y of the shadow
+/// axis and the radii l1, l2 of the penumbra and the umbra in the
+/// fundamental plane are in Earth radii, the declination d and the
+/// Greenwich hour angle mu of the shadow axis in radians. The elements are
+/// valid for about three hours from the reference epoch.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BesselianElements {
+    t0: JulianDate,
+    x: Polynomial,
+    y: Polynomial,
+    d: Polynomial,
+    mu: Polynomial,
+    l1: Polynomial,
+    l2: Polynomial,
+    tan_f1: f64,
+    tan_f2: f64
+}
+
+///
+/// CentralPoint: Point of the central line at the TT date with the
+/// duration of the central eclipse [s]
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CentralPoint {
+    jd: JulianDate,
+    longitude: Angle,
+    latitude: Angle,
+    duration: f64
+}
+
+impl CentralPoint {
+    pub fn jd(&self) -> JulianDate {
+        self.jd
+    }
+
+    pub fn longitude(&self) -> Angle {
+        self.longitude
+    }
+
+    pub fn latitude(&self) -> Angle {
+        self.latitude
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.duration
+    }
+}
+
+///
+/// GlobalCircumstances: Type of the eclipse, TT date, gamma (least
+/// distance of the shadow axis from the centre of the Earth) and magnitude
+/// of the greatest eclipse with the position of the point of the greatest
+/// eclipse. The magnitude of a central eclipse is the ratio of the apparent
+/// diameters of the Moon and the Sun.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GlobalCircumstances {
+    kind: SolarEclipseType,
+    greatest: JulianDate,
+    gamma: f64,
+    magnitude: f64,
+    longitude: Angle,
+    latitude: Angle,
+    duration: Option<f64>
+}
+
+impl GlobalCircumstances {
+    pub fn kind(&self) -> SolarEclipseType {
+        self.kind
+    }
+
+    pub fn greatest(&self) -> JulianDate {
+        self.greatest
+    }
+
+    pub fn gamma(&self) -> f64 {
+        self.gamma
+    }
+
+    pub fn magnitude(&self) -> f64 {
+        self.magnitude
+    }
+
+    pub fn longitude(&self) -> Angle {
+        self.longitude
+    }
+
+    pub fn latitude(&self) -> Angle {
+        self.latitude
+    }
+
+    ///
+    /// duration: Duration of the central eclipse at the point of the
+    /// greatest eclipse [s]
+    ///
+    pub fn duration(&self) -> Option<f64> {
+        self.duration
+    }
+}
+
+///
+/// Contact: TT date of a contact with the position angle (from the north
+/// point of the solar disk towards the east) and the vertex angle (from the
+/// zenith point) of the point of contact
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Contact {
+    jd: JulianDate,
+    position_angle: Angle,
+    vertex_angle: Angle
+}
+
+impl Contact {
+    pub fn jd(&self) -> JulianDate {
+        self.jd
+    }
+
+    pub fn position_angle(&self) -> Angle {
+        self.position_angle
+    }
+
+    pub fn vertex_angle(&self) -> Angle {
+        self.vertex_angle
+    }
+}
+
+///
+/// LocalCircumstances: Circumstances of the eclipse for an observer: the
+/// contacts (the second and the third only for a total or annular eclipse),
+/// the TT date of the maximum with the magnitude, the obscuration (covered
+/// fraction of the area of the solar disk) and the altitude of the Sun.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LocalCircumstances {
+    kind: SolarEclipseType,
+    first: Contact,
+    second: Option<Contact>,
+    maximum: Contact,
+    third: Option<Contact>,
+    fourth: Contact,
+    magnitude: f64,
+    obscuration: f64,
+    altitude: Angle
+}
+
+impl LocalCircumstances {
+    pub fn kind(&self) -> SolarEclipseType {
+        self.kind
+    }
+
+    pub fn first(&self) -> Contact {
+        self.first
+    }
+
+    pub fn second(&self) -> Option<Contact> {
+        self.second
+    }
+
+    pub fn maximum(&self) -> Contact {
+        self.maximum
+    }
+
+    pub fn third(&self) -> Option<Contact> {
+        self.third
+    }
+
+    pub fn fourth(&self) -> Contact {
+        self.fourth
+    }
+
+    pub fn magnitude(&self) -> f64 {
+        self.magnitude
+    }
+
+    pub fn obscuration(&self) -> f64 {
+        self.obscuration
+    }
+
+    pub fn altitude(&self) -> Angle {
+        self.altitude
+    }
+}
+
+///
+/// Shadow geometry for an observer: coordinates of the shadow axis relative
+/// to the observer (u, v), radii of the penumbra and the umbra at the
+/// observer and the observer's coordinates in the fundamental plane
+///
+struct Shadow {
+    u: f64,
+    v: f64,
+    l1: f64,
+    l2: f64,
+    xi: f64,
+    eta: f64
+}
+
+impl Shadow {
+    fn distance(&self) -> f64 {
+        self.u.hypot(self.v)
+    }
+}
+
+impl BesselianElements {
+    ///
+    /// new: Computes the elements for the eclipse near the TT date
+    ///
+    pub fn new<E>(ephemeris: &E, jd: JulianDate) -> Result<BesselianElements>
+        where E: Ephemeris + ?Sized
+    {
+        let t0 = JulianDate::new(((jd.jd() - 0.5) * 24.0).round() / 24.0 + 0.5);
+
+        let mut values = Vec::with_capacity(NODES.len());
+        for &t in NODES.iter() {
+            values.push(instant(ephemeris, t0 + t / 24.0)?);
+        }
+
+        let fit = |f: &dyn Fn(&Instant) -> f64| {
+            Polynomial::fit([
+                f(&values[0]), f(&values[1]), f(&values[2]), f(&values[3])
+            ])
+        };
+
+        let mut mu = [values[0].mu; 4];
+        for k in 1..4 {
+            mu[k] = mu[k - 1] + wrap(values[k].mu - mu[k - 1]);
+        }
+
+        Ok(BesselianElements {
+            t0,
+            x: fit(&|e| e.x),
+            y: fit(&|e| e.y),
+            d: fit(&|e| e.d),
+            mu: Polynomial::fit(mu),
+            l1: fit(&|e| e.l1),
+            l2: fit(&|e| e.l2),
+            tan_f1: (values[1].tan_f1 + values[2].tan_f1) / 2.0,
+            tan_f2: (values[1].tan_f2 + values[2].tan_f2) / 2.0
+        })
+    }
+
+    ///
+    /// t0: Reference epoch of the elements (TT)
+    ///
+    pub fn t0(&self) -> JulianDate {
+        self.t0
+    }
+
+    pub fn x(&self, jd: JulianDate) -> f64 {
+        self.x.value(self.hours(jd))
+    }
+
+    pub fn y(&self, jd: JulianDate) -> f64 {
+        self.y.value(self.hours(jd))
+    }
+
+    pub fn d(&self, jd: JulianDate) -> f64 {
+        self.d.value(self.hours(jd))
+    }
+
+    pub fn mu(&self, jd: JulianDate) -> f64 {
+        self.mu.value(self.hours(jd)).fmod(PI2)
+    }
+
+    pub fn l1(&self, jd: JulianDate) -> f64 {
+        self.l1.value(self.hours(jd))
+    }
+
+    pub fn l2(&self, jd: JulianDate) -> f64 {
+        self.l2.value(self.hours(jd))
+    }
+
+    pub fn tan_f1(&self) -> f64 {
+        self.tan_f1
+    }
+
+    pub fn tan_f2(&self) -> f64 {
+        self.tan_f2
+    }
+
+    fn hours(&self, jd: JulianDate) -> f64 {
+        (jd - self.t0) * 24.0
+    }
+
+    fn date(&self, t: f64) -> JulianDate {
+        self.t0 + t / 24.0
+    }
+
+    ///
+    /// Rotation from the Earth-fixed frame to the fundamental frame
+    ///
+    fn rotation(&self, t: f64) -> Mat3D {
+        Mat3D::r_x(FRAC_PI_2 - self.d.value(t)) *
+            Mat3D::r_z(FRAC_PI_2 - self.mu.value(t))
+    }
+
+    ///
+    /// Distance of the shadow axis from the centre of the Earth
+    ///
+    fn gamma(&self, t: f64) -> f64 {
+        self.x.value(t).hypot(self.y.value(t))
+    }
+
+    ///
+    /// Parameter of the intersection of the shadow axis with the surface
+    /// of the Earth (the coordinate along the axis) and the corresponding
+    /// Earth-fixed point. The discriminant is negative when the axis misses
+    /// the Earth.
+    ///
+    fn intersection(&self, t: f64) -> (f64, f64, Vec3D) {
+        let m = self.rotation(t).t();
+        let p = m * Vec3D::cartesian(self.x.value(t), self.y.value(t), 0.0);
+        let g = m * Vec3D::unit_z();
+        let (discriminant, zeta) = intersection(p, g);
+
+        (discriminant, zeta, p + g * zeta)
+    }
+
+    ///
+    /// Shadow geometry for an Earth-fixed point
+    ///
+    fn shadow(&self, r: Vec3D, t: f64) -> Shadow {
+        let rho: CartesianVec3D = (self.rotation(t) * r).into();
+
+        Shadow {
+            u: self.x.value(t) - rho.x(),
+            v: self.y.value(t) - rho.y(),
+            l1: self.l1.value(t) - rho.z() * self.tan_f1,
+            l2: self.l2.value(t) - rho.z() * self.tan_f2,
+            xi: rho.x(),
+            eta: rho.y()
+        }
+    }
+
+    ///
+    /// Speed of the shadow relative to an Earth-fixed point in the
+    /// fundamental plane [Earth radii/h]
+    ///
+    fn speed(&self, r: Vec3D, t: f64) -> f64 {
+        let s1 = self.shadow(r, t + STEP);
+        let s0 = self.shadow(r, t - STEP);
+
+        (s1.u - s0.u).hypot(s1.v - s0.v) / (2.0 * STEP)
+    }
+
+    fn central_point(&self, t: f64) -> Option<CentralPoint> {
+        let (discriminant, _, r) = self.intersection(t);
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        let (longitude, latitude) = geodetic(r);
+        let shadow = self.shadow(r, t);
+
+        Some(CentralPoint {
+            jd: self.date(t),
+            longitude,
+            latitude,
+            duration: 2.0 * shadow.l2.abs() / self.speed(r, t) * 3600.0
+        })
+    }
+
+    ///
+    /// central_line: Point of the central line at the TT date, None when the
+    /// shadow axis misses the Earth
+    ///
+    pub fn central_line(&self, jd: JulianDate) -> Option<CentralPoint> {
+        self.central_point(self.hours(jd))
+    }
+
+    ///
+    /// Time of the greatest eclipse [h]
+    ///
+    fn greatest_time(&self) -> Result<f64> {
+        let f = |t: f64| Ok(self.gamma(t));
+        Ok(golden_section(f, -SPAN, SPAN, ACCURACY)?.0)
+    }
+
+    ///
+    /// Times of the beginning and the end of the central eclipse [h], moved
+    /// inside by the accuracy so that the shadow axis meets the Earth
+    ///
+    fn central_limits(&self, t: f64) -> Result<(f64, f64)> {
+        let f = |t: f64| Ok(self.intersection(t).0);
+
+        Ok((
+            pegasus(f, t - SPAN, t, ACCURACY)? + ACCURACY,
+            pegasus(f, t, t + SPAN, ACCURACY)? - ACCURACY
+        ))
+    }
+
+    ///
+    /// central_path: Points of the central line from the beginning to the
+    /// end of the central eclipse with the step [d], which must be positive,
+    /// None for a partial eclipse
+    ///
+    pub fn central_path(&self, step: f64) -> Result<Option<Vec<CentralPoint>>> {
+        if step <= 0.0 {
+            return Err(Error::InvalidArgumentError("step"));
+        }
+
+        let t = self.greatest_time()?;
+        if self.intersection(t).0 < 0.0 {
+            return Ok(None);
+        }
+
+        let (begin, end) = self.central_limits(t)?;
+        let step = step * 24.0;
+        let count = ((end - begin) / step).floor() as usize;
+
+        let mut path = Vec::with_capacity(count + 2);
+        path.extend((0..=count).filter_map(
+            |k| self.central_point(begin + step * k as f64)
+        ));
+        if end - begin - step * count as f64 > ACCURACY {
+            path.extend(self.central_point(end));
+        }
+
+        Ok(Some(path))
+    }
+
+    ///
+    /// global: Circumstances of the greatest eclipse, None when the penumbra
+    /// misses the Earth
+    ///
+    pub fn global(&self) -> Result<Option<GlobalCircumstances>> {
+        let t = self.greatest_time()?;
+        let gamma = self.gamma(t);
+        let (discriminant, _, r) = self.intersection(t);
+
+        if discriminant >= 0.0 {
+            let shadow = self.shadow(r, t);
+            let (begin, end) = self.central_limits(t)?;
+            let l2 = |t: f64| {
+                let (_, _, r) = self.intersection(t);
+                self.shadow(r, t).l2
+            };
+
+            let kind = if shadow.l2 < 0.0 {
+                if l2(begin) > 0.0 || l2(end) > 0.0 {
+                    SolarEclipseType::Hybrid
+                } else {
+                    SolarEclipseType::Total
+                }
+            } else if l2(begin) < 0.0 || l2(end) < 0.0 {
+                SolarEclipseType::Hybrid
+            } else {
+                SolarEclipseType::Annular
+            };
+
+            let (longitude, latitude) = geodetic(r);
+            return Ok(Some(GlobalCircumstances {
+                kind,
+                greatest: self.date(t),
+                gamma,
+                magnitude: (shadow.l1 - shadow.l2) / (shadow.l1 + shadow.l2),
+                longitude,
+                latitude,
+                duration: Some(
+                    2.0 * shadow.l2.abs() / self.speed(r, t) * 3600.0
+                )
+            }));
+        }
+
+        // Point of the Earth's limb nearest to the shadow axis
+        let (x, y) = (self.x.value(t), self.y.value(t));
+        let m = self.rotation(t).t();
+        let limb = m * Vec3D::cartesian(x / gamma, y / gamma, 0.0);
+        let s: SphericalVec3D = limb.into();
+        let (sp, cp) = s.theta().sin_cos();
+        let ba = 1.0 - F_EARTH;
+        let radius = ba / (ba * ba * cp * cp + sp * sp).sqrt();
+        let limb = limb * radius;
+
+        let shadow = self.shadow(limb, t);
+        let distance = shadow.distance();
+        if distance >= shadow.l1 {
+            return Ok(None);
+        }
+
+        // The umbra or the antumbra may touch the Earth without its axis
+        let kind = if distance >= shadow.l2.abs() {
+            SolarEclipseType::Partial
+        } else if shadow.l2 < 0.0 {
+            SolarEclipseType::Total
+        } else {
+            SolarEclipseType::Annular
+        };
+
+        let (longitude, latitude) = geodetic(limb);
+        Ok(Some(GlobalCircumstances {
+            kind,
+            greatest: self.date(t),
+            gamma,
+            magnitude: (shadow.l1 - distance) / (shadow.l1 + shadow.l2),
+            longitude,
+            latitude,
+            duration: None
+        }))
+    }
+
+    fn contact(&self, r: Vec3D, t: f64) -> Contact {
+        let shadow = self.shadow(r, t);
+        let p = shadow.u.atan2(shadow.v).fmod(PI2);
+        let q = shadow.xi.atan2(shadow.eta);
+
+        Contact {
+            jd: self.date(t),
+            position_angle: Angle::from(p),
+            vertex_angle: Angle::from((p - q).fmod(PI2))
+        }
+    }
+
+    ///
+    /// local: Circumstances of the eclipse for the observer, None when the
+    /// observer is outside the penumbra during the whole eclipse. The
+    /// circumstances are computed regardless of the altitude of the Sun.
+    ///
+    pub fn local(&self,
+                 observer: &Observer) -> Result<Option<LocalCircumstances>> {
+        let r = earth_fixed(observer);
+
+        let (t, distance) = golden_section(
+            |t| Ok(self.shadow(r, t).distance()), -SPAN, SPAN, ACCURACY
+        )?;
+        let shadow = self.shadow(r, t);
+        if distance >= shadow.l1 {
+            return Ok(None);
+        }
+
+        let penumbra = |t: f64| {
+            let s = self.shadow(r, t);
+            Ok(s.distance() - s.l1)
+        };
+        let umbra = |t: f64| {
+            let s = self.shadow(r, t);
+            Ok(s.distance() - s.l2.abs())
+        };
+
+        let first = pegasus(penumbra, t - SPAN, t, ACCURACY)?;
+        let fourth = pegasus(penumbra, t, t + SPAN, ACCURACY)?;
+
+        let (kind, second, third) = if distance < shadow.l2.abs() {
+            let kind = if shadow.l2 < 0.0 {
+                SolarEclipseType::Total
+            } else {
+                SolarEclipseType::Annular
+            };
+            let second = pegasus(umbra, first, t, ACCURACY)?;
+            let third = pegasus(umbra, t, fourth, ACCURACY)?;
+
+            (
+                kind,
+                Some(self.contact(r, second)),
+                Some(self.contact(r, third))
+            )
+        } else {
+            (SolarEclipseType::Partial, None, None)
+        };
+
+        let sun = self.rotation(t).t() * Vec3D::unit_z();
+        let altitude = sun.dot(zenith(observer)).asin();
+
+        let radius = (shadow.l1 + shadow.l2) / 2.0;
+        let moon = (shadow.l1 - shadow.l2) / 2.0;
+
+        Ok(Some(LocalCircumstances {
+            kind,
+            first: self.contact(r, first),
+            second,
+            maximum: self.contact(r, t),
+            third,
+            fourth: self.contact(r, fourth),
+            magnitude: (shadow.l1 - distance) / (shadow.l1 + shadow.l2),
+            obscuration: covered_fraction(radius, moon, distance),
+            altitude: Angle::from(altitude)
+        }))
+    }
+}
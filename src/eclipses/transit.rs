@@ -0,0 +1,346 @@
+use crate::base::Result;
+use crate::base::angle::{angular_radius, Angle};
+use crate::base::error::Error;
+use crate::base::linalg::{Mat3D, Norm, Vec3D};
+use crate::base::roots::{golden_section, pegasus};
+use crate::ephemeris::{apparent, Body, Ephemeris};
+use crate::events::{Category, EventIterator, EventKind};
+use crate::frames::sidereal::gast;
+use crate::frames::topocentric::observer_position;
+use crate::observer::Observer;
+use crate::time::JulianDate;
+
+use super::{position_angle, zenith};
+
+///
+/// Half-width of the search interval around the inferior conjunction [d]
+///
+const SPAN: f64 = 0.5;
+
+///
+/// Largest shift of the local contacts from the geocentric ones [d]
+///
+const PARALLAX_SPAN: f64 = 0.05;
+
+///
+/// Accuracy of the times [d]
+///
+const ACCURACY: f64 = 1e-7;
+
+///
+/// Separation of the centres of the Sun and of the planet and semidiameters
+/// of the Sun and of the planet [rad]
+///
+struct Disks {
+    distance: f64,
+    sun: f64,
+    planet: f64
+}
+
+///
+/// Apparent positions of the Sun and of the planet referred to the true
+/// equator and equinox of the date for the observer, geocentric when the
+/// observer is None [AU]
+///
+fn positions<E>(ephemeris: &E, body: Body, observer: Option<&Observer>,
+                jd: JulianDate) -> Result<(Vec3D, Vec3D)>
+    where E: Ephemeris + ?Sized
+{
+    let sun = apparent(ephemeris, Body::Sun, jd)?;
+    let planet = apparent(ephemeris, body, jd)?;
+
+    Ok(match observer {
+        Some(observer) => {
+            let r = observer_position(observer, jd);
+            (sun - r, planet - r)
+        },
+        None => (sun, planet)
+    })
+}
+
+fn disks<E>(ephemeris: &E, body: Body, observer: Option<&Observer>,
+            jd: JulianDate) -> Result<Disks>
+    where E: Ephemeris + ?Sized
+{
+    let (sun, planet) = positions(ephemeris, body, observer, jd)?;
+
+    Ok(Disks {
+        distance: sun.separation(planet),
+        sun: angular_radius(Body::Sun.radius(), sun.norm()),
+        planet: angular_radius(body.radius(), planet.norm())
+    })
+}
+
+///
+/// Dates of the contacts I to IV
+///
+type Contacts = (f64, Option<f64>, Option<f64>, f64);
+
+///
+/// Contacts of the disks in the intervals before and after the TT date of
+/// the least separation: the exterior contacts (I, IV) and the interior
+/// contacts (II, III) when the planet is entirely on the disk of the Sun
+///
+fn contacts<F>(at: F, t: f64, before: (f64, f64),
+               after: (f64, f64)) -> Result<Contacts>
+    where F: Fn(f64) -> Result<Disks>
+{
+    let exterior = |x: f64| {
+        let d = at(x)?;
+        Ok(d.distance - d.sun - d.planet)
+    };
+    let interior = |x: f64| {
+        let d = at(x)?;
+        Ok(d.distance - d.sun + d.planet)
+    };
+
+    let greatest = at(t)?;
+    let (second, third) =
+        if greatest.distance < greatest.sun - greatest.planet {
+            (
+                Some(pegasus(interior, before.0, before.1, ACCURACY)?),
+                Some(pegasus(interior, after.0, after.1, ACCURACY)?)
+            )
+        } else {
+            (None, None)
+        };
+
+    Ok((
+        pegasus(exterior, before.0, before.1, ACCURACY)?,
+        second,
+        third,
+        pegasus(exterior, after.0, after.1, ACCURACY)?
+    ))
+}
+
+///
+/// Transit: Geocentric circumstances of a transit of Mercury or Venus
+/// across the disk of the Sun: the TT dates of the exterior contacts (I,
+/// IV), of the interior contacts (II, III) and of the greatest transit and
+/// the least separation of the centres of the Sun and of the planet
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Transit {
+    body: Body,
+    first: JulianDate,
+    second: Option<JulianDate>,
+    greatest: JulianDate,
+    third: Option<JulianDate>,
+    fourth: JulianDate,
+    separation: Angle
+}
+
+///
+/// Contact: TT date of a contact for an observer with the position angle of
+/// the planet from the centre of the Sun (from the north towards the east)
+/// and the altitude of the Sun
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Contact {
+    jd: JulianDate,
+    position_angle: Angle,
+    altitude: Angle
+}
+
+impl Contact {
+    pub fn jd(&self) -> JulianDate {
+        self.jd
+    }
+
+    pub fn position_angle(&self) -> Angle {
+        self.position_angle
+    }
+
+    pub fn altitude(&self) -> Angle {
+        self.altitude
+    }
+}
+
+///
+/// LocalTransit: Circumstances of a transit for an observer. The contacts
+/// are given regardless of the altitude of the Sun.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LocalTransit {
+    first: Contact,
+    second: Option<Contact>,
+    greatest: Contact,
+    third: Option<Contact>,
+    fourth: Contact,
+    separation: Angle
+}
+
+impl LocalTransit {
+    pub fn first(&self) -> Contact {
+        self.first
+    }
+
+    pub fn second(&self) -> Option<Contact> {
+        self.second
+    }
+
+    pub fn greatest(&self) -> Contact {
+        self.greatest
+    }
+
+    pub fn third(&self) -> Option<Contact> {
+        self.third
+    }
+
+    pub fn fourth(&self) -> Contact {
+        self.fourth
+    }
+
+    pub fn separation(&self) -> Angle {
+        self.separation
+    }
+}
+
+impl Transit {
+    ///
+    /// new: Computes the transit of Mercury or Venus at the inferior
+    /// conjunction near the TT date, None when the planet misses the disk of
+    /// the Sun
+    ///
+    pub fn new<E>(ephemeris: &E, body: Body,
+                  jd: JulianDate) -> Result<Option<Transit>>
+        where E: Ephemeris + ?Sized
+    {
+        if body != Body::Mercury && body != Body::Venus {
+            return Err(Error::InvalidBodyError);
+        }
+
+        let at = |x: f64| disks(ephemeris, body, None, JulianDate::new(x));
+
+        let (t, _) = golden_section(
+            |x| Ok(at(x)?.distance), jd.jd() - SPAN, jd.jd() + SPAN, ACCURACY
+        )?;
+
+        let (sun, planet) = positions(ephemeris, body, None,
+                                      JulianDate::new(t))?;
+        let greatest = at(t)?;
+        if planet.norm() > sun.norm() ||
+            greatest.distance >= greatest.sun + greatest.planet {
+            return Ok(None);
+        }
+
+        let (first, second, third, fourth) =
+            contacts(at, t, (t - SPAN, t), (t, t + SPAN))?;
+
+        Ok(Some(Transit {
+            body,
+            first: JulianDate::new(first),
+            second: second.map(JulianDate::new),
+            greatest: JulianDate::new(t),
+            third: third.map(JulianDate::new),
+            fourth: JulianDate::new(fourth),
+            separation: Angle::from(greatest.distance)
+        }))
+    }
+
+    pub fn body(&self) -> Body {
+        self.body
+    }
+
+    pub fn first(&self) -> JulianDate {
+        self.first
+    }
+
+    pub fn second(&self) -> Option<JulianDate> {
+        self.second
+    }
+
+    pub fn greatest(&self) -> JulianDate {
+        self.greatest
+    }
+
+    pub fn third(&self) -> Option<JulianDate> {
+        self.third
+    }
+
+    pub fn fourth(&self) -> JulianDate {
+        self.fourth
+    }
+
+    pub fn separation(&self) -> Angle {
+        self.separation
+    }
+
+    ///
+    /// local: Circumstances of the transit for the observer, None when the
+    /// planet misses the disk of the Sun for the observer
+    ///
+    pub fn local<E>(&self, ephemeris: &E,
+                    observer: &Observer) -> Result<Option<LocalTransit>>
+        where E: Ephemeris + ?Sized
+    {
+        let at = |x: f64| {
+            disks(ephemeris, self.body, Some(observer), JulianDate::new(x))
+        };
+        let first = self.first.jd() - PARALLAX_SPAN;
+        let fourth = self.fourth.jd() + PARALLAX_SPAN;
+
+        let (t, _) = golden_section(|x| Ok(at(x)?.distance), first, fourth,
+                                    ACCURACY)?;
+        let greatest = at(t)?;
+        if greatest.distance >= greatest.sun + greatest.planet {
+            return Ok(None);
+        }
+
+        let (c1, c2, c3, c4) = contacts(at, t, (first, t), (t, fourth))?;
+
+        let contact = |x: f64| -> Result<Contact> {
+            let jd = JulianDate::new(x);
+            let (sun, planet) = positions(ephemeris, self.body,
+                                          Some(observer), jd)?;
+            let up = Mat3D::r_z(gast(jd.tt_to_ut())).t() *
+                zenith(observer);
+
+            Ok(Contact {
+                jd,
+                position_angle: Angle::from(position_angle(sun, planet)),
+                altitude: Angle::from((sun.dot(up) / sun.norm()).asin())
+            })
+        };
+
+        Ok(Some(LocalTransit {
+            first: contact(c1)?,
+            second: c2.map(contact).transpose()?,
+            greatest: contact(t)?,
+            third: c3.map(contact).transpose()?,
+            fourth: contact(c4)?,
+            separation: Angle::from(greatest.distance)
+        }))
+    }
+}
+
+///
+/// transits: Transits of Mercury or Venus with the greatest transit between
+/// the TT dates
+///
+pub fn transits<E>(ephemeris: &E, body: Body, start: JulianDate,
+                   end: JulianDate) -> Result<Vec<Transit>>
+    where E: Ephemeris + ?Sized
+{
+    if body != Body::Mercury && body != Body::Venus {
+        return Err(Error::InvalidBodyError);
+    }
+
+    let mut transits = Vec::new();
+
+    let conjunctions = EventIterator::new(ephemeris, start - SPAN, end + SPAN)
+        .with_categories(&[Category::Conjunctions])
+        .with_bodies(&[Body::Sun, body]);
+    for event in conjunctions {
+        let event = event?;
+        if let EventKind::Conjunction(..) = event.kind() {
+            if let Some(transit) = Transit::new(ephemeris, body, event.jd())? {
+                if transit.greatest >= start && transit.greatest < end {
+                    transits.push(transit);
+                }
+            }
+        }
+    }
+
+    Ok(transits)
+}
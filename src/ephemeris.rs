@@ -0,0 +1,292 @@
+pub mod kepler;
+pub mod moon;
+pub mod state;
+pub mod vsop87;
+
+use crate::astrometry::aberration;
+use crate::astrometry::light_time::LightTime;
+use crate::base::Result;
+use crate::base::consts::{ARCS, AU, R_EARTH, R_MOON, R_SUN};
+use crate::base::error::Error;
+use crate::base::linalg::{Mat3D, Norm, Vec3D};
+use crate::frames::nutation::{nutation, nutation_matrix};
+use crate::frames::precession::{
+    frame_bias_matrix, obliquity, precession_matrix
+};
+use crate::time::JulianDate;
+
+///
+/// Mean obliquity of the ecliptic at J2000 (IAU 2006) ["]
+///
+const OBLIQUITY_J2000: f64 = 84381.406;
+
+///
+/// Step of the numerical differentiation of the positions [d]
+///
+const DIFFERENTIATION_STEP: f64 = 0.01;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Body {
+    Sun,
+    Mercury,
+    Venus,
+    Earth,
+    Moon,
+    Mars,
+    Jupiter,
+    Saturn,
+    Uranus,
+    Neptune
+}
+
+impl Body {
+    pub const PLANETS: [Body; 7] = [
+        Body::Mercury,
+        Body::Venus,
+        Body::Mars,
+        Body::Jupiter,
+        Body::Saturn,
+        Body::Uranus,
+        Body::Neptune
+    ];
+
+    ///
+    /// radius: Equatorial radius of the body [km]
+    ///
+    pub fn radius(&self) -> f64 {
+        match *self {
+            Body::Sun => R_SUN,
+            Body::Mercury => 2439.7,
+            Body::Venus => 6051.8,
+            Body::Earth => R_EARTH,
+            Body::Moon => R_MOON,
+            Body::Mars => 3396.19,
+            Body::Jupiter => 71492.0,
+            Body::Saturn => 60268.0,
+            Body::Uranus => 25559.0,
+            Body::Neptune => 24764.0
+        }
+    }
+
+    ///
+    /// mass_ratio: Ratio of the mass of the Sun to the mass of the body
+    ///
+    pub fn mass_ratio(&self) -> f64 {
+        match *self {
+            Body::Sun => 1.0,
+            Body::Mercury => 6023600.0,
+            Body::Venus => 408523.71,
+            Body::Earth => 332946.050895,
+            Body::Moon => 27068703.24,
+            Body::Mars => 3098708.0,
+            Body::Jupiter => 1047.3486,
+            Body::Saturn => 3497.898,
+            Body::Uranus => 22902.98,
+            Body::Neptune => 19412.24
+        }
+    }
+}
+
+///
+/// Ephemeris: Source of the positions and the velocities of the solar system
+/// bodies
+///
+pub trait Ephemeris {
+    ///
+    /// state: Barycentric position [AU] and velocity [AU/d] of the body
+    /// referred to the ICRS for the TDB date
+    ///
+    fn state(&self, body: Body, jd: JulianDate) -> Result<(Vec3D, Vec3D)>;
+
+    ///
+    /// position: Barycentric position of the body referred to the ICRS [AU]
+    ///
+    fn position(&self, body: Body, jd: JulianDate) -> Result<Vec3D> {
+        Ok(self.state(body, jd)?.0)
+    }
+}
+
+///
+/// AnalyticEphemeris: Low precision analytical ephemeris. Mercury, Venus and
+/// the Earth use the abridged VSOP87 theory (about 1"), the Moon the abridged
+/// ELP-2000/82 theory (about 10"), the other planets the mean Keplerian
+/// elements valid from 1800 to 2050 (from 25" for Mars to 10' for Saturn).
+///
+#[derive(Debug, Copy, Clone, Default)]
+pub struct AnalyticEphemeris;
+
+impl Ephemeris for AnalyticEphemeris {
+    fn state(&self, body: Body, jd: JulianDate) -> Result<(Vec3D, Vec3D)> {
+        let (sun, sun_velocity) = self.sun(jd);
+
+        match body {
+            Body::Sun => Ok((sun, sun_velocity)),
+            Body::Earth => {
+                let (r, v) = self.earth(jd);
+                Ok((sun + r, sun_velocity + v))
+            },
+            Body::Moon => {
+                let (r, v) = self.earth(jd);
+                let (rm, vm) = self.moon(jd);
+                Ok((sun + r + rm, sun_velocity + v + vm))
+            },
+            _ => {
+                let (r, v) = self.planet(body, jd)?;
+                Ok((sun + r, sun_velocity + v))
+            }
+        }
+    }
+}
+
+impl AnalyticEphemeris {
+    pub fn new() -> AnalyticEphemeris {
+        AnalyticEphemeris
+    }
+
+    fn elements(body: Body) -> Result<&'static kepler::Elements> {
+        match body {
+            Body::Mercury => Ok(&kepler::MERCURY),
+            Body::Venus => Ok(&kepler::VENUS),
+            Body::Mars => Ok(&kepler::MARS),
+            Body::Jupiter => Ok(&kepler::JUPITER),
+            Body::Saturn => Ok(&kepler::SATURN),
+            Body::Uranus => Ok(&kepler::URANUS),
+            Body::Neptune => Ok(&kepler::NEPTUNE),
+            _ => Err(Error::InvalidBodyError)
+        }
+    }
+
+    ///
+    /// Heliocentric state of a planet referred to the ICRS
+    ///
+    fn planet(&self, body: Body, jd: JulianDate) -> Result<(Vec3D, Vec3D)> {
+        let series = match body {
+            Body::Mercury => &vsop87::MERCURY,
+            Body::Venus => &vsop87::VENUS,
+            _ => return self.mean_planet(body, jd)
+        };
+
+        Ok((
+            heliocentric(series, jd),
+            differentiate(|t| heliocentric(series, t), jd)
+        ))
+    }
+
+    ///
+    /// Heliocentric state of a planet from its mean Keplerian elements
+    ///
+    fn mean_planet(&self, body: Body,
+                   jd: JulianDate) -> Result<(Vec3D, Vec3D)> {
+        let (r, v) = AnalyticEphemeris::elements(body)?.state(jd);
+        let m = Mat3D::r_x(-OBLIQUITY_J2000 / ARCS);
+
+        Ok((m * r, m * v))
+    }
+
+    ///
+    /// Barycentric state of the Sun from the mean orbits of the planets
+    ///
+    fn sun(&self, jd: JulianDate) -> (Vec3D, Vec3D) {
+        let mut mass = 1.0;
+        let mut r = Vec3D::zero();
+        let mut v = Vec3D::zero();
+
+        let mut add = |ratio: f64, (rp, vp): (Vec3D, Vec3D)| {
+            mass += 1.0 / ratio;
+            r += rp * (1.0 / ratio);
+            v += vp * (1.0 / ratio);
+        };
+
+        for body in Body::PLANETS.iter() {
+            if let Ok(state) = self.mean_planet(*body, jd) {
+                add(body.mass_ratio(), state);
+            }
+        }
+
+        let m = Mat3D::r_x(-OBLIQUITY_J2000 / ARCS);
+        let (re, ve) = kepler::EARTH_MOON.state(jd);
+        let ratio = 1.0 / (1.0 / Body::Earth.mass_ratio() +
+            1.0 / Body::Moon.mass_ratio());
+        add(ratio, (m * re, m * ve));
+
+        (r * (-1.0 / mass), v * (-1.0 / mass))
+    }
+
+    fn earth(&self, jd: JulianDate) -> (Vec3D, Vec3D) {
+        (
+            heliocentric(&vsop87::EARTH, jd),
+            differentiate(|t| heliocentric(&vsop87::EARTH, t), jd)
+        )
+    }
+
+    ///
+    /// Geocentric position of the Moon referred to the ICRS
+    ///
+    fn moon_position(&self, jd: JulianDate) -> Vec3D {
+        let (l, b, r) = moon::position(jd);
+        let ecliptic = Vec3D::spherical(r / AU, l, b).unwrap_or_default();
+
+        precession_matrix(jd).t() * Mat3D::r_x(-obliquity(jd)) * ecliptic
+    }
+
+    fn moon(&self, jd: JulianDate) -> (Vec3D, Vec3D) {
+        (
+            self.moon_position(jd),
+            differentiate(|t| self.moon_position(t), jd)
+        )
+    }
+}
+
+///
+/// Heliocentric position of a planet from its VSOP87 series referred to the
+/// ICRS
+///
+fn heliocentric(series: &vsop87::Series, jd: JulianDate) -> Vec3D {
+    let (l, b, r) = series.position(jd);
+    let ecliptic = Vec3D::spherical(r, l, b).unwrap_or_default();
+
+    precession_matrix(jd).t() * Mat3D::r_x(-obliquity(jd)) * ecliptic
+}
+
+fn differentiate<F>(f: F, jd: JulianDate) -> Vec3D
+    where F: Fn(JulianDate) -> Vec3D
+{
+    let h = DIFFERENTIATION_STEP;
+    (f(jd + h) - f(jd - h)) * (0.5 / h)
+}
+
+///
+/// apparent: Geocentric apparent position of the body referred to the true
+/// equator and equinox of the TT date [AU]. The position is corrected for
+/// the light time and for the annual aberration.
+///
+pub fn apparent<E>(ephemeris: &E, body: Body, jd: JulianDate) -> Result<Vec3D>
+    where E: Ephemeris + ?Sized
+{
+    if body == Body::Earth {
+        return Err(Error::InvalidBodyError);
+    }
+
+    let (earth, velocity) = ephemeris.state(Body::Earth, jd)?;
+
+    let r = LightTime::new(ephemeris, earth, body, jd)?.position();
+    let distance = r.norm();
+    let r = aberration::classical((r / distance)?, velocity)? * distance;
+
+    let npb = nutation_matrix(jd) * precession_matrix(jd) * frame_bias_matrix();
+    Ok(npb * r)
+}
+
+///
+/// apparent_ecliptic: Geocentric apparent position of the body referred to
+/// the true ecliptic and equinox of the TT date [AU]
+///
+pub fn apparent_ecliptic<E>(ephemeris: &E, body: Body,
+                            jd: JulianDate) -> Result<Vec3D>
+    where E: Ephemeris + ?Sized
+{
+    let r = apparent(ephemeris, body, jd)?;
+    let (_, deps) = nutation(jd);
+
+    Ok(Mat3D::r_x(obliquity(jd) + deps) * r)
+}
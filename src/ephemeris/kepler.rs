@@ -0,0 +1,282 @@
+use crate::base::{Real, Result};
+use crate::base::angle::Angle;
+use crate::base::consts::{JULIAN_CENTURY, PI2, RAD};
+use crate::base::error::Error;
+use crate::base::linalg::{CartesianVec3D, Mat3D, Norm, Vec3D};
+use crate::time::JulianDate;
+
+const MAX_ITERATIONS: usize = 30;
+
+///
+/// Eccentricity below which an orbit is taken as circular
+///
+const ECCENTRICITY_EPS: f64 = 1e-12;
+
+///
+/// Elements: Mean orbital elements of a planet referred to the ecliptic and
+/// equinox of J2000 (E. M. Standish, "Keplerian Elements for Approximate
+/// Positions of the Major Planets", valid 1800 AD - 2050 AD). Each element
+/// is given with its rate per Julian century: semi-major axis [AU],
+/// eccentricity, inclination, mean longitude, longitude of the perihelion and
+/// longitude of the ascending node [deg].
+///
+#[derive(Debug, Copy, Clone)]
+pub struct Elements {
+    a: (f64, f64),
+    e: (f64, f64),
+    i: (f64, f64),
+    l: (f64, f64),
+    peri: (f64, f64),
+    node: (f64, f64),
+}
+
+pub const MERCURY: Elements = Elements {
+    a: (0.38709927, 0.00000037),
+    e: (0.20563593, 0.00001906),
+    i: (7.00497902, -0.00594749),
+    l: (252.25032350, 149472.67411175),
+    peri: (77.45779628, 0.16047689),
+    node: (48.33076593, -0.12534081),
+};
+
+pub const VENUS: Elements = Elements {
+    a: (0.72333566, 0.00000390),
+    e: (0.00677672, -0.00004107),
+    i: (3.39467605, -0.00078890),
+    l: (181.97909950, 58517.81538729),
+    peri: (131.60246718, 0.00268329),
+    node: (76.67984255, -0.27769418),
+};
+
+pub const EARTH_MOON: Elements = Elements {
+    a: (1.00000261, 0.00000562),
+    e: (0.01671123, -0.00004392),
+    i: (-0.00001531, -0.01294668),
+    l: (100.46457166, 35999.37244981),
+    peri: (102.93768193, 0.32327364),
+    node: (0.0, 0.0),
+};
+
+pub const MARS: Elements = Elements {
+    a: (1.52371034, 0.00001847),
+    e: (0.09339410, 0.00007882),
+    i: (1.84969142, -0.00813131),
+    l: (-4.55343205, 19140.30268499),
+    peri: (-23.94362959, 0.44441088),
+    node: (49.55953891, -0.29257343),
+};
+
+pub const JUPITER: Elements = Elements {
+    a: (5.20288700, -0.00011607),
+    e: (0.04838624, -0.00013253),
+    i: (1.30439695, -0.00183714),
+    l: (34.39644051, 3034.74612775),
+    peri: (14.72847983, 0.21252668),
+    node: (100.47390909, 0.20469106),
+};
+
+pub const SATURN: Elements = Elements {
+    a: (9.53667594, -0.00125060),
+    e: (0.05386179, -0.00050991),
+    i: (2.48599187, 0.00193609),
+    l: (49.95424423, 1222.49362201),
+    peri: (92.59887831, -0.41897216),
+    node: (113.66242448, -0.28867794),
+};
+
+pub const URANUS: Elements = Elements {
+    a: (19.18916464, -0.00196176),
+    e: (0.04725744, -0.00004397),
+    i: (0.77263783, -0.00242939),
+    l: (313.23810451, 428.48202785),
+    peri: (170.95427630, 0.40805281),
+    node: (74.01692503, 0.04240589),
+};
+
+pub const NEPTUNE: Elements = Elements {
+    a: (30.06992276, 0.00026291),
+    e: (0.00859048, 0.00005105),
+    i: (1.77004347, 0.00035372),
+    l: (-55.12002969, 218.45945325),
+    peri: (44.96476227, -0.32241464),
+    node: (131.78422574, -0.00508664),
+};
+
+///
+/// eccentric_anomaly: Solves Kepler's equation for the mean anomaly m and
+/// the eccentricity e [rad]
+///
+pub fn eccentric_anomaly(m: f64, e: f64) -> f64 {
+    let m = m.fmod(PI2);
+    let mut ea = if e < 0.8 { m } else { std::f64::consts::PI };
+
+    for _ in 0..MAX_ITERATIONS {
+        let delta = (ea - e * ea.sin() - m) / (1.0 - e * ea.cos());
+        ea -= delta;
+
+        if delta.abs() < 1e-14 {
+            break;
+        }
+    }
+
+    ea
+}
+
+impl Elements {
+    ///
+    /// state: Heliocentric position [AU] and velocity [AU/d] referred to the
+    /// ecliptic and equinox of J2000 for the TT date
+    ///
+    pub fn state(&self, jd: JulianDate) -> (Vec3D, Vec3D) {
+        let t = jd.centuries();
+        let value = |(v, dv): (f64, f64)| v + dv * t;
+
+        let a = value(self.a);
+        let e = value(self.e);
+        let i = value(self.i) * RAD;
+        let peri = value(self.peri) * RAD;
+        let node = value(self.node) * RAD;
+        let m = value(self.l) * RAD - peri;
+        let n = self.l.1 * RAD / JULIAN_CENTURY;
+
+        let ea = eccentric_anomaly(m, e);
+        let (s, c) = ea.sin_cos();
+        let fac = (1.0 - e * e).sqrt();
+        let k = n / (1.0 - e * c);
+
+        let r = Vec3D::cartesian(a * (c - e), a * fac * s, 0.0);
+        let v = Vec3D::cartesian(-a * k * s, a * k * fac * c, 0.0);
+
+        let orbit = Mat3D::r_z(-node) * Mat3D::r_x(-i) * Mat3D::r_z(node - peri);
+        (orbit * r, orbit * v)
+    }
+}
+
+///
+/// OrbitalElements: Osculating elements of an elliptic orbit: semi-major
+/// axis, eccentricity, inclination, longitude of the ascending node,
+/// argument of the periapsis and mean anomaly, referred to the frame of the
+/// state vector they were computed from
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OrbitalElements {
+    a: f64,
+    e: f64,
+    i: Angle,
+    node: Angle,
+    argument: Angle,
+    m: Angle
+}
+
+impl OrbitalElements {
+    pub fn new(a: f64, e: f64, i: Angle, node: Angle, argument: Angle,
+               m: Angle) -> OrbitalElements {
+        OrbitalElements { a, e, i, node, argument, m }
+    }
+
+    ///
+    /// from_state: Elements of the position and the velocity for the
+    /// gravitational parameter in the same units. The node and the argument
+    /// of the periapsis are zero for the equatorial and the circular orbits.
+    /// The parabolic, hyperbolic and rectilinear orbits are not supported.
+    ///
+    pub fn from_state(r: Vec3D, v: Vec3D, gm: f64) -> Result<OrbitalElements> {
+        let distance = r.norm();
+        let h = r.cross(v);
+        let energy = v.dot(v) / 2.0 - gm / distance;
+        if distance == 0.0 || h.norm() == 0.0 || energy >= 0.0 {
+            return Err(Error::ConversionError);
+        }
+
+        let a = -gm / (2.0 * energy);
+        let hc: CartesianVec3D = h.into();
+        let i = hc.x().hypot(hc.y()).atan2(hc.z());
+        let node = if hc.x() == 0.0 && hc.y() == 0.0 {
+            0.0
+        } else {
+            hc.x().atan2(-hc.y())
+        };
+
+        // Eccentricity vector and position in the plane of the orbit
+        let plane = Mat3D::r_x(i) * Mat3D::r_z(node);
+        let ev = (r * (v.dot(v) - gm / distance) - v * r.dot(v)) * (1.0 / gm);
+        let ev: CartesianVec3D = (plane * ev).into();
+        let rp: CartesianVec3D = (plane * r).into();
+
+        let e = ev.x().hypot(ev.y());
+        let argument = if e < ECCENTRICITY_EPS {
+            0.0
+        } else {
+            ev.y().atan2(ev.x())
+        };
+        let nu = rp.y().atan2(rp.x()) - argument;
+        let (s, c) = nu.sin_cos();
+        let ea = ((1.0 - e * e).sqrt() * s).atan2(e + c);
+        let m = ea - e * ea.sin();
+
+        Ok(OrbitalElements::new(
+            a, e, Angle::from(i), Angle::from(node.fmod(PI2)),
+            Angle::from(argument.fmod(PI2)), Angle::from(m.fmod(PI2))
+        ))
+    }
+
+    pub fn semi_major_axis(&self) -> f64 {
+        self.a
+    }
+
+    pub fn eccentricity(&self) -> f64 {
+        self.e
+    }
+
+    pub fn inclination(&self) -> Angle {
+        self.i
+    }
+
+    pub fn node(&self) -> Angle {
+        self.node
+    }
+
+    pub fn argument_of_periapsis(&self) -> Angle {
+        self.argument
+    }
+
+    pub fn mean_anomaly(&self) -> Angle {
+        self.m
+    }
+
+    ///
+    /// mean_motion: Mean motion for the gravitational parameter [rad per
+    /// unit of time]
+    ///
+    pub fn mean_motion(&self, gm: f64) -> f64 {
+        (gm / self.a.powi(3)).sqrt()
+    }
+
+    ///
+    /// period: Orbital period for the gravitational parameter
+    ///
+    pub fn period(&self, gm: f64) -> f64 {
+        PI2 / self.mean_motion(gm)
+    }
+
+    ///
+    /// state: Position and velocity for the gravitational parameter
+    ///
+    pub fn state(&self, gm: f64) -> (Vec3D, Vec3D) {
+        let (a, e) = (self.a, self.e);
+        let i: f64 = self.i.into();
+        let node: f64 = self.node.into();
+        let argument: f64 = self.argument.into();
+
+        let ea = eccentric_anomaly(self.m.into(), e);
+        let (s, c) = ea.sin_cos();
+        let fac = (1.0 - e * e).sqrt();
+        let k = self.mean_motion(gm) / (1.0 - e * c);
+
+        let r = Vec3D::cartesian(a * (c - e), a * fac * s, 0.0);
+        let v = Vec3D::cartesian(-a * k * s, a * k * fac * c, 0.0);
+
+        let orbit = Mat3D::r_z(-node) * Mat3D::r_x(-i) * Mat3D::r_z(-argument);
+        (orbit * r, orbit * v)
+    }
+}
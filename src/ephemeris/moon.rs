@@ -0,0 +1,199 @@
+use crate::base::Real;
+use crate::base::consts::{PI2, RAD};
+use crate::time::JulianDate;
+
+///
+/// Periodic terms for the longitude [1e-6 deg] and the distance [1e-3 km] of
+/// the Moon: multipliers of the arguments D, M, M' and F
+///
+const LONGITUDE_DISTANCE_TERMS: [(i8, i8, i8, i8, f64, f64); 60] = [
+    (0,  0,  1,  0, 6288774.0, -20905355.0),
+    (2,  0, -1,  0, 1274027.0,  -3699111.0),
+    (2,  0,  0,  0,  658314.0,  -2955968.0),
+    (0,  0,  2,  0,  213618.0,   -569925.0),
+    (0,  1,  0,  0, -185116.0,     48888.0),
+    (0,  0,  0,  2, -114332.0,     -3149.0),
+    (2,  0, -2,  0,   58793.0,    246158.0),
+    (2, -1, -1,  0,   57066.0,   -152138.0),
+    (2,  0,  1,  0,   53322.0,   -170733.0),
+    (2, -1,  0,  0,   45758.0,   -204586.0),
+    (0,  1, -1,  0,  -40923.0,   -129620.0),
+    (1,  0,  0,  0,  -34720.0,    108743.0),
+    (0,  1,  1,  0,  -30383.0,    104755.0),
+    (2,  0,  0, -2,   15327.0,     10321.0),
+    (0,  0,  1,  2,  -12528.0,         0.0),
+    (0,  0,  1, -2,   10980.0,     79661.0),
+    (4,  0, -1,  0,   10675.0,    -34782.0),
+    (0,  0,  3,  0,   10034.0,    -23210.0),
+    (4,  0, -2,  0,    8548.0,    -21636.0),
+    (2,  1, -1,  0,   -7888.0,     24208.0),
+    (2,  1,  0,  0,   -6766.0,     30824.0),
+    (1,  0, -1,  0,   -5163.0,     -8379.0),
+    (1,  1,  0,  0,    4987.0,    -16675.0),
+    (2, -1,  1,  0,    4036.0,    -12831.0),
+    (2,  0,  2,  0,    3994.0,    -10445.0),
+    (4,  0,  0,  0,    3861.0,    -11650.0),
+    (2,  0, -3,  0,    3665.0,     14403.0),
+    (0,  1, -2,  0,   -2689.0,     -7003.0),
+    (2,  0, -1,  2,   -2602.0,         0.0),
+    (2, -1, -2,  0,    2390.0,     10056.0),
+    (1,  0,  1,  0,   -2348.0,      6322.0),
+    (2, -2,  0,  0,    2236.0,     -9884.0),
+    (0,  1,  2,  0,   -2120.0,      5751.0),
+    (0,  2,  0,  0,   -2069.0,         0.0),
+    (2, -2, -1,  0,    2048.0,     -4950.0),
+    (2,  0,  1, -2,   -1773.0,      4130.0),
+    (2,  0,  0,  2,   -1595.0,         0.0),
+    (4, -1, -1,  0,    1215.0,     -3958.0),
+    (0,  0,  2,  2,   -1110.0,         0.0),
+    (3,  0, -1,  0,    -892.0,      3258.0),
+    (2,  1,  1,  0,    -810.0,      2616.0),
+    (4, -1, -2,  0,     759.0,     -1897.0),
+    (0,  2, -1,  0,    -713.0,     -2117.0),
+    (2,  2, -1,  0,    -700.0,      2354.0),
+    (2,  1, -2,  0,     691.0,         0.0),
+    (2, -1,  0, -2,     596.0,         0.0),
+    (4,  0,  1,  0,     549.0,     -1423.0),
+    (0,  0,  4,  0,     537.0,     -1117.0),
+    (4, -1,  0,  0,     520.0,     -1571.0),
+    (1,  0, -2,  0,    -487.0,     -1739.0),
+    (2,  1,  0, -2,    -399.0,         0.0),
+    (0,  0,  2, -2,    -381.0,     -4421.0),
+    (1,  1,  1,  0,     351.0,         0.0),
+    (3,  0, -2,  0,    -340.0,         0.0),
+    (4,  0, -3,  0,     330.0,         0.0),
+    (2, -1,  2,  0,     327.0,         0.0),
+    (0,  2,  1,  0,    -323.0,      1165.0),
+    (1,  1, -1,  0,     299.0,         0.0),
+    (2,  0,  3,  0,     294.0,         0.0),
+    (2,  0, -1, -2,       0.0,      8752.0),
+];
+
+///
+/// Periodic terms for the latitude of the Moon [1e-6 deg]: multipliers of
+/// the arguments D, M, M' and F
+///
+const LATITUDE_TERMS: [(i8, i8, i8, i8, f64); 60] = [
+    (0,  0,  0,  1, 5128122.0),
+    (0,  0,  1,  1,  280602.0),
+    (0,  0,  1, -1,  277693.0),
+    (2,  0,  0, -1,  173237.0),
+    (2,  0, -1,  1,   55413.0),
+    (2,  0, -1, -1,   46271.0),
+    (2,  0,  0,  1,   32573.0),
+    (0,  0,  2,  1,   17198.0),
+    (2,  0,  1, -1,    9266.0),
+    (0,  0,  2, -1,    8822.0),
+    (2, -1,  0, -1,    8216.0),
+    (2,  0, -2, -1,    4324.0),
+    (2,  0,  1,  1,    4200.0),
+    (2,  1,  0, -1,   -3359.0),
+    (2, -1, -1,  1,    2463.0),
+    (2, -1,  0,  1,    2211.0),
+    (2, -1, -1, -1,    2065.0),
+    (0,  1, -1, -1,   -1870.0),
+    (4,  0, -1, -1,    1828.0),
+    (0,  1,  0,  1,   -1794.0),
+    (0,  0,  0,  3,   -1749.0),
+    (0,  1, -1,  1,   -1565.0),
+    (1,  0,  0,  1,   -1491.0),
+    (0,  1,  1,  1,   -1475.0),
+    (0,  1,  1, -1,   -1410.0),
+    (0,  1,  0, -1,   -1344.0),
+    (1,  0,  0, -1,   -1335.0),
+    (0,  0,  3,  1,    1107.0),
+    (4,  0,  0, -1,    1021.0),
+    (4,  0, -1,  1,     833.0),
+    (0,  0,  1, -3,     777.0),
+    (4,  0, -2,  1,     671.0),
+    (2,  0,  0, -3,     607.0),
+    (2,  0,  2, -1,     596.0),
+    (2, -1,  1, -1,     491.0),
+    (2,  0, -2,  1,    -451.0),
+    (0,  0,  3, -1,     439.0),
+    (2,  0,  2,  1,     422.0),
+    (2,  0, -3, -1,     421.0),
+    (2,  1, -1,  1,    -366.0),
+    (2,  1,  0,  1,    -351.0),
+    (4,  0,  0,  1,     331.0),
+    (2, -1,  1,  1,     315.0),
+    (2, -2,  0, -1,     302.0),
+    (0,  0,  1,  3,    -283.0),
+    (2,  1,  1, -1,    -229.0),
+    (1,  1,  0, -1,     223.0),
+    (1,  1,  0,  1,     223.0),
+    (0,  1, -2, -1,    -220.0),
+    (2,  1, -1, -1,    -220.0),
+    (1,  0,  1,  1,    -185.0),
+    (2, -1, -2, -1,     181.0),
+    (0,  1,  2,  1,    -177.0),
+    (4,  0, -2, -1,     176.0),
+    (4, -1, -1, -1,     166.0),
+    (1,  0,  1, -1,    -164.0),
+    (4,  0,  1, -1,     132.0),
+    (1,  0, -1, -1,    -119.0),
+    (4, -1,  0, -1,     115.0),
+    (2, -2,  0,  1,     107.0),
+];
+
+///
+/// position: Geocentric ecliptic longitude, latitude [rad] and distance [km]
+/// of the Moon referred to the mean ecliptic and equinox of the TT date
+/// (abridged ELP-2000/82 theory after J. Meeus, "Astronomical Algorithms")
+///
+pub fn position(jd: JulianDate) -> (f64, f64, f64) {
+    let t = jd.centuries();
+
+    let lm = 218.3164477 + t * (481267.88123421 + t * (-0.0015786 +
+        t * (1.0 / 538841.0 - t / 65194000.0)));
+    let d = 297.8501921 + t * (445267.1114034 + t * (-0.0018819 +
+        t * (1.0 / 545868.0 - t / 113065000.0)));
+    let m = 357.5291092 + t * (35999.0502909 + t * (-0.0001536 +
+        t / 24490000.0));
+    let mm = 134.9633964 + t * (477198.8675055 + t * (0.0087414 +
+        t * (1.0 / 69699.0 - t / 14712000.0)));
+    let f = 93.2720950 + t * (483202.0175233 + t * (-0.0036539 +
+        t * (-1.0 / 3526000.0 + t / 863310000.0)));
+
+    let a1 = (119.75 + 131.849 * t) * RAD;
+    let a2 = (53.09 + 479264.290 * t) * RAD;
+    let a3 = (313.45 + 481266.484 * t) * RAD;
+    let e = 1.0 - t * (0.002516 + t * 0.0000074);
+
+    let (lm, d, m, mm, f) = (lm * RAD, d * RAD, m * RAD, mm * RAD, f * RAD);
+    let factor = |k: i8| match k.abs() {
+        1 => e,
+        2 => e * e,
+        _ => 1.0
+    };
+    let argument = |kd: i8, km: i8, kmm: i8, kf: i8| {
+        f64::from(kd) * d + f64::from(km) * m + f64::from(kmm) * mm +
+            f64::from(kf) * f
+    };
+
+    let mut sl = 0.0;
+    let mut sr = 0.0;
+    for &(kd, km, kmm, kf, cl, cr) in LONGITUDE_DISTANCE_TERMS.iter() {
+        let (s, c) = argument(kd, km, kmm, kf).sin_cos();
+        let k = factor(km);
+
+        sl += cl * k * s;
+        sr += cr * k * c;
+    }
+
+    let mut sb = 0.0;
+    for &(kd, km, kmm, kf, cb) in LATITUDE_TERMS.iter() {
+        sb += cb * factor(km) * argument(kd, km, kmm, kf).sin();
+    }
+
+    sl += 3958.0 * a1.sin() + 1962.0 * (lm - f).sin() + 318.0 * a2.sin();
+    sb += -2235.0 * lm.sin() + 382.0 * a3.sin() + 175.0 * (a1 - f).sin() +
+        175.0 * (a1 + f).sin() + 127.0 * (lm - mm).sin() -
+        115.0 * (lm + mm).sin();
+
+    (
+        (lm + sl * 1e-6 * RAD).fmod(PI2),
+        sb * 1e-6 * RAD,
+        385000.56 + sr * 1e-3
+    )
+}
@@ -0,0 +1,228 @@
+use std::ops;
+
+use crate::base::Result;
+use crate::base::error::Error;
+use crate::base::linalg::{Mat3D, Vec3D};
+use crate::ephemeris::kepler::OrbitalElements;
+use crate::frames::rotating::RotatingFrame;
+use crate::time::JulianDate;
+
+///
+/// Frame: Reference frame of a state vector
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Frame {
+    Icrs,
+    EclipticJ2000,
+    MeanEquatorOfDate,
+    TrueEquatorOfDate,
+    EclipticOfDate,
+    Itrs
+}
+
+///
+/// StateVector: Position and velocity of a body, optionally tagged with
+/// the date and the reference frame they refer to. The sums and the
+/// differences fail if both operands are tagged with different epochs or
+/// frames and keep the tags of either operand, the rotations clear the
+/// frame.
+///
+#[derive(Debug, Copy, Clone)]
+pub struct StateVector {
+    position: Vec3D,
+    velocity: Vec3D,
+    epoch: Option<JulianDate>,
+    frame: Option<Frame>
+}
+
+impl StateVector {
+    pub fn new(position: Vec3D, velocity: Vec3D) -> StateVector {
+        StateVector {
+            position,
+            velocity,
+            epoch: None,
+            frame: None
+        }
+    }
+
+    pub fn zero() -> StateVector {
+        StateVector::new(Vec3D::zero(), Vec3D::zero())
+    }
+
+    ///
+    /// from_elements: State vector of the osculating elements for the
+    /// gravitational parameter
+    ///
+    pub fn from_elements(elements: &OrbitalElements, gm: f64) -> StateVector {
+        let (position, velocity) = elements.state(gm);
+        StateVector::new(position, velocity)
+    }
+
+    pub fn with_epoch(mut self, epoch: JulianDate) -> Self {
+        self.epoch = Some(epoch);
+        self
+    }
+
+    pub fn with_frame(mut self, frame: Frame) -> Self {
+        self.frame = Some(frame);
+        self
+    }
+
+    pub fn position(&self) -> Vec3D {
+        self.position
+    }
+
+    pub fn velocity(&self) -> Vec3D {
+        self.velocity
+    }
+
+    pub fn epoch(&self) -> Option<JulianDate> {
+        self.epoch
+    }
+
+    pub fn frame(&self) -> Option<Frame> {
+        self.frame
+    }
+
+    ///
+    /// rotate: State vector in the frame of the rotation matrix
+    ///
+    pub fn rotate(&self, rotation: Mat3D, frame: Frame) -> StateVector {
+        (rotation * *self).with_frame(frame)
+    }
+
+    ///
+    /// transform: State vector in the rotating frame. The time unit of the
+    /// derivative of the rotation has to be the one of the velocity.
+    ///
+    pub fn transform(&self, rotating: &RotatingFrame,
+                     frame: Frame) -> StateVector {
+        (*rotating * *self).with_frame(frame)
+    }
+
+    ///
+    /// to_elements: Osculating elements of the elliptic orbit for the
+    /// gravitational parameter in the units of the state vector
+    ///
+    pub fn to_elements(&self, gm: f64) -> Result<OrbitalElements> {
+        OrbitalElements::from_state(self.position, self.velocity, gm)
+    }
+
+    fn with_tags(self, rhs: &StateVector) -> Result<StateVector> {
+        Ok(StateVector {
+            epoch: merge_tags(self.epoch, rhs.epoch)?,
+            frame: merge_tags(self.frame, rhs.frame)?,
+            ..self
+        })
+    }
+}
+
+///
+/// Common tag of two operands, which fails if both are tagged differently
+///
+pub(crate) fn merge_tags<T>(lhs: Option<T>,
+                            rhs: Option<T>) -> Result<Option<T>>
+    where T: PartialEq
+{
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) if lhs != rhs => Err(Error::TagMismatchError),
+        (lhs, rhs) => Ok(lhs.or(rhs))
+    }
+}
+
+impl From<(Vec3D, Vec3D)> for StateVector {
+    fn from((position, velocity): (Vec3D, Vec3D)) -> Self {
+        StateVector::new(position, velocity)
+    }
+}
+
+impl From<StateVector> for (Vec3D, Vec3D) {
+    fn from(state: StateVector) -> Self {
+        (state.position, state.velocity)
+    }
+}
+
+impl ops::Neg for StateVector {
+    type Output = Self;
+
+    fn neg(self) -> StateVector {
+        StateVector {
+            position: self.position * -1.0,
+            velocity: self.velocity * -1.0,
+            ..self
+        }
+    }
+}
+
+impl ops::Add for StateVector {
+    type Output = Result<StateVector>;
+
+    fn add(self, rhs: StateVector) -> Self::Output {
+        StateVector {
+            position: self.position + rhs.position,
+            velocity: self.velocity + rhs.velocity,
+            ..self
+        }.with_tags(&rhs)
+    }
+}
+
+impl ops::Sub for StateVector {
+    type Output = Result<StateVector>;
+
+    fn sub(self, rhs: StateVector) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl ops::Mul<f64> for StateVector {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> StateVector {
+        StateVector {
+            position: self.position * rhs,
+            velocity: self.velocity * rhs,
+            ..self
+        }
+    }
+}
+
+impl ops::Mul<StateVector> for f64 {
+    type Output = StateVector;
+
+    fn mul(self, rhs: StateVector) -> StateVector {
+        rhs * self
+    }
+}
+
+impl ops::MulAssign<f64> for StateVector {
+    fn mul_assign(&mut self, rhs: f64) {
+        *self = *self * rhs;
+    }
+}
+
+impl ops::Mul<StateVector> for Mat3D {
+    type Output = StateVector;
+
+    fn mul(self, rhs: StateVector) -> StateVector {
+        StateVector {
+            position: self * rhs.position,
+            velocity: self * rhs.velocity,
+            epoch: rhs.epoch,
+            frame: None
+        }
+    }
+}
+
+impl ops::Mul<StateVector> for RotatingFrame {
+    type Output = StateVector;
+
+    fn mul(self, rhs: StateVector) -> StateVector {
+        let (position, velocity) = self.transform(rhs.position, rhs.velocity);
+        StateVector {
+            position,
+            velocity,
+            epoch: rhs.epoch,
+            frame: None
+        }
+    }
+}
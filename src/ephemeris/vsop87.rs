@@ -0,0 +1,653 @@
+// Some phases of the series happen to be close to pi
+#![allow(clippy::approx_constant)]
+
+use crate::base::Real;
+use crate::base::consts::{JD_J2000, PI2};
+use crate::time::JulianDate;
+
+///
+/// Term of a VSOP87 series: amplitude [1e-8], phase [rad] and frequency
+/// [rad per Julian millennium]
+///
+type Term = (f64, f64, f64);
+
+///
+/// Series: Coefficients of the polynomial in time for the heliocentric
+/// longitude, latitude and radius vector
+///
+pub struct Series {
+    l: &'static [&'static [Term]],
+    b: &'static [&'static [Term]],
+    r: &'static [&'static [Term]],
+}
+
+fn evaluate(series: &[&[Term]], tau: f64) -> f64 {
+    let mut result = 0.0;
+
+    for terms in series.iter().rev() {
+        let sum: f64 = terms.iter()
+            .map(|&(a, b, c)| a * (b + c * tau).cos())
+            .sum();
+        result = result * tau + sum;
+    }
+
+    result * 1e-8
+}
+
+impl Series {
+    ///
+    /// position: Heliocentric ecliptic longitude, latitude [rad] and radius
+    /// vector [AU] referred to the mean ecliptic and equinox of the TT date
+    ///
+    pub fn position(&self, jd: JulianDate) -> (f64, f64, f64) {
+        let tau = (jd.jd() - JD_J2000) / 365250.0;
+
+        (
+            evaluate(self.l, tau).fmod(PI2),
+            evaluate(self.b, tau),
+            evaluate(self.r, tau)
+        )
+    }
+}
+
+///
+/// Abridged VSOP87D series of Mercury
+///
+pub const MERCURY: Series = Series {
+    l: &[MERCURY_L0, MERCURY_L1, MERCURY_L2, MERCURY_L3, MERCURY_L4, MERCURY_L5],
+    b: &[MERCURY_B0, MERCURY_B1, MERCURY_B2, MERCURY_B3, MERCURY_B4],
+    r: &[MERCURY_R0, MERCURY_R1, MERCURY_R2, MERCURY_R3],
+};
+
+const MERCURY_L0: &[Term] = &[
+    (440250710.0, 0.0, 0.0),
+    (40989415.0, 1.48302034, 26087.90314157),
+    (5046294.0, 4.4778549, 52175.8062831),
+    (855347.0, 1.165203, 78263.709425),
+    (165590.0, 4.119692, 104351.612566),
+    (34562.0, 0.77931, 130439.51571),
+    (7583.0, 3.7135, 156527.4188),
+    (3560.0, 1.5120, 1109.3786),
+    (1803.0, 4.1033, 5661.3320),
+    (1726.0, 0.3583, 182615.3220),
+    (1590.0, 2.9951, 25028.5212),
+    (1365.0, 4.5992, 27197.2817),
+    (1017.0, 0.8803, 31749.2352),
+    (714.0, 1.541, 24978.525),
+    (644.0, 5.303, 21535.950),
+    (451.0, 6.050, 51116.424),
+    (404.0, 3.282, 208703.225),
+    (352.0, 5.242, 20426.571),
+    (345.0, 2.792, 15874.618),
+    (343.0, 5.765, 955.600),
+    (339.0, 5.863, 25558.212),
+    (325.0, 1.337, 53285.185),
+    (273.0, 2.495, 529.691),
+    (264.0, 3.917, 57837.138),
+    (260.0, 0.987, 4551.953),
+    (239.0, 0.113, 1059.382),
+    (235.0, 0.267, 11322.664),
+    (217.0, 0.660, 13521.751),
+    (209.0, 2.092, 47623.853),
+    (183.0, 2.629, 27043.503),
+    (182.0, 2.434, 25661.305),
+    (176.0, 4.536, 51066.428),
+    (173.0, 2.452, 24498.830),
+    (142.0, 3.360, 37410.567),
+    (138.0, 0.291, 10213.286),
+    (125.0, 3.721, 39609.655),
+    (118.0, 2.781, 77204.327),
+    (106.0, 4.206, 19804.827),
+];
+
+const MERCURY_L1: &[Term] = &[
+    (2608814706223.0, 0.0, 0.0),
+    (1126008.0, 6.2170397, 26087.9031416),
+    (303471.0, 3.055655, 52175.806283),
+    (80538.0, 6.10455, 78263.70942),
+    (21245.0, 2.83532, 104351.61257),
+    (5592.0, 5.8268, 130439.5157),
+    (1472.0, 2.5185, 156527.4188),
+    (388.0, 5.480, 182615.322),
+    (352.0, 3.052, 1109.379),
+    (103.0, 2.149, 208703.225),
+    (94.0, 6.12, 27197.28),
+    (91.0, 0.00, 24978.52),
+    (52.0, 5.62, 5661.33),
+    (44.0, 4.57, 25028.52),
+    (28.0, 3.04, 51066.43),
+    (27.0, 5.09, 234791.13),
+];
+
+const MERCURY_L2: &[Term] = &[
+    (53050.0, 0.0, 0.0),
+    (16904.0, 4.69072, 26087.90314),
+    (7397.0, 1.3474, 52175.8063),
+    (3018.0, 4.4564, 78263.7094),
+    (1107.0, 1.2623, 104351.6126),
+    (378.0, 4.320, 130439.516),
+    (123.0, 1.069, 156527.419),
+    (39.0, 4.08, 182615.32),
+    (15.0, 4.63, 1109.38),
+    (12.0, 0.79, 208703.23),
+];
+
+const MERCURY_L3: &[Term] = &[
+    (188.0, 0.035, 52175.806),
+    (142.0, 3.125, 26087.903),
+    (97.0, 3.00, 78263.71),
+    (44.0, 6.02, 104351.61),
+    (35.0, 0.0, 0.0),
+    (18.0, 2.78, 130439.52),
+    (7.0, 5.82, 156527.42),
+    (3.0, 2.57, 182615.32),
+];
+
+const MERCURY_L4: &[Term] = &[
+    (114.0, 3.1416, 0.0),
+    (2.0, 2.03, 26087.90),
+    (2.0, 1.42, 78263.71),
+    (2.0, 4.50, 52175.81),
+    (1.0, 4.50, 104351.61),
+    (1.0, 1.27, 130439.52),
+];
+
+const MERCURY_L5: &[Term] = &[
+    (1.0, 3.14, 0.0),
+];
+
+const MERCURY_B0: &[Term] = &[
+    (11737529.0, 1.98357499, 26087.90314157),
+    (2388077.0, 5.0373896, 52175.8062831),
+    (1222840.0, 3.1415927, 0.0),
+    (543252.0, 1.796444, 78263.709425),
+    (129779.0, 4.832325, 104351.612566),
+    (31867.0, 1.58088, 130439.51571),
+    (7963.0, 4.6097, 156527.4188),
+    (2014.0, 1.3532, 182615.3220),
+    (514.0, 4.378, 208703.225),
+    (209.0, 2.020, 24978.525),
+    (208.0, 4.918, 27197.282),
+    (132.0, 1.119, 234791.128),
+    (121.0, 1.813, 53285.185),
+    (100.0, 5.657, 20426.571),
+];
+
+const MERCURY_B1: &[Term] = &[
+    (429151.0, 3.501698, 26087.903142),
+    (146234.0, 3.141593, 0.0),
+    (22675.0, 0.01515, 52175.80628),
+    (10895.0, 0.48540, 78263.70942),
+    (6353.0, 3.4294, 104351.6126),
+    (2496.0, 0.1605, 130439.5157),
+    (860.0, 3.185, 156527.419),
+    (278.0, 6.210, 182615.322),
+    (86.0, 2.95, 208703.23),
+    (28.0, 0.29, 27197.28),
+    (26.0, 5.98, 234791.13),
+];
+
+const MERCURY_B2: &[Term] = &[
+    (11831.0, 4.79066, 26087.90314),
+    (1914.0, 0.0, 0.0),
+    (1045.0, 1.2122, 52175.8063),
+    (266.0, 4.434, 78263.709),
+    (170.0, 1.623, 104351.613),
+    (96.0, 4.80, 130439.52),
+    (45.0, 1.61, 156527.42),
+    (18.0, 4.67, 182615.32),
+    (7.0, 1.43, 208703.23),
+];
+
+const MERCURY_B3: &[Term] = &[
+    (235.0, 0.354, 26087.903),
+    (161.0, 0.0, 0.0),
+    (19.0, 4.36, 52175.81),
+    (6.0, 2.51, 78263.71),
+    (5.0, 6.14, 104351.61),
+    (3.0, 3.14, 156527.42),
+    (2.0, 6.27, 130439.52),
+];
+
+const MERCURY_B4: &[Term] = &[
+    (4.0, 1.75, 26087.90),
+    (1.0, 3.14, 0.0),
+];
+
+const MERCURY_R0: &[Term] = &[
+    (39528272.0, 0.0, 0.0),
+    (7834132.0, 6.1923372, 26087.9031416),
+    (795526.0, 2.959897, 52175.806283),
+    (121282.0, 6.010642, 78263.709425),
+    (21922.0, 2.77820, 104351.61257),
+    (4354.0, 5.8289, 130439.5157),
+    (918.0, 2.597, 156527.419),
+    (290.0, 1.424, 25028.521),
+    (260.0, 3.028, 27197.282),
+    (202.0, 5.647, 182615.322),
+    (201.0, 5.592, 31749.235),
+    (142.0, 6.253, 24978.525),
+    (100.0, 3.734, 21535.950),
+];
+
+const MERCURY_R1: &[Term] = &[
+    (217348.0, 4.656172, 26087.903142),
+    (44142.0, 1.42386, 52175.80628),
+    (10094.0, 4.47466, 78263.70942),
+    (2433.0, 1.2423, 104351.6126),
+    (1624.0, 0.0, 0.0),
+    (604.0, 4.293, 130439.516),
+    (153.0, 1.061, 156527.419),
+    (39.0, 4.11, 182615.32),
+];
+
+const MERCURY_R2: &[Term] = &[
+    (3118.0, 3.0823, 26087.9031),
+    (1245.0, 6.1518, 52175.8063),
+    (425.0, 2.926, 78263.709),
+    (136.0, 5.980, 104351.613),
+    (42.0, 2.75, 130439.52),
+    (22.0, 3.14, 0.0),
+    (13.0, 5.80, 156527.42),
+];
+
+const MERCURY_R3: &[Term] = &[
+    (33.0, 1.68, 26087.90),
+    (24.0, 4.63, 52175.81),
+    (12.0, 1.39, 78263.71),
+    (5.0, 4.44, 104351.61),
+    (2.0, 1.21, 130439.52),
+];
+
+///
+/// Abridged VSOP87D series of Venus
+///
+pub const VENUS: Series = Series {
+    l: &[VENUS_L0, VENUS_L1, VENUS_L2, VENUS_L3, VENUS_L4, VENUS_L5],
+    b: &[VENUS_B0, VENUS_B1, VENUS_B2, VENUS_B3, VENUS_B4],
+    r: &[VENUS_R0, VENUS_R1, VENUS_R2, VENUS_R3, VENUS_R4],
+};
+
+const VENUS_L0: &[Term] = &[
+    (317614667.0, 0.0, 0.0),
+    (1353968.0, 5.5931332, 10213.2855462),
+    (89892.0, 5.30650, 20426.57109),
+    (5477.0, 4.4163, 7860.4194),
+    (3456.0, 2.6996, 11790.6291),
+    (2372.0, 2.9938, 3930.2097),
+    (1664.0, 4.2502, 1577.3435),
+    (1438.0, 4.1575, 9683.5946),
+    (1317.0, 5.1867, 26.2983),
+    (1201.0, 6.1536, 30639.8566),
+    (769.0, 0.816, 9437.763),
+    (761.0, 1.950, 529.691),
+    (708.0, 1.065, 775.523),
+    (585.0, 3.998, 191.448),
+    (500.0, 4.123, 15720.839),
+    (429.0, 3.586, 19367.189),
+    (327.0, 5.677, 5507.553),
+    (326.0, 4.591, 10404.734),
+    (232.0, 3.163, 9153.904),
+    (180.0, 4.653, 1109.379),
+    (155.0, 5.570, 19651.048),
+    (128.0, 4.226, 20.775),
+    (128.0, 0.962, 5661.332),
+    (106.0, 1.537, 801.821),
+];
+
+const VENUS_L1: &[Term] = &[
+    (1021352943053.0, 0.0, 0.0),
+    (95708.0, 2.46424, 10213.28555),
+    (14445.0, 0.51625, 20426.57109),
+    (213.0, 1.795, 30639.857),
+    (174.0, 2.655, 26.298),
+    (152.0, 6.106, 1577.344),
+    (82.0, 5.70, 191.45),
+    (70.0, 2.68, 9437.76),
+    (52.0, 3.60, 775.52),
+    (38.0, 1.03, 529.69),
+    (30.0, 1.25, 5507.55),
+    (25.0, 6.11, 10404.73),
+];
+
+const VENUS_L2: &[Term] = &[
+    (54127.0, 0.0, 0.0),
+    (3891.0, 0.3451, 10213.2855),
+    (1338.0, 2.0201, 20426.5711),
+    (24.0, 2.05, 26.30),
+    (19.0, 3.54, 30639.86),
+    (10.0, 3.97, 775.52),
+    (7.0, 1.52, 1577.34),
+    (6.0, 1.00, 191.45),
+];
+
+const VENUS_L3: &[Term] = &[
+    (136.0, 4.804, 10213.286),
+    (78.0, 3.67, 20426.57),
+    (26.0, 0.0, 0.0),
+];
+
+const VENUS_L4: &[Term] = &[
+    (114.0, 3.1416, 0.0),
+    (3.0, 5.21, 20426.57),
+    (2.0, 2.51, 10213.29),
+];
+
+const VENUS_L5: &[Term] = &[
+    (1.0, 3.14, 0.0),
+];
+
+const VENUS_B0: &[Term] = &[
+    (5923638.0, 0.2670278, 10213.2855462),
+    (40108.0, 1.14737, 20426.57109),
+    (32815.0, 3.14159, 0.0),
+    (1011.0, 1.0895, 30639.8566),
+    (149.0, 6.254, 18073.705),
+    (138.0, 0.860, 1577.344),
+    (130.0, 3.672, 9437.763),
+    (120.0, 3.705, 2352.866),
+    (108.0, 4.539, 22003.915),
+];
+
+const VENUS_B1: &[Term] = &[
+    (513348.0, 1.803643, 10213.285546),
+    (4380.0, 3.3862, 20426.5711),
+    (199.0, 0.0, 0.0),
+    (197.0, 2.530, 30639.857),
+];
+
+const VENUS_B2: &[Term] = &[
+    (22378.0, 3.38509, 10213.28555),
+    (282.0, 0.0, 0.0),
+    (173.0, 5.256, 20426.571),
+    (27.0, 3.87, 30639.86),
+];
+
+const VENUS_B3: &[Term] = &[
+    (647.0, 4.992, 10213.286),
+    (20.0, 3.14, 0.0),
+    (6.0, 0.77, 20426.57),
+    (3.0, 5.44, 30639.86),
+];
+
+const VENUS_B4: &[Term] = &[
+    (14.0, 0.32, 10213.29),
+];
+
+const VENUS_R0: &[Term] = &[
+    (72334821.0, 0.0, 0.0),
+    (489824.0, 4.021518, 10213.285546),
+    (1658.0, 4.9021, 20426.5711),
+    (1632.0, 2.8455, 7860.4194),
+    (1378.0, 1.1285, 11790.6291),
+    (498.0, 2.587, 9683.595),
+    (374.0, 1.423, 3930.210),
+    (264.0, 5.529, 9437.763),
+    (237.0, 2.551, 15720.839),
+    (222.0, 2.013, 19367.189),
+    (126.0, 2.728, 1577.344),
+    (119.0, 3.020, 10404.734),
+];
+
+const VENUS_R1: &[Term] = &[
+    (34551.0, 0.89199, 10213.28555),
+    (234.0, 1.772, 20426.571),
+    (234.0, 3.142, 0.0),
+];
+
+const VENUS_R2: &[Term] = &[
+    (1407.0, 5.0637, 10213.2856),
+    (16.0, 5.47, 20426.57),
+    (13.0, 0.0, 0.0),
+];
+
+const VENUS_R3: &[Term] = &[
+    (50.0, 3.22, 10213.29),
+];
+
+const VENUS_R4: &[Term] = &[
+    (1.0, 0.92, 10213.29),
+];
+
+///
+/// Abridged VSOP87D series of the Earth
+///
+pub const EARTH: Series = Series {
+    l: &[EARTH_L0, EARTH_L1, EARTH_L2, EARTH_L3, EARTH_L4, EARTH_L5],
+    b: &[EARTH_B0, EARTH_B1],
+    r: &[EARTH_R0, EARTH_R1, EARTH_R2, EARTH_R3, EARTH_R4],
+};
+
+const EARTH_L0: &[Term] = &[
+    (175347046.0, 0.0, 0.0),
+    (3341656.0, 4.6692568, 6283.0758500),
+    (34894.0, 4.62610, 12566.15170),
+    (3497.0, 2.7441, 5753.3849),
+    (3418.0, 2.8289, 3.5231),
+    (3136.0, 3.6277, 77713.7715),
+    (2676.0, 4.4181, 7860.4194),
+    (2343.0, 6.1352, 3930.2097),
+    (1324.0, 0.7425, 11506.7698),
+    (1273.0, 2.0371, 529.6910),
+    (1199.0, 1.1096, 1577.3435),
+    (990.0, 5.233, 5884.927),
+    (902.0, 2.045, 26.298),
+    (857.0, 3.508, 398.149),
+    (780.0, 1.179, 5223.694),
+    (753.0, 2.533, 5507.553),
+    (505.0, 4.583, 18849.228),
+    (492.0, 4.205, 775.523),
+    (357.0, 2.920, 0.067),
+    (317.0, 5.849, 11790.629),
+    (284.0, 1.899, 796.298),
+    (271.0, 0.315, 10977.079),
+    (243.0, 0.345, 5486.778),
+    (206.0, 4.806, 2544.314),
+    (205.0, 1.869, 5573.143),
+    (202.0, 2.458, 6069.777),
+    (156.0, 0.833, 213.299),
+    (132.0, 3.411, 2942.463),
+    (126.0, 1.083, 20.775),
+    (115.0, 0.645, 0.980),
+    (103.0, 0.636, 4694.003),
+    (102.0, 0.976, 15720.839),
+    (102.0, 4.267, 7.114),
+    (99.0, 6.21, 2146.17),
+    (98.0, 0.68, 155.42),
+    (86.0, 5.98, 161000.69),
+    (85.0, 1.30, 6275.96),
+    (85.0, 3.67, 71430.70),
+    (80.0, 1.81, 17260.15),
+    (79.0, 3.04, 12036.46),
+    (75.0, 1.76, 5088.63),
+    (74.0, 3.50, 3154.69),
+    (74.0, 4.68, 801.82),
+    (70.0, 0.83, 9437.76),
+    (62.0, 3.98, 8827.39),
+    (61.0, 1.82, 7084.90),
+    (57.0, 2.78, 6286.60),
+    (56.0, 4.39, 14143.50),
+    (56.0, 3.47, 6279.55),
+    (52.0, 0.19, 12139.55),
+    (52.0, 1.33, 1748.02),
+    (51.0, 0.28, 5856.48),
+    (49.0, 0.49, 1194.45),
+    (41.0, 5.37, 8429.24),
+    (41.0, 2.40, 19651.05),
+    (39.0, 6.17, 10447.39),
+    (37.0, 6.04, 10213.29),
+    (37.0, 2.57, 1059.38),
+    (36.0, 1.71, 2352.87),
+    (36.0, 1.78, 6812.77),
+    (33.0, 0.59, 17789.85),
+    (30.0, 0.44, 83996.85),
+    (30.0, 2.74, 1349.87),
+    (25.0, 3.16, 4690.48),
+];
+
+const EARTH_L1: &[Term] = &[
+    (628331966747.0, 0.0, 0.0),
+    (206059.0, 2.678235, 6283.075850),
+    (4303.0, 2.6351, 12566.1517),
+    (425.0, 1.590, 3.523),
+    (119.0, 5.796, 26.298),
+    (109.0, 2.966, 1577.344),
+    (93.0, 2.59, 18849.23),
+    (72.0, 1.14, 529.69),
+    (68.0, 1.87, 398.15),
+    (67.0, 4.41, 5507.55),
+    (59.0, 2.89, 5223.69),
+    (56.0, 2.17, 155.42),
+    (45.0, 0.40, 796.30),
+    (36.0, 0.47, 775.52),
+    (29.0, 2.65, 7.11),
+    (21.0, 5.34, 0.98),
+    (19.0, 1.85, 5486.78),
+    (19.0, 4.97, 213.30),
+    (17.0, 2.99, 6275.96),
+    (16.0, 0.03, 2544.31),
+    (16.0, 1.43, 2146.17),
+    (15.0, 1.21, 10977.08),
+    (12.0, 2.83, 1748.02),
+    (12.0, 3.26, 5088.63),
+    (12.0, 5.27, 1194.45),
+    (12.0, 2.08, 4694.00),
+    (11.0, 0.77, 553.57),
+    (10.0, 1.30, 6286.60),
+    (10.0, 4.24, 1349.87),
+    (9.0, 2.70, 242.73),
+    (9.0, 5.64, 951.72),
+    (8.0, 5.30, 2352.87),
+    (6.0, 2.65, 9437.76),
+    (6.0, 4.67, 4690.48),
+];
+
+const EARTH_L2: &[Term] = &[
+    (52919.0, 0.0, 0.0),
+    (8720.0, 1.0721, 6283.0758),
+    (309.0, 0.867, 12566.152),
+    (27.0, 0.05, 3.52),
+    (16.0, 5.19, 26.30),
+    (16.0, 3.68, 155.42),
+    (10.0, 0.76, 18849.23),
+    (9.0, 2.06, 77713.77),
+    (7.0, 0.83, 775.52),
+    (5.0, 4.66, 1577.34),
+    (4.0, 1.03, 7.11),
+    (4.0, 3.44, 5573.14),
+    (3.0, 5.14, 796.30),
+    (3.0, 6.05, 5507.55),
+    (3.0, 1.19, 242.73),
+    (3.0, 6.12, 529.69),
+    (3.0, 0.31, 398.15),
+    (3.0, 2.28, 553.57),
+    (2.0, 4.38, 5223.69),
+    (2.0, 3.75, 0.98),
+];
+
+const EARTH_L3: &[Term] = &[
+    (289.0, 5.844, 6283.076),
+    (35.0, 0.0, 0.0),
+    (17.0, 5.49, 12566.15),
+    (3.0, 5.20, 155.42),
+    (1.0, 4.72, 3.52),
+    (1.0, 5.30, 18849.23),
+    (1.0, 5.97, 242.73),
+];
+
+const EARTH_L4: &[Term] = &[
+    (114.0, 3.142, 0.0),
+    (8.0, 4.13, 6283.08),
+    (1.0, 3.84, 12566.15),
+];
+
+const EARTH_L5: &[Term] = &[
+    (1.0, 3.14, 0.0),
+];
+
+const EARTH_B0: &[Term] = &[
+    (280.0, 3.199, 84334.662),
+    (102.0, 5.422, 5507.553),
+    (80.0, 3.88, 5223.69),
+    (44.0, 3.70, 2352.87),
+    (32.0, 4.00, 1577.34),
+];
+
+const EARTH_B1: &[Term] = &[
+    (9.0, 3.90, 5507.55),
+    (6.0, 1.73, 5223.69),
+];
+
+const EARTH_R0: &[Term] = &[
+    (100013989.0, 0.0, 0.0),
+    (1670700.0, 3.0984635, 6283.0758500),
+    (13956.0, 3.05525, 12566.15170),
+    (3084.0, 5.1985, 77713.7715),
+    (1628.0, 1.1739, 5753.3849),
+    (1576.0, 2.8469, 7860.4194),
+    (925.0, 5.453, 11506.770),
+    (542.0, 4.564, 3930.210),
+    (472.0, 3.661, 5884.927),
+    (346.0, 0.964, 5507.553),
+    (329.0, 5.900, 5223.694),
+    (307.0, 0.299, 5573.143),
+    (243.0, 4.273, 11790.629),
+    (212.0, 5.847, 1577.344),
+    (186.0, 5.022, 10977.079),
+    (175.0, 3.012, 18849.228),
+    (110.0, 5.055, 5486.778),
+    (98.0, 0.89, 6069.78),
+    (86.0, 5.69, 15720.84),
+    (86.0, 1.27, 161000.69),
+    (65.0, 0.27, 17260.15),
+    (63.0, 0.92, 529.69),
+    (57.0, 2.01, 83996.85),
+    (56.0, 5.24, 71430.70),
+    (49.0, 3.25, 2544.31),
+    (47.0, 2.58, 775.52),
+    (45.0, 5.54, 9437.76),
+    (43.0, 6.01, 6275.96),
+    (39.0, 5.36, 4694.00),
+    (38.0, 2.39, 8827.39),
+    (37.0, 0.83, 19651.05),
+    (37.0, 4.90, 12139.55),
+    (36.0, 1.67, 12036.46),
+    (35.0, 1.84, 2942.46),
+    (33.0, 0.24, 7084.90),
+    (32.0, 0.18, 5088.63),
+    (32.0, 1.78, 398.15),
+    (28.0, 1.21, 6286.60),
+    (28.0, 1.90, 6279.55),
+    (26.0, 4.59, 10447.39),
+];
+
+const EARTH_R1: &[Term] = &[
+    (103019.0, 1.107490, 6283.075850),
+    (1721.0, 1.0644, 12566.1517),
+    (702.0, 3.142, 0.0),
+    (32.0, 1.02, 18849.23),
+    (31.0, 2.84, 5507.55),
+    (25.0, 1.32, 5223.69),
+    (18.0, 1.42, 1577.34),
+    (10.0, 5.91, 10977.08),
+    (9.0, 1.42, 6275.96),
+    (9.0, 0.27, 5486.78),
+];
+
+const EARTH_R2: &[Term] = &[
+    (4359.0, 5.7846, 6283.0758),
+    (124.0, 5.579, 12566.152),
+    (12.0, 3.14, 0.0),
+    (9.0, 3.63, 77713.77),
+    (6.0, 1.87, 5573.14),
+    (3.0, 5.47, 18849.23),
+];
+
+const EARTH_R3: &[Term] = &[
+    (145.0, 4.273, 6283.076),
+    (7.0, 3.92, 12566.15),
+];
+
+const EARTH_R4: &[Term] = &[
+    (4.0, 2.56, 6283.08),
+];
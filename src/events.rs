@@ -0,0 +1,563 @@
+use std::cmp::Ordering;
+use std::f64::consts::{FRAC_PI_2, PI};
+use std::iter;
+
+use crate::base::Result;
+use crate::base::angle::{angular_radius, wrap};
+use crate::base::consts::RAD;
+use crate::base::linalg::{Norm, SphericalVec3D, Vec3D};
+use crate::base::roots::{golden_section, pegasus};
+use crate::eclipses::lunar::{LunarEclipse, LunarEclipseType, ShadowRule};
+use crate::eclipses::solar::{BesselianElements, SolarEclipseType};
+use crate::ephemeris::{apparent, apparent_ecliptic, Body, Ephemeris};
+use crate::frames::sidereal::gast;
+use crate::frames::topocentric::horizontal_parallax;
+use crate::observer::Observer;
+use crate::time::JulianDate;
+
+///
+/// Length of the scanning window [d]
+///
+const WINDOW: f64 = 1.0;
+
+///
+/// Largest distance of an event from the window where it was found [d]
+///
+const MARGIN: f64 = 0.5;
+
+///
+/// Accuracy of the event times [d]
+///
+const ACCURACY: f64 = 1e-6;
+
+///
+/// Step of the altitude tabulation for the rising and setting [d]
+///
+const RISE_SET_STEP: f64 = 1.0 / 24.0;
+
+///
+/// Step of the numerical differentiation of the lunar distance [d]
+///
+const DIFFERENTIATION_STEP: f64 = 0.01;
+
+///
+/// Standard altitudes of the upper limb of the Sun and of the centre of a
+/// planet at the rising and the setting, including the refraction [rad]
+///
+const SUN_ALTITUDE: f64 = -0.8333 * RAD;
+const PLANET_ALTITUDE: f64 = -0.5667 * RAD;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Phase {
+    NewMoon,
+    FirstQuarter,
+    FullMoon,
+    LastQuarter
+}
+
+impl Phase {
+    const ALL: [Phase; 4] = [
+        Phase::NewMoon,
+        Phase::FirstQuarter,
+        Phase::FullMoon,
+        Phase::LastQuarter
+    ];
+
+    ///
+    /// elongation: Difference of the ecliptic longitudes of the Moon and of
+    /// the Sun at the phase [rad]
+    ///
+    pub fn elongation(&self) -> f64 {
+        match *self {
+            Phase::NewMoon => 0.0,
+            Phase::FirstQuarter => FRAC_PI_2,
+            Phase::FullMoon => PI,
+            Phase::LastQuarter => 3.0 * FRAC_PI_2
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Season {
+    MarchEquinox,
+    JuneSolstice,
+    SeptemberEquinox,
+    DecemberSolstice
+}
+
+impl Season {
+    const ALL: [Season; 4] = [
+        Season::MarchEquinox,
+        Season::JuneSolstice,
+        Season::SeptemberEquinox,
+        Season::DecemberSolstice
+    ];
+
+    ///
+    /// longitude: Apparent ecliptic longitude of the Sun at the beginning of
+    /// the season [rad]
+    ///
+    pub fn longitude(&self) -> f64 {
+        match *self {
+            Season::MarchEquinox => 0.0,
+            Season::JuneSolstice => FRAC_PI_2,
+            Season::SeptemberEquinox => PI,
+            Season::DecemberSolstice => 3.0 * FRAC_PI_2
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EventKind {
+    Phase(Phase),
+    Rise(Body),
+    Set(Body),
+    Conjunction(Body, Body),
+    Season(Season),
+    SolarEclipse(SolarEclipseType),
+    LunarEclipse(LunarEclipseType),
+    Perigee,
+    Apogee
+}
+
+///
+/// Event: Astronomical event with its TT date
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Event {
+    jd: JulianDate,
+    kind: EventKind
+}
+
+impl Event {
+    pub fn new(jd: JulianDate, kind: EventKind) -> Event {
+        Event { jd, kind }
+    }
+
+    pub fn jd(&self) -> JulianDate {
+        self.jd
+    }
+
+    pub fn kind(&self) -> EventKind {
+        self.kind
+    }
+}
+
+///
+/// Category: Group of the events searched by the iterator
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Category {
+    Phases,
+    RisesSets,
+    Conjunctions,
+    Seasons,
+    Eclipses,
+    Apsides
+}
+
+impl Category {
+    pub const ALL: [Category; 6] = [
+        Category::Phases,
+        Category::RisesSets,
+        Category::Conjunctions,
+        Category::Seasons,
+        Category::Eclipses,
+        Category::Apsides
+    ];
+}
+
+///
+/// EventIterator: Lazy iterator over the astronomical events between two
+/// TT dates. The interval is scanned day by day as the iterator advances and
+/// the events are yielded in the time order. Rises and sets are searched
+/// only when an observer is given, conjunctions are searched for every pair
+/// of the bodies except the Sun and the Moon.
+///
+pub struct EventIterator<'a, E> where E: Ephemeris + ?Sized {
+    ephemeris: &'a E,
+    start: JulianDate,
+    end: JulianDate,
+    scanned: JulianDate,
+    categories: Vec<Category>,
+    bodies: Vec<Body>,
+    observer: Option<Observer>,
+    rule: ShadowRule,
+    pending: Vec<Event>,
+    failed: bool
+}
+
+impl<'a, E> EventIterator<'a, E> where E: Ephemeris + ?Sized {
+    pub fn new(ephemeris: &'a E, start: JulianDate,
+               end: JulianDate) -> EventIterator<'a, E> {
+        let mut bodies = vec![Body::Sun, Body::Moon];
+        bodies.extend_from_slice(&Body::PLANETS);
+
+        EventIterator {
+            ephemeris,
+            start,
+            end,
+            scanned: start,
+            categories: Category::ALL.to_vec(),
+            bodies,
+            observer: None,
+            rule: ShadowRule::default(),
+            pending: Vec::new(),
+            failed: false
+        }
+    }
+
+    pub fn with_categories(mut self, categories: &[Category]) -> Self {
+        self.categories = categories.to_vec();
+        self
+    }
+
+    pub fn with_bodies(mut self, bodies: &[Body]) -> Self {
+        self.bodies = bodies.iter()
+            .filter(|&&body| body != Body::Earth)
+            .cloned()
+            .collect();
+        self
+    }
+
+    pub fn with_observer(mut self, observer: Observer) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    pub fn with_shadow_rule(mut self, rule: ShadowRule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    fn has(&self, category: Category) -> bool {
+        self.categories.contains(&category)
+    }
+
+    ///
+    /// Scans the next window and stores the found events
+    ///
+    fn scan(&mut self) -> Result<()> {
+        let t0 = self.scanned;
+        let t1 = if self.end - t0 < WINDOW { self.end } else { t0 + WINDOW };
+
+        let mut events = Vec::new();
+
+        if self.has(Category::Phases) || self.has(Category::Eclipses) {
+            self.find_phases(t0, t1, &mut events)?;
+        }
+        if self.has(Category::Seasons) {
+            self.find_seasons(t0, t1, &mut events)?;
+        }
+        if self.has(Category::Conjunctions) {
+            self.find_conjunctions(t0, t1, &mut events)?;
+        }
+        if self.has(Category::Apsides) {
+            self.find_apsides(t0, t1, &mut events)?;
+        }
+        if self.has(Category::RisesSets) {
+            if let Some(observer) = self.observer {
+                self.find_rises_sets(&observer, t0, t1, &mut events)?;
+            }
+        }
+
+        let (start, end) = (self.start, self.end);
+        self.pending.extend(
+            events.into_iter().filter(|e| e.jd >= start && e.jd < end)
+        );
+        self.pending.sort_by(
+            |a, b| a.jd.partial_cmp(&b.jd).unwrap_or(Ordering::Equal)
+        );
+
+        self.scanned = t1;
+        Ok(())
+    }
+
+    fn longitude(&self, body: Body, jd: JulianDate) -> Result<f64> {
+        let s: SphericalVec3D =
+            apparent_ecliptic(self.ephemeris, body, jd)?.into();
+        Ok(s.phi())
+    }
+
+    fn elongation(&self, jd: JulianDate, phase: Phase) -> Result<f64> {
+        let moon = self.longitude(Body::Moon, jd)?;
+        let sun = self.longitude(Body::Sun, jd)?;
+
+        Ok(wrap(moon - sun - phase.elongation()))
+    }
+
+    fn find_phases(&self, t0: JulianDate, t1: JulianDate,
+                   events: &mut Vec<Event>) -> Result<()> {
+        for phase in Phase::ALL.iter() {
+            let f = |jd: JulianDate| self.elongation(jd, *phase);
+
+            if !crosses(f(t0)?, f(t1)?) {
+                continue;
+            }
+
+            let jd = refine(f, t0, t1)?;
+            if self.has(Category::Phases) {
+                events.push(Event::new(jd, EventKind::Phase(*phase)));
+            }
+
+            if self.has(Category::Eclipses) {
+                let eclipse = match *phase {
+                    Phase::NewMoon => self.solar_eclipse(jd)?,
+                    Phase::FullMoon => {
+                        LunarEclipse::new(self.ephemeris, jd, self.rule)?
+                            .map(|eclipse| Event::new(
+                                eclipse.greatest(),
+                                EventKind::LunarEclipse(eclipse.kind())
+                            ))
+                    },
+                    _ => None
+                };
+                events.extend(eclipse);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn find_seasons(&self, t0: JulianDate, t1: JulianDate,
+                    events: &mut Vec<Event>) -> Result<()> {
+        for season in Season::ALL.iter() {
+            let f = |jd: JulianDate| {
+                Ok(wrap(self.longitude(Body::Sun, jd)? - season.longitude()))
+            };
+
+            if crosses(f(t0)?, f(t1)?) {
+                let jd = refine(f, t0, t1)?;
+                events.push(Event::new(jd, EventKind::Season(*season)));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn find_conjunctions(&self, t0: JulianDate, t1: JulianDate,
+                         events: &mut Vec<Event>) -> Result<()> {
+        let lon0 = self.bodies.iter()
+            .map(|&body| self.longitude(body, t0))
+            .collect::<Result<Vec<f64>>>()?;
+        let lon1 = self.bodies.iter()
+            .map(|&body| self.longitude(body, t1))
+            .collect::<Result<Vec<f64>>>()?;
+
+        for (i, &first) in self.bodies.iter().enumerate() {
+            for (j, &second) in self.bodies.iter().enumerate().skip(i + 1) {
+                if is_syzygy(first, second) {
+                    continue;
+                }
+
+                let f0 = wrap(lon0[i] - lon0[j]);
+                let f1 = wrap(lon1[i] - lon1[j]);
+                if !crosses(f0, f1) {
+                    continue;
+                }
+
+                let f = |jd: JulianDate| {
+                    Ok(wrap(
+                        self.longitude(first, jd)? -
+                            self.longitude(second, jd)?
+                    ))
+                };
+                let jd = refine(f, t0, t1)?;
+                events.push(
+                    Event::new(jd, EventKind::Conjunction(first, second))
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn lunar_distance(&self, jd: JulianDate) -> Result<f64> {
+        let moon = self.ephemeris.position(Body::Moon, jd)?;
+        let earth = self.ephemeris.position(Body::Earth, jd)?;
+
+        Ok((moon - earth).norm())
+    }
+
+    fn lunar_distance_rate(&self, jd: JulianDate) -> Result<f64> {
+        let h = DIFFERENTIATION_STEP;
+        Ok(
+            (self.lunar_distance(jd + h)? - self.lunar_distance(jd - h)?) /
+                (2.0 * h)
+        )
+    }
+
+    fn find_apsides(&self, t0: JulianDate, t1: JulianDate,
+                    events: &mut Vec<Event>) -> Result<()> {
+        let f = |jd: JulianDate| self.lunar_distance_rate(jd);
+        let f0 = f(t0)?;
+        let f1 = f(t1)?;
+
+        if changes_sign(f0, f1) {
+            let jd = refine(f, t0, t1)?;
+            let kind = if f0 < f1 {
+                EventKind::Perigee
+            } else {
+                EventKind::Apogee
+            };
+            events.push(Event::new(jd, kind));
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Difference of the sines of the altitude of the body and of its standard
+    /// altitude at the rising and the setting
+    ///
+    fn altitude(&self, body: Body, observer: &Observer,
+                jd: JulianDate) -> Result<f64> {
+        let r = apparent(self.ephemeris, body, jd)?;
+        let s: SphericalVec3D = r.into();
+
+        let longitude: f64 = observer.longitude().into();
+        let latitude: f64 = observer.latitude().into();
+
+        let tau = gast(jd.tt_to_ut()) + longitude - s.phi();
+        let (sp, cp) = latitude.sin_cos();
+        let (sd, cd) = s.theta().sin_cos();
+
+        let h0 = match body {
+            Body::Sun => SUN_ALTITUDE,
+            Body::Moon => 0.7275 * horizontal_parallax(s.r()) + PLANET_ALTITUDE,
+            _ => PLANET_ALTITUDE
+        };
+
+        Ok(sp * sd + cp * cd * tau.cos() - h0.sin())
+    }
+
+    fn find_rises_sets(&self, observer: &Observer, t0: JulianDate,
+                       t1: JulianDate, events: &mut Vec<Event>) -> Result<()> {
+        let steps = ((t1 - t0) / RISE_SET_STEP).ceil().max(1.0) as usize;
+        let step = (t1 - t0) / steps as f64;
+
+        for &body in self.bodies.iter() {
+            let f = |jd: JulianDate| self.altitude(body, observer, jd);
+
+            let mut a = t0;
+            let mut fa = f(a)?;
+            for k in 1..=steps {
+                let b = if k == steps { t1 } else { t0 + step * k as f64 };
+                let fb = f(b)?;
+
+                if changes_sign(fa, fb) {
+                    let jd = refine(f, a, b)?;
+                    let kind = if fa < fb {
+                        EventKind::Rise(body)
+                    } else {
+                        EventKind::Set(body)
+                    };
+                    events.push(Event::new(jd, kind));
+                }
+
+                a = b;
+                fa = fb;
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Geocentric apparent positions of the Sun and of the Moon
+    ///
+    fn sun_moon(&self, jd: JulianDate) -> Result<(Vec3D, Vec3D)> {
+        Ok((
+            apparent(self.ephemeris, Body::Sun, jd)?,
+            apparent(self.ephemeris, Body::Moon, jd)?
+        ))
+    }
+
+    ///
+    /// Checks whether a solar eclipse is visible somewhere on the Earth near
+    /// the new moon and gives the greatest eclipse with the type
+    ///
+    fn solar_eclipse(&self, jd: JulianDate) -> Result<Option<Event>> {
+        let f = |x: f64| {
+            let (sun, moon) = self.sun_moon(JulianDate::new(x))?;
+            Ok(sun.separation(moon))
+        };
+        let (x, distance) = golden_section(
+            f, jd.jd() - MARGIN / 2.0, jd.jd() + MARGIN / 2.0, ACCURACY
+        )?;
+
+        let (sun, moon) = self.sun_moon(JulianDate::new(x))?;
+        let limit = horizontal_parallax(moon.norm()) -
+            horizontal_parallax(sun.norm()) +
+            angular_radius(Body::Moon.radius(), moon.norm()) +
+            angular_radius(Body::Sun.radius(), sun.norm());
+        if distance >= limit {
+            return Ok(None);
+        }
+
+        let elements =
+            BesselianElements::new(self.ephemeris, JulianDate::new(x))?;
+        Ok(elements.global()?.map(|circumstances| Event::new(
+            circumstances.greatest(),
+            EventKind::SolarEclipse(circumstances.kind())
+        )))
+    }
+}
+
+impl<'a, E> iter::Iterator for EventIterator<'a, E>
+    where E: Ephemeris + ?Sized
+{
+    type Item = Result<Event>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        loop {
+            let ready = match self.pending.first() {
+                Some(event) => self.scanned >= self.end ||
+                    event.jd < self.scanned - MARGIN,
+                None => self.scanned >= self.end
+            };
+            if ready {
+                break;
+            }
+
+            if let Err(error) = self.scan() {
+                self.failed = true;
+                return Some(Err(error));
+            }
+        }
+
+        if self.pending.is_empty() {
+            None
+        } else {
+            Some(Ok(self.pending.remove(0)))
+        }
+    }
+}
+
+fn changes_sign(f0: f64, f1: f64) -> bool {
+    (f0 <= 0.0 && f1 > 0.0) || (f0 >= 0.0 && f1 < 0.0)
+}
+
+///
+/// Checks whether the reduced angle passes through zero (and not through
+/// the discontinuity at pi)
+///
+fn crosses(f0: f64, f1: f64) -> bool {
+    changes_sign(f0, f1) && (f1 - f0).abs() < FRAC_PI_2
+}
+
+fn is_syzygy(first: Body, second: Body) -> bool {
+    (first == Body::Sun && second == Body::Moon) ||
+        (first == Body::Moon && second == Body::Sun)
+}
+
+fn refine<F>(f: F, t0: JulianDate, t1: JulianDate) -> Result<JulianDate>
+    where F: Fn(JulianDate) -> Result<f64>
+{
+    let root = pegasus(|x| f(JulianDate::new(x)), t0.jd(), t1.jd(), ACCURACY)?;
+    Ok(JulianDate::new(root))
+}
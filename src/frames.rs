@@ -0,0 +1,9 @@
+pub mod cio;
+pub mod eop;
+pub mod fk;
+pub mod nutation;
+pub mod precession;
+pub mod rotating;
+pub mod sidereal;
+pub mod tagged;
+pub mod topocentric;
@@ -0,0 +1,538 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::base::Result;
+use crate::base::angle::Angle;
+use crate::base::consts::{ARCS, DAY, PI2};
+use crate::base::error::Error;
+use crate::base::linalg::{CartesianVec3D, Mat3D, Vec3D};
+use crate::frames::eop::EarthOrientation;
+use crate::frames::nutation::nutation_2000b;
+use crate::frames::precession::fukushima_williams_angles;
+use crate::frames::rotating::RotatingFrame;
+use crate::frames::sidereal::earth_rotation_angle;
+use crate::time::JulianDate;
+
+///
+/// Rate of the Earth rotation angle [rad/s of UT1]
+///
+const ERA_RATE: f64 = PI2 * 1.0027378119113546 / DAY;
+
+///
+/// Term of the series of the CIO locator: multipliers of the arguments l,
+/// l', F, D, Omega and the coefficients of the sine and the cosine [uas]
+///
+type Term = (i8, i8, i8, i8, i8, f64, f64);
+
+///
+/// Polynomial part of s + XY/2 (IERS Conventions 2010, table 5.2d) [uas]
+///
+const S_POLYNOMIAL: [f64; 6] = [
+    94.00, 3808.65, -122.68, -72574.11, 27.98, 15.62
+];
+
+///
+/// Periodic terms of s + XY/2 above 0.3 uas multiplied by the powers 0 to 4
+/// of the time
+///
+const S_TERMS_0: [Term; 16] = [
+    (0, 0, 0, 0, 1, -2640.73, 0.39),
+    (0, 0, 0, 0, 2, -63.53, 0.02),
+    (0, 0, 2, -2, 3, -11.75, -0.01),
+    (0, 0, 2, -2, 1, -11.21, -0.01),
+    (0, 0, 2, -2, 2, 4.57, 0.00),
+    (0, 0, 2, 0, 3, -2.02, 0.00),
+    (0, 0, 2, 0, 1, -1.98, 0.00),
+    (0, 0, 0, 0, 3, 1.72, 0.00),
+    (0, 1, 0, 0, 1, 1.41, 0.01),
+    (0, 1, 0, 0, -1, 1.26, 0.01),
+    (1, 0, 0, 0, -1, 0.63, 0.00),
+    (1, 0, 0, 0, 1, 0.63, 0.00),
+    (0, 1, 2, -2, 3, -0.46, 0.00),
+    (0, 1, 2, -2, 1, -0.45, 0.00),
+    (0, 0, 4, -4, 4, -0.36, 0.00),
+    (0, 0, 2, 0, 0, -0.32, 0.00)
+];
+
+const S_TERMS_1: [Term; 3] = [
+    (0, 0, 0, 0, 1, -0.07, 3.57),
+    (0, 0, 0, 0, 2, 1.73, -0.03),
+    (0, 0, 2, -2, 3, 0.00, 0.48)
+];
+
+const S_TERMS_2: [Term; 8] = [
+    (0, 0, 0, 0, 1, 743.52, -0.17),
+    (0, 0, 2, -2, 2, 56.91, 0.06),
+    (0, 0, 2, 0, 2, 9.84, -0.01),
+    (0, 0, 0, 0, 2, -8.85, 0.01),
+    (0, 1, 0, 0, 0, -6.38, -0.05),
+    (1, 0, 0, 0, 0, -3.07, 0.00),
+    (0, 1, 2, -2, 2, 2.23, 0.00),
+    (0, 0, 2, 0, 1, 1.67, 0.00)
+];
+
+const S_TERMS_3: [Term; 4] = [
+    (0, 0, 0, 0, 1, 0.30, -23.42),
+    (0, 0, 2, -2, 2, -0.03, -1.46),
+    (0, 0, 2, 0, 2, -0.01, -0.25),
+    (0, 0, 0, 0, 2, 0.00, 0.23)
+];
+
+const S_TERMS_4: [Term; 1] = [
+    (0, 0, 0, 0, 1, -0.26, -0.01)
+];
+
+///
+/// Polynomial parts of X and Y of the IAU 2006/2000A series (IERS
+/// Conventions 2010, tables 5.2a and 5.2b) [uas]
+///
+const X_POLYNOMIAL: [f64; 6] = [
+    -16617.0, 2004191898.0, -429782.9, -198618.34, 7.578, 5.9285
+];
+
+const Y_POLYNOMIAL: [f64; 6] = [
+    -6951.0, -25896.0, -22407274.7, 1900.59, 1112.526, 0.1358
+];
+
+///
+/// Number of the arguments of the IAU 2006/2000A series: l, l', F, D,
+/// Omega, the mean longitudes of the planets Mercury to Neptune and the
+/// general precession in longitude
+///
+const ARGUMENTS: usize = 14;
+
+///
+/// Rate of the TIO locator [arcsec/cy]
+///
+const S_PRIME_RATE: f64 = -47e-6;
+
+///
+/// Fundamental arguments l, l', F, D and Omega of the IERS Conventions 2003
+/// for the Julian centuries of TT since J2000 [rad]
+///
+fn fundamental_arguments(t: f64) -> [f64; 5] {
+    let l = 485868.249036 + t * (1717915923.2178 + t * (31.8792 +
+        t * (0.051635 - t * 0.00024470)));
+    let lp = 1287104.793048 + t * (129596581.0481 + t * (-0.5532 +
+        t * (0.000136 - t * 0.00001149)));
+    let f = 335779.526232 + t * (1739527262.8478 + t * (-12.7512 +
+        t * (-0.001037 + t * 0.00000417)));
+    let d = 1072260.703692 + t * (1602961601.2090 + t * (-6.3706 +
+        t * (0.006593 - t * 0.00003169)));
+    let om = 450160.398036 + t * (-6962890.5431 + t * (7.4722 +
+        t * (0.007702 - t * 0.00005939)));
+
+    [l / ARCS, lp / ARCS, f / ARCS, d / ARCS, om / ARCS]
+}
+
+///
+/// Mean longitudes of Mercury to Neptune and the general precession in
+/// longitude of the IERS Conventions 2003 for the Julian centuries of TT
+/// since J2000 [rad]
+///
+fn planetary_arguments(t: f64) -> [f64; 9] {
+    [
+        4.402608842 + 2608.7903141574 * t,
+        3.176146697 + 1021.3285546211 * t,
+        1.753470314 + 628.3075849991 * t,
+        6.203480913 + 334.0612426700 * t,
+        0.599546497 + 52.9690962641 * t,
+        0.874016757 + 21.3299104960 * t,
+        5.481293872 + 7.4781598567 * t,
+        5.311886287 + 3.8133035638 * t,
+        (0.02438175 + 0.00000538691 * t) * t
+    ]
+}
+
+///
+/// cip_2000b: Coordinates X and Y of the celestial intermediate pole in the
+/// GCRS for the TT date from the IAU 2006 precession and the truncated IAU
+/// 2000B nutation [rad]. The IAU 2000B series omits the planetary terms and
+/// differs from the IAU 2000A by up to 1 mas, so that the pole is not the
+/// IAU 2006/2000A pole of the IERS Conventions 2010.
+///
+pub fn cip_2000b(jd: JulianDate) -> (f64, f64) {
+    let (gamma, phi, psi, eps) = fukushima_williams_angles(jd);
+    let (dpsi, deps) = nutation_2000b(jd);
+
+    let npb = Mat3D::r_x(-(eps + deps)) * Mat3D::r_z(-(psi + dpsi)) *
+        Mat3D::r_x(phi) * Mat3D::r_z(gamma);
+    let pole = npb.t() * Vec3D::unit_z();
+    let (x, y, _) = components(pole);
+
+    (x, y)
+}
+
+///
+/// cio_locator: Position s of the celestial intermediate origin on the
+/// equator of the CIP with the coordinates X, Y for the TT date [rad]
+///
+pub fn cio_locator(jd: JulianDate, x: f64, y: f64) -> f64 {
+    let t = jd.centuries();
+    let arguments = fundamental_arguments(t);
+    let series = |terms: &[Term]| -> f64 {
+        terms.iter().map(|&(kl, klp, kf, kd, kom, s, c)| {
+            let arg = [kl, klp, kf, kd, kom].iter().zip(arguments.iter())
+                .map(|(&k, &a)| f64::from(k) * a)
+                .sum::<f64>();
+            s * arg.sin() + c * arg.cos()
+        }).sum()
+    };
+
+    let w = [
+        S_POLYNOMIAL[0] + series(&S_TERMS_0),
+        S_POLYNOMIAL[1] + series(&S_TERMS_1),
+        S_POLYNOMIAL[2] + series(&S_TERMS_2),
+        S_POLYNOMIAL[3] + series(&S_TERMS_3),
+        S_POLYNOMIAL[4] + series(&S_TERMS_4),
+        S_POLYNOMIAL[5]
+    ];
+    let s = w.iter().rev().fold(0.0, |acc, &c| acc * t + c);
+
+    s * 1e-6 / ARCS - x * y / 2.0
+}
+
+///
+/// tio_locator: Position s' of the terrestrial intermediate origin on the
+/// equator of the CIP for the TT date [rad]
+///
+pub fn tio_locator(jd: JulianDate) -> f64 {
+    S_PRIME_RATE * jd.centuries() / ARCS
+}
+
+///
+/// celestial_to_intermediate_matrix: Transformation from the GCRS to the
+/// celestial intermediate reference system for the CIP coordinates X, Y
+/// and the CIO locator s
+///
+pub fn celestial_to_intermediate_matrix(x: f64, y: f64, s: f64) -> Mat3D {
+    let r2 = x * x + y * y;
+    let e = if r2 > 0.0 { y.atan2(x) } else { 0.0 };
+    let d = (r2 / (1.0 - r2)).sqrt().atan();
+
+    Mat3D::r_z(-(e + s)) * Mat3D::r_y(d) * Mat3D::r_z(e)
+}
+
+///
+/// polar_motion_matrix: Transformation from the terrestrial intermediate
+/// reference system to the ITRS for the coordinates of the pole and the TIO
+/// locator s'
+///
+pub fn polar_motion_matrix(x_p: f64, y_p: f64, s_prime: f64) -> Mat3D {
+    Mat3D::r_x(-y_p) * Mat3D::r_y(-x_p) * Mat3D::r_z(s_prime)
+}
+
+fn components(v: Vec3D) -> (f64, f64, f64) {
+    let c: CartesianVec3D = v.into();
+    (c.x(), c.y(), c.z())
+}
+
+///
+/// Term of the IAU 2006/2000A series of X or Y: power of the time,
+/// coefficients of the sine and the cosine [uas] and multipliers of the
+/// arguments
+///
+#[derive(Debug, Copy, Clone)]
+struct CipTerm {
+    power: usize,
+    sin: f64,
+    cos: f64,
+    multipliers: [i8; ARGUMENTS]
+}
+
+///
+/// CipSeries: IAU 2006/2000A series of the coordinates X, Y of the CIP
+/// loaded from the tables 5.2a and 5.2b of the IERS Conventions 2010
+/// (tab5.2a.txt and tab5.2b.txt). The series gives the pole of the IERS
+/// Conventions 2010 to which the celestial pole offsets of the IERS are
+/// referred.
+///
+#[derive(Debug, Clone)]
+pub struct CipSeries {
+    x: Vec<CipTerm>,
+    y: Vec<CipTerm>
+}
+
+fn parse<T: FromStr>(field: &str, line: usize) -> Result<T> {
+    field.parse().map_err(|_| Error::ParseError(line))
+}
+
+///
+/// Terms of a table: the blocks of the powers of the time start with the
+/// line "j = <power>", the lines of the terms with the index of the term
+/// followed by the coefficients of the sine and the cosine and the 14
+/// multipliers. The other lines are skipped.
+///
+fn parse_terms(text: &str) -> Result<Vec<CipTerm>> {
+    let mut terms = Vec::new();
+    let mut power = None;
+
+    for (i, line) in text.lines().enumerate() {
+        let n = i + 1;
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+
+        match fields.as_slice() {
+            ["j", "=", j, ..] => power = Some(parse(j, n)?),
+            [index, ..] if index.starts_with(|c: char| c.is_ascii_digit()) => {
+                let power = power.ok_or(Error::ParseError(n))?;
+                if fields.len() != ARGUMENTS + 3 {
+                    return Err(Error::ParseError(n));
+                }
+
+                let mut multipliers = [0; ARGUMENTS];
+                for (k, field) in multipliers.iter_mut().zip(&fields[3..]) {
+                    *k = parse(field, n)?;
+                }
+
+                terms.push(CipTerm {
+                    power,
+                    sin: parse(fields[1], n)?,
+                    cos: parse(fields[2], n)?,
+                    multipliers
+                });
+            },
+            _ => continue
+        }
+    }
+
+    Ok(terms)
+}
+
+///
+/// Value of the polynomial and the periodic terms for the Julian centuries
+/// and the arguments [uas]
+///
+fn evaluate(polynomial: &[f64], terms: &[CipTerm], t: f64,
+            arguments: &[f64; ARGUMENTS]) -> f64 {
+    let mut w = polynomial.to_vec();
+    for term in terms {
+        let arg = term.multipliers.iter().zip(arguments.iter())
+            .map(|(&k, &a)| f64::from(k) * a)
+            .sum::<f64>();
+        if term.power >= w.len() {
+            w.resize(term.power + 1, 0.0);
+        }
+        w[term.power] += term.sin * arg.sin() + term.cos * arg.cos();
+    }
+
+    w.iter().rev().fold(0.0, |acc, &c| acc * t + c)
+}
+
+impl CipSeries {
+    ///
+    /// from_tables: Series from the contents of the tables of X (5.2a) and
+    /// Y (5.2b)
+    ///
+    pub fn from_tables(x: &str, y: &str) -> Result<CipSeries> {
+        Ok(CipSeries {
+            x: parse_terms(x)?,
+            y: parse_terms(y)?
+        })
+    }
+
+    ///
+    /// from_files: Series from the files of the tables of X (tab5.2a.txt)
+    /// and Y (tab5.2b.txt)
+    ///
+    pub fn from_files<P: AsRef<Path>>(x: P, y: P) -> Result<CipSeries> {
+        CipSeries::from_tables(
+            &fs::read_to_string(x)?, &fs::read_to_string(y)?
+        )
+    }
+
+    ///
+    /// len: Number of the periodic terms of X and Y
+    ///
+    pub fn len(&self) -> usize {
+        self.x.len() + self.y.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty() && self.y.is_empty()
+    }
+
+    ///
+    /// cip: Coordinates X and Y of the IAU 2006/2000A CIP in the GCRS for
+    /// the TT date [rad]
+    ///
+    pub fn cip(&self, jd: JulianDate) -> (f64, f64) {
+        let t = jd.centuries();
+        let mut arguments = [0.0; ARGUMENTS];
+        arguments[..5].copy_from_slice(&fundamental_arguments(t));
+        arguments[5..].copy_from_slice(&planetary_arguments(t));
+
+        let x = evaluate(&X_POLYNOMIAL, &self.x, t, &arguments);
+        let y = evaluate(&Y_POLYNOMIAL, &self.y, t, &arguments);
+
+        (x * 1e-6 / ARCS, y * 1e-6 / ARCS)
+    }
+}
+
+///
+/// CelestialToTerrestrial: CIO based transformation from the GCRS to the
+/// ITRS for the TT date: the celestial motion of the CIP, the Earth rotation
+/// angle and the polar motion. The CIP follows from the IAU 2006/2000A
+/// series when they are given (see CipSeries) and otherwise from the IAU
+/// 2006 precession and the IAU 2000B nutation (see cip_2000b), accurate to
+/// about 1 mas. UT1 follows from TT - UT of the time module and the polar
+/// motion and the celestial pole offsets are zero unless they are given.
+///
+#[derive(Debug, Copy, Clone)]
+pub struct CelestialToTerrestrial {
+    jd: JulianDate,
+    ut1: JulianDate,
+    x_p: Angle,
+    y_p: Angle,
+    dx: Angle,
+    dy: Angle,
+    pole: Option<(f64, f64)>
+}
+
+impl CelestialToTerrestrial {
+    pub fn new(jd: JulianDate) -> CelestialToTerrestrial {
+        CelestialToTerrestrial {
+            jd,
+            ut1: jd.tt_to_ut(),
+            x_p: Angle::default(),
+            y_p: Angle::default(),
+            dx: Angle::default(),
+            dy: Angle::default(),
+            pole: None
+        }
+    }
+
+    pub fn with_ut1(mut self, ut1: JulianDate) -> Self {
+        self.ut1 = ut1;
+        self
+    }
+
+    pub fn with_polar_motion(mut self, x_p: Angle, y_p: Angle) -> Self {
+        self.x_p = x_p;
+        self.y_p = y_p;
+        self
+    }
+
+    ///
+    /// with_cip_series: Takes the CIP of the date from the IAU 2006/2000A
+    /// series instead of the IAU 2000B nutation
+    ///
+    pub fn with_cip_series(mut self, series: &CipSeries) -> Self {
+        self.pole = Some(series.cip(self.jd));
+        self
+    }
+
+    ///
+    /// with_pole_offsets: Sets the celestial pole offsets dX, dY. The
+    /// offsets of the IERS are referred to the IAU 2006/2000A model, they
+    /// give the observed pole with the CIP series only, added to the IAU
+    /// 2000B pole they leave the differences of the two series of up to
+    /// 1 mas.
+    ///
+    pub fn with_pole_offsets(mut self, dx: Angle, dy: Angle) -> Self {
+        self.dx = dx;
+        self.dy = dy;
+        self
+    }
+
+    ///
+    /// with_earth_orientation: Sets UT1, the polar motion and the celestial
+    /// pole offsets from the Earth orientation parameters of the date
+    ///
+    pub fn with_earth_orientation(self, eop: &EarthOrientation) -> Self {
+        self.with_ut1(eop.ut1())
+            .with_polar_motion(eop.x_p(), eop.y_p())
+            .with_pole_offsets(eop.dx(), eop.dy())
+    }
+
+    pub fn jd(&self) -> JulianDate {
+        self.jd
+    }
+
+    pub fn ut1(&self) -> JulianDate {
+        self.ut1
+    }
+
+    ///
+    /// cip: Coordinates X, Y of the IAU 2006/2000A CIP or, without the
+    /// series, of the IAU 2000B CIP including the pole offsets [rad]
+    ///
+    pub fn cip(&self) -> (f64, f64) {
+        let (x, y) = self.pole.unwrap_or_else(|| cip_2000b(self.jd));
+        let dx: f64 = self.dx.into();
+        let dy: f64 = self.dy.into();
+
+        (x + dx, y + dy)
+    }
+
+    ///
+    /// earth_rotation_angle: Earth rotation angle of the date [rad]
+    ///
+    pub fn earth_rotation_angle(&self) -> f64 {
+        earth_rotation_angle(self.ut1)
+    }
+
+    ///
+    /// celestial_to_intermediate: Transformation from the GCRS to the
+    /// celestial intermediate reference system
+    ///
+    pub fn celestial_to_intermediate(&self) -> Mat3D {
+        let (x, y) = self.cip();
+        celestial_to_intermediate_matrix(x, y, cio_locator(self.jd, x, y))
+    }
+
+    ///
+    /// polar_motion: Transformation from the terrestrial intermediate
+    /// reference system to the ITRS
+    ///
+    pub fn polar_motion(&self) -> Mat3D {
+        polar_motion_matrix(
+            self.x_p.into(), self.y_p.into(), tio_locator(self.jd)
+        )
+    }
+
+    ///
+    /// matrix: Transformation from the GCRS to the ITRS
+    ///
+    pub fn matrix(&self) -> Mat3D {
+        self.polar_motion() * Mat3D::r_z(self.earth_rotation_angle()) *
+            self.celestial_to_intermediate()
+    }
+
+    ///
+    /// derivative: Time derivative of the transformation from the GCRS to
+    /// the ITRS due to the Earth rotation [1/s]
+    ///
+    pub fn derivative(&self) -> Mat3D {
+        let (s, c) = self.earth_rotation_angle().sin_cos();
+        let rotation = Mat3D::from_rows(
+            Vec3D::cartesian(-s, c, 0.0),
+            Vec3D::cartesian(-c, -s, 0.0),
+            Vec3D::zero()
+        ) * ERA_RATE;
+
+        self.polar_motion() * rotation * self.celestial_to_intermediate()
+    }
+
+    ///
+    /// rotating_frame: Transformation of the positions and the velocities
+    /// [per s] from the GCRS to the ITRS
+    ///
+    pub fn rotating_frame(&self) -> RotatingFrame {
+        RotatingFrame::new(self.matrix(), self.derivative())
+    }
+
+    ///
+    /// to_terrestrial: Position in the ITRS of the position in the GCRS
+    ///
+    pub fn to_terrestrial(&self, r: Vec3D) -> Vec3D {
+        self.matrix() * r
+    }
+
+    ///
+    /// to_celestial: Position in the GCRS of the position in the ITRS
+    ///
+    pub fn to_celestial(&self, r: Vec3D) -> Vec3D {
+        self.matrix().t() * r
+    }
+}
@@ -0,0 +1,301 @@
+use std::fs;
+use std::path::Path;
+
+use crate::base::Result;
+use crate::base::angle::Angle;
+use crate::base::consts::{ARCS, DAY};
+use crate::base::error::Error;
+use crate::base::linalg::Mat3D;
+use crate::frames::cio;
+use crate::time::{tai_utc, JulianDate};
+
+///
+/// Number of the points of the Lagrange interpolation
+///
+const INTERPOLATION_POINTS: usize = 4;
+
+///
+/// EarthOrientation: Earth orientation parameters at the UTC date: the
+/// coordinates of the pole, UT1 - UTC [s] and the celestial pole offsets
+/// dX, dY with respect to the IAU 2006/2000A precession-nutation
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EarthOrientation {
+    utc: JulianDate,
+    x_p: Angle,
+    y_p: Angle,
+    ut1_utc: f64,
+    dx: Angle,
+    dy: Angle
+}
+
+impl EarthOrientation {
+    pub fn utc(&self) -> JulianDate {
+        self.utc
+    }
+
+    pub fn x_p(&self) -> Angle {
+        self.x_p
+    }
+
+    pub fn y_p(&self) -> Angle {
+        self.y_p
+    }
+
+    pub fn ut1_utc(&self) -> f64 {
+        self.ut1_utc
+    }
+
+    pub fn dx(&self) -> Angle {
+        self.dx
+    }
+
+    pub fn dy(&self) -> Angle {
+        self.dy
+    }
+
+    ///
+    /// ut1: UT1 of the date
+    ///
+    pub fn ut1(&self) -> JulianDate {
+        self.utc + self.ut1_utc / DAY
+    }
+
+    ///
+    /// polar_motion_matrix: Transformation from the terrestrial intermediate
+    /// reference system of the date to the ITRS, including the TIO locator
+    ///
+    pub fn polar_motion_matrix(&self) -> Mat3D {
+        let tt = self.utc.utc_to_tt();
+
+        cio::polar_motion_matrix(
+            self.x_p.into(), self.y_p.into(), cio::tio_locator(tt)
+        )
+    }
+}
+
+///
+/// Tabulated values: the coordinates of the pole and the celestial pole
+/// offsets [rad] and UT1 - TAI [s], which is continuous over the leap
+/// seconds
+///
+#[derive(Debug, Copy, Clone)]
+struct Record {
+    mjd: f64,
+    x_p: f64,
+    y_p: f64,
+    ut1_tai: f64,
+    dx: f64,
+    dy: f64
+}
+
+impl Record {
+    fn new(mjd: f64, x_p: f64, y_p: f64, ut1_utc: f64, dx: f64,
+           dy: f64) -> Record {
+        Record {
+            mjd,
+            x_p,
+            y_p,
+            ut1_tai: ut1_utc - tai_utc(JulianDate::from_mjd(mjd)),
+            dx,
+            dy
+        }
+    }
+}
+
+///
+/// EopTable: Earth orientation parameters of the IERS loaded from the
+/// finals2000A files (Bulletin A) or the EOP C04 series and interpolated by
+/// the Lagrange polynomials
+///
+#[derive(Debug, Clone)]
+pub struct EopTable {
+    records: Vec<Record>
+}
+
+///
+/// Trimmed field of the fixed columns, None when it is blank
+///
+fn column(line: &str, start: usize, end: usize) -> Option<&str> {
+    line.get(start..end.min(line.len()))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+fn parse(field: &str, line: usize) -> Result<f64> {
+    field.parse().map_err(|_| Error::ParseError(line))
+}
+
+impl EopTable {
+    ///
+    /// from_finals: Table from the contents of a finals2000A file. The
+    /// predictions without UT1 - UTC are skipped, the missing celestial
+    /// pole offsets are zero.
+    ///
+    pub fn from_finals(text: &str) -> Result<EopTable> {
+        let mas = 1e-3 / ARCS;
+        let mut records = Vec::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let n = i + 1;
+            let mjd = match column(line, 7, 15) {
+                Some(mjd) => parse(mjd, n)?,
+                None => continue
+            };
+            let (x_p, y_p, ut1_utc) = match (
+                column(line, 18, 27), column(line, 37, 46),
+                column(line, 58, 68)
+            ) {
+                (Some(x_p), Some(y_p), Some(ut1_utc)) => {
+                    (parse(x_p, n)?, parse(y_p, n)?, parse(ut1_utc, n)?)
+                },
+                _ => continue
+            };
+            let offset = |start, end| column(line, start, end)
+                .map_or(Ok(0.0), |s| parse(s, n));
+
+            records.push(Record::new(
+                mjd, x_p / ARCS, y_p / ARCS, ut1_utc,
+                offset(97, 106)? * mas, offset(116, 125)? * mas
+            ));
+        }
+
+        Ok(EopTable::new(records))
+    }
+
+    ///
+    /// from_c04: Table from the contents of an EOP C04 file (the 14 series
+    /// with the columns year, month, day, MJD, x, y, UT1 - UTC, LOD, dX, dY
+    /// or the 20 series with the hour after the day and without LOD)
+    ///
+    pub fn from_c04(text: &str) -> Result<EopTable> {
+        let mut records = Vec::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let n = i + 1;
+            if !line.trim_start().starts_with(|c: char| c.is_ascii_digit()) {
+                continue;
+            }
+
+            let fields = line.split_whitespace()
+                .map(|s| parse(s, n))
+                .collect::<Result<Vec<f64>>>()?;
+            if fields.len() < 10 {
+                return Err(Error::ParseError(n));
+            }
+
+            // The 20 series has the hour where the 14 series has the MJD
+            let (mjd, values) = if fields[3] < 24.0 {
+                (fields[4] + fields[3] / 24.0,
+                 [fields[5], fields[6], fields[7], fields[8], fields[9]])
+            } else {
+                (fields[3],
+                 [fields[4], fields[5], fields[6], fields[8], fields[9]])
+            };
+
+            let [x_p, y_p, ut1_utc, dx, dy] = values;
+            records.push(Record::new(
+                mjd, x_p / ARCS, y_p / ARCS, ut1_utc, dx / ARCS, dy / ARCS
+            ));
+        }
+
+        Ok(EopTable::new(records))
+    }
+
+    ///
+    /// from_file: Table from a finals2000A or an EOP C04 file. The C04
+    /// files are recognized by the four digits of the year in the first
+    /// column.
+    ///
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<EopTable> {
+        let text = fs::read_to_string(path)?;
+        let c04 = text.lines()
+            .find(|line| {
+                line.trim_start().starts_with(|c: char| c.is_ascii_digit())
+            })
+            .and_then(|line| line.split_whitespace().next())
+            .is_some_and(|year| year.len() == 4);
+
+        if c04 {
+            EopTable::from_c04(&text)
+        } else {
+            EopTable::from_finals(&text)
+        }
+    }
+
+    fn new(mut records: Vec<Record>) -> EopTable {
+        records.sort_by(|a, b| a.mjd.total_cmp(&b.mjd));
+        records.dedup_by(|a, b| a.mjd == b.mjd);
+
+        EopTable { records }
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    ///
+    /// range: First and last UTC dates of the table, None when it is empty
+    ///
+    pub fn range(&self) -> Option<(JulianDate, JulianDate)> {
+        let first = self.records.first()?;
+        let last = self.records.last()?;
+
+        Some((JulianDate::from_mjd(first.mjd), JulianDate::from_mjd(last.mjd)))
+    }
+
+    ///
+    /// at: Earth orientation parameters interpolated at the UTC date
+    ///
+    pub fn at(&self, utc: JulianDate) -> Result<EarthOrientation> {
+        let mjd = utc.mjd();
+        let records = &self.records;
+        let n = records.len();
+        if n == 0 || !(records[0].mjd..=records[n - 1].mjd).contains(&mjd) {
+            return Err(Error::OutOfRangeError);
+        }
+
+        // Points centred on the interval of the date
+        let i = records.partition_point(|r| r.mjd <= mjd).max(1);
+        let count = INTERPOLATION_POINTS.min(n);
+        let start = (i.saturating_sub(count / 2)).min(n - count);
+        let points = &records[start..start + count];
+
+        let interpolate = |value: fn(&Record) -> f64| {
+            points.iter().enumerate().map(|(j, p)| {
+                let weight: f64 = points.iter().enumerate()
+                    .filter(|&(k, _)| k != j)
+                    .map(|(_, q)| (mjd - q.mjd) / (p.mjd - q.mjd))
+                    .product();
+                weight * value(p)
+            }).sum::<f64>()
+        };
+
+        Ok(EarthOrientation {
+            utc,
+            x_p: Angle::from(interpolate(|r| r.x_p)),
+            y_p: Angle::from(interpolate(|r| r.y_p)),
+            ut1_utc: interpolate(|r| r.ut1_tai) + tai_utc(utc),
+            dx: Angle::from(interpolate(|r| r.dx)),
+            dy: Angle::from(interpolate(|r| r.dy))
+        })
+    }
+
+    ///
+    /// utc_to_ut1: UT1 of the UTC date
+    ///
+    pub fn utc_to_ut1(&self, utc: JulianDate) -> Result<JulianDate> {
+        Ok(self.at(utc)?.ut1())
+    }
+
+    ///
+    /// tt_to_ut1: UT1 of the TT date
+    ///
+    pub fn tt_to_ut1(&self, tt: JulianDate) -> Result<JulianDate> {
+        self.utc_to_ut1(tt.tt_to_utc())
+    }
+}
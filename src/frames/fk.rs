@@ -0,0 +1,309 @@
+use crate::astrometry::star::Star;
+use crate::base::Real;
+use crate::base::Result;
+use crate::base::angle::Angle;
+use crate::base::consts::{ARCS, JD_J2000, JULIAN_YEAR, PI2, TROPICAL_YEAR};
+use crate::base::linalg::{CartesianVec3D, Mat3D, Norm, SphericalVec3D, Vec3D};
+use crate::time::{Epoch, JulianDate};
+
+///
+/// Radians per year to arcseconds per century
+///
+const PMF: f64 = 100.0 * ARCS;
+
+///
+/// Kilometres per second to AU per tropical century
+///
+const VF: f64 = 21.095;
+
+///
+/// Parallaxes [arcsec] treated as zero
+///
+const TINY: f64 = 1e-30;
+
+///
+/// E-terms of the aberration (Seidelmann 1992, 3.591-2): the vector [rad]
+/// and its rate of change [arcsec/cy]
+///
+const E_TERMS: [f64; 3] = [-1.62557e-6, -0.31919e-6, -0.13843e-6];
+const E_TERMS_RATE: [f64; 3] = [1.245e-3, -1.580e-3, -0.659e-3];
+
+///
+/// Transformation of the position and the velocity [arcsec/cy] from the
+/// FK4 (B1950.0) to the FK5 (J2000.0) (Standish 1982, Seidelmann 1992,
+/// 3.591-4)
+///
+const FK4_TO_FK5: [[f64; 6]; 6] = [
+    [0.9999256782, -0.0111820611, -0.0048579477,
+     0.00000242395018, -0.00000002710663, -0.00000001177656],
+    [0.0111820610, 0.9999374784, -0.0000271765,
+     0.00000002710663, 0.00000242397878, -0.00000000006587],
+    [0.0048579479, -0.0000271474, 0.9999881997,
+     0.00000001177656, -0.00000000006582, 0.00000242410173],
+    [-0.000551, -0.238565, 0.435739,
+     0.99994704, -0.01118251, -0.00485767],
+    [0.238514, -0.002667, -0.008541,
+     0.01118251, 0.99995883, -0.00002718],
+    [-0.435623, 0.012254, 0.002117,
+     0.00485767, -0.00002714, 1.00000956]
+];
+
+///
+/// Transformation of the position and the velocity [arcsec/cy] from the
+/// FK5 (J2000.0) to the FK4 (B1950.0)
+///
+const FK5_TO_FK4: [[f64; 6]; 6] = [
+    [0.9999256795, 0.0111814828, 0.0048590039,
+     -0.00000242389840, -0.00000002710544, -0.00000001177742],
+    [-0.0111814828, 0.9999374849, -0.0000271771,
+     0.00000002710544, -0.00000242392702, 0.00000000006585],
+    [-0.0048590040, -0.0000271558, 0.9999881946,
+     0.00000001177742, 0.00000000006585, -0.00000242404995],
+    [-0.000551, 0.238509, -0.435614,
+     0.99990432, 0.01118145, 0.00485852],
+    [-0.238560, -0.002667, 0.012254,
+     -0.01118145, 0.99991613, -0.00002717],
+    [0.435730, -0.008541, 0.002117,
+     -0.00485852, -0.00002716, 0.99996684]
+];
+
+///
+/// Orientation of the FK5 with respect to the Hipparcos frame (ICRS) at
+/// J2000.0 [mas] and its spin [mas/yr] (Mignard and Froeschle 2000)
+///
+const FK5_ORIENTATION: [f64; 3] = [-19.9, -9.1, 22.9];
+const FK5_SPIN: [f64; 3] = [-0.30, 0.60, 0.70];
+
+///
+/// Rotation matrix of the rotation vector [rad] (the axis multiplied by the
+/// angle)
+///
+fn rotation_vector(w: Vec3D) -> Mat3D {
+    let phi = w.norm();
+    if phi == 0.0 {
+        return Mat3D::identity();
+    }
+
+    let c: CartesianVec3D = (w * (1.0 / phi)).into();
+    let (x, y, z) = (c.x(), c.y(), c.z());
+    let (s, co) = phi.sin_cos();
+    let f = 1.0 - co;
+
+    Mat3D::from_rows(
+        Vec3D::cartesian(x * x * f + co, x * y * f + z * s, x * z * f - y * s),
+        Vec3D::cartesian(x * y * f - z * s, y * y * f + co, y * z * f + x * s),
+        Vec3D::cartesian(x * z * f + y * s, y * z * f - x * s, z * z * f + co)
+    )
+}
+
+fn vector(v: [f64; 3]) -> Vec3D {
+    Vec3D::cartesian(v[0], v[1], v[2])
+}
+
+///
+/// fk5_to_icrs_matrix: Transformation from the FK5 (J2000.0) to the ICRS for
+/// the positions of the epoch, including the spin of the FK5
+///
+pub fn fk5_to_icrs_matrix(epoch: JulianDate) -> Mat3D {
+    let years = (epoch.jd() - JD_J2000) / JULIAN_YEAR;
+    let mas = 1e-3 / ARCS;
+
+    rotation_vector(vector(FK5_ORIENTATION) * mas) *
+        rotation_vector(vector(FK5_SPIN) * (mas * years))
+}
+
+///
+/// fk5_to_icrs: Catalogue place of a FK5 (J2000.0) star referred to the
+/// ICRS at the same epoch
+///
+pub fn fk5_to_icrs(star: &Star) -> Result<Star> {
+    let (r, v) = star.state();
+    let rotation = fk5_to_icrs_matrix(star.epoch());
+    let spin = vector(FK5_SPIN) * (1e-3 / ARCS / JULIAN_YEAR);
+
+    let icrs = Star::from_state(
+        rotation * r, rotation * (v + r.cross(spin)), star.epoch()
+    )?;
+    Ok(keep_distance(star, icrs))
+}
+
+///
+/// icrs_to_fk5: Catalogue place of a star referred to the FK5 (J2000.0) at
+/// the same epoch
+///
+pub fn icrs_to_fk5(star: &Star) -> Result<Star> {
+    let (r, v) = star.state();
+    let rotation = fk5_to_icrs_matrix(star.epoch()).t();
+    let spin = vector(FK5_SPIN) * (1e-3 / ARCS / JULIAN_YEAR);
+
+    let r5 = rotation * r;
+    let fk5 = Star::from_state(r5, rotation * v + spin.cross(r5),
+                               star.epoch())?;
+    Ok(keep_distance(star, fk5))
+}
+
+///
+/// The stars without a parallax keep the parallax and the radial velocity
+///
+fn keep_distance(star: &Star, converted: Star) -> Star {
+    if star.parallax() > 0.0 {
+        converted
+    } else {
+        converted
+            .with_parallax(star.parallax())
+            .with_radial_velocity(star.radial_velocity())
+    }
+}
+
+///
+/// Position (a unit vector) and velocity [arcsec/cy] of a star with the
+/// proper motions scaled by the ratio of the years
+///
+fn catalogue_vectors(star: &Star, year: f64) -> (Vec3D, Vec3D) {
+    let alpha: f64 = star.right_ascension().into();
+    let delta: f64 = star.declination().into();
+    let (sa, ca) = alpha.sin_cos();
+    let (sd, cd) = delta.sin_cos();
+
+    let u = star.direction();
+    let mu_a = star.pm_ra() * 0.1 * year;
+    let mu_d = star.pm_dec() * 0.1 * year;
+    let w = star.radial_velocity() * star.parallax() * 1e-3 * VF;
+
+    let v = Vec3D::cartesian(
+        -mu_a * sa - mu_d * sd * ca,
+        mu_a * ca - mu_d * sd * sa,
+        mu_d * cd
+    ) + u * w;
+
+    (u, v)
+}
+
+///
+/// Star from the position and the velocity [arcsec/cy] with the proper
+/// motions scaled by the ratio of the years
+///
+fn catalogue_star(star: &Star, (r, v): (Vec3D, Vec3D), year: f64,
+                  epoch: JulianDate) -> Result<Star> {
+    let distance = r.norm();
+    let s: SphericalVec3D = r.into();
+    let (sa, ca) = s.phi().sin_cos();
+    let (sd, cd) = s.theta().sin_cos();
+    let east = Vec3D::cartesian(-sa, ca, 0.0);
+    let north = Vec3D::cartesian(-sd * ca, -sd * sa, cd);
+    let u = (r / distance)?;
+
+    let mut parallax = star.parallax();
+    let mut radial_velocity = star.radial_velocity();
+    if parallax * 1e-3 > TINY {
+        radial_velocity = v.dot(u) / (parallax * 1e-3 * VF);
+        parallax /= distance;
+    }
+
+    Ok(Star::new(Angle::from(s.phi().fmod(PI2)), Angle::from(s.theta()))
+        .with_proper_motion(v.dot(east) / distance * 10.0 / year,
+                            v.dot(north) / distance * 10.0 / year)
+        .with_parallax(parallax)
+        .with_radial_velocity(radial_velocity)
+        .with_epoch(epoch))
+}
+
+fn transform(m: &[[f64; 6]; 6], (r, v): (Vec3D, Vec3D)) -> (Vec3D, Vec3D) {
+    let r: CartesianVec3D = r.into();
+    let v: CartesianVec3D = v.into();
+    let x = [r.x(), r.y(), r.z(), v.x(), v.y(), v.z()];
+    let y: Vec<f64> = m.iter()
+        .map(|row| row.iter().zip(x.iter()).map(|(a, b)| a * b).sum())
+        .collect();
+
+    (Vec3D::cartesian(y[0], y[1], y[2]), Vec3D::cartesian(y[3], y[4], y[5]))
+}
+
+///
+/// fk4_to_fk5: Catalogue place of a FK4 (B1950.0) star referred to the FK5
+/// (J2000.0) at the epoch J2000.0 (Standish 1982). The E-terms of the
+/// aberration are removed and the star is first carried to the epoch
+/// B1950.0. The proper motions of the stars are per Julian year.
+///
+pub fn fk4_to_fk5(star: &Star) -> Result<Star> {
+    let b1950 = Epoch::b1950().jd();
+    let star = if star.epoch() == b1950 {
+        *star
+    } else {
+        star.propagate(b1950)?
+    };
+
+    let (r0, v0) = catalogue_vectors(&star, TROPICAL_YEAR / JULIAN_YEAR);
+    let a = vector(E_TERMS);
+    let a_dot = vector(E_TERMS_RATE);
+    let r1 = r0 - a + r0 * r0.dot(a);
+    let v1 = v0 - a_dot + r0 * r0.dot(a_dot);
+
+    catalogue_star(&star, transform(&FK4_TO_FK5, (r1, v1)), 1.0,
+                   JulianDate::j2000())
+}
+
+///
+/// fk5_to_fk4: Catalogue place of a FK5 (J2000.0) star referred to the FK4
+/// (B1950.0) at the epoch B1950.0 including the E-terms of the aberration.
+/// The star is first carried to the epoch J2000.0.
+///
+pub fn fk5_to_fk4(star: &Star) -> Result<Star> {
+    let star = if star.epoch() == JulianDate::j2000() {
+        *star
+    } else {
+        star.propagate(JulianDate::j2000())?
+    };
+
+    let (r1, v1) = transform(&FK5_TO_FK4, catalogue_vectors(&star, 1.0));
+    let a = vector(E_TERMS);
+    let a_dot = vector(E_TERMS_RATE);
+
+    // E-terms with the length of the position iterated once
+    let p = r1 + a * r1.norm() - r1 * r1.dot(a);
+    let w = p.norm();
+    let r2 = r1 + a * w - r1 * r1.dot(a);
+    let v2 = v1 + a_dot * w - r2 * r2.dot(a_dot);
+
+    catalogue_star(&star, (r2, v2), TROPICAL_YEAR / JULIAN_YEAR,
+                   Epoch::b1950().jd())
+}
+
+///
+/// fk4_to_fk5_direction: Direction of a FK4 (B1950.0) place observed at the
+/// epoch referred to the FK5 (J2000.0), assuming no proper motion in the
+/// FK5 (Seidelmann 1992, 3.591)
+///
+pub fn fk4_to_fk5_direction(u: Vec3D, epoch: JulianDate) -> Result<Vec3D> {
+    let r0 = (u / u.norm())?;
+
+    // E-terms adjusted to give no proper motion in the FK5
+    let w = (epoch - Epoch::b1950().jd()) / TROPICAL_YEAR / PMF;
+    let a = vector(E_TERMS) + vector(E_TERMS_RATE) * w;
+    let p = r0 - (a - r0 * r0.dot(a));
+
+    let (r, v) = transform(&FK4_TO_FK5, (p, Vec3D::zero()));
+
+    // Fictitious proper motion of the FK5
+    let w = (epoch.jd() - JD_J2000) / JULIAN_YEAR / PMF;
+    let r = r + v * w;
+
+    r / r.norm()
+}
+
+///
+/// fk5_to_fk4_direction: Direction of a FK5 (J2000.0) place without the
+/// proper motion referred to the FK4 (B1950.0) at the epoch
+///
+pub fn fk5_to_fk4_direction(u: Vec3D, epoch: JulianDate) -> Result<Vec3D> {
+    let s: SphericalVec3D = u.into();
+    let star = fk5_to_fk4(
+        &Star::new(Angle::from(s.phi()), Angle::from(s.theta()))
+    )?;
+    let (r, v) = catalogue_vectors(&star, 1.0);
+
+    // Fictitious proper motion of the FK4 [arcsec/cy]
+    let r = r + v * ((epoch - Epoch::b1950().jd()) / JULIAN_YEAR / PMF);
+
+    r / r.norm()
+}
@@ -0,0 +1,87 @@
+use crate::base::consts::ARCS;
+use crate::base::linalg::Mat3D;
+use crate::time::JulianDate;
+
+///
+/// obliquity: Mean obliquity of the ecliptic according to the IAU 2006
+/// precession model [rad]
+///
+pub fn obliquity(jd: JulianDate) -> f64 {
+    let t = jd.centuries();
+
+    (84381.406 + t * (-46.836769 + t * (-0.0001831 + t * (0.00200340 +
+        t * (-0.000000576 - t * 0.0000000434))))) / ARCS
+}
+
+///
+/// precession_angles: Equatorial precession angles zeta, z and theta of the
+/// IAU 2006 model from J2000 to the date [rad]
+///
+pub fn precession_angles(jd: JulianDate) -> (f64, f64, f64) {
+    let t = jd.centuries();
+
+    let zeta = 2.650545 + t * (2306.083227 + t * (0.2988499 +
+        t * (0.01801828 + t * (-0.000005971 - t * 0.0000003173))));
+    let z = -2.650545 + t * (2306.077181 + t * (1.0927348 +
+        t * (0.01826837 + t * (-0.000028596 - t * 0.0000002904))));
+    let theta = t * (2004.191903 + t * (-0.4294934 + t * (-0.04182264 +
+        t * (-0.000007089 - t * 0.0000001274))));
+
+    (zeta / ARCS, z / ARCS, theta / ARCS)
+}
+
+///
+/// fukushima_williams_angles: Precession angles gamma, phi and psi of the
+/// IAU 2006 model including the frame bias and the mean obliquity of the
+/// date (Fukushima-Williams parameterization) [rad]
+///
+pub fn fukushima_williams_angles(jd: JulianDate) -> (f64, f64, f64, f64) {
+    let t = jd.centuries();
+
+    let gamma = -0.052928 + t * (10.556378 + t * (0.4932044 +
+        t * (-0.00031238 + t * (-0.000002788 + t * 0.0000000260))));
+    let phi = 84381.412819 + t * (-46.811016 + t * (0.0511268 +
+        t * (0.00053289 + t * (-0.000000440 - t * 0.0000000176))));
+    let psi = -0.041775 + t * (5038.481484 + t * (1.5584175 +
+        t * (-0.00018522 + t * (-0.000026452 - t * 0.0000000148))));
+
+    (gamma / ARCS, phi / ARCS, psi / ARCS, obliquity(jd))
+}
+
+///
+/// precession_matrix: Transformation from the mean equator and equinox of
+/// J2000 to the mean equator and equinox of the date
+///
+pub fn precession_matrix(jd: JulianDate) -> Mat3D {
+    let (zeta, z, theta) = precession_angles(jd);
+    Mat3D::r_z(-z) * Mat3D::r_y(theta) * Mat3D::r_z(-zeta)
+}
+
+///
+/// frame_bias_matrix: Transformation from the ICRS to the mean equator and
+/// equinox of J2000 (IERS Conventions 2003)
+///
+pub fn frame_bias_matrix() -> Mat3D {
+    let dpsi = -0.041775 / ARCS;
+    let deps = -0.0068192 / ARCS;
+    let dra = -0.0146 / ARCS;
+
+    Mat3D::r_x(-deps) * Mat3D::r_y(dpsi * (84381.448 / ARCS).sin()) *
+        Mat3D::r_z(dra)
+}
+
+///
+/// equatorial_to_ecliptic: Transformation from the mean equator to the mean
+/// ecliptic of the date
+///
+pub fn equatorial_to_ecliptic(jd: JulianDate) -> Mat3D {
+    Mat3D::r_x(obliquity(jd))
+}
+
+///
+/// ecliptic_to_equatorial: Transformation from the mean ecliptic to the mean
+/// equator of the date
+///
+pub fn ecliptic_to_equatorial(jd: JulianDate) -> Mat3D {
+    Mat3D::r_x(-obliquity(jd))
+}
@@ -0,0 +1,136 @@
+use std::ops;
+
+use crate::base::linalg::{Mat3D, Vec3D};
+
+///
+/// Matrix of the cross product with the vector: [v]x r = v x r
+///
+fn cross_matrix(v: Vec3D) -> Mat3D {
+    Mat3D::from_rows(
+        Vec3D::unit_x().cross(v),
+        Vec3D::unit_y().cross(v),
+        Vec3D::unit_z().cross(v)
+    )
+}
+
+///
+/// RotatingFrame: Transformation to a frame which rotates with respect to
+/// the original one: the rotation matrix R and its time derivative dR/dt,
+/// so that r' = R r and v' = R v + dR/dt r. The time unit of the derivative
+/// is the one of the velocities.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RotatingFrame {
+    matrix: Mat3D,
+    derivative: Mat3D
+}
+
+impl RotatingFrame {
+    pub fn new(matrix: Mat3D, derivative: Mat3D) -> RotatingFrame {
+        RotatingFrame {
+            matrix,
+            derivative
+        }
+    }
+
+    ///
+    /// fixed: Transformation to a frame which does not rotate
+    ///
+    pub fn fixed(matrix: Mat3D) -> RotatingFrame {
+        RotatingFrame::new(matrix, Mat3D::zeros())
+    }
+
+    ///
+    /// from_angular_velocity: Transformation to a frame which rotates with
+    /// the angular velocity referred to the original frame
+    ///
+    pub fn from_angular_velocity(matrix: Mat3D,
+                                 omega: Vec3D) -> RotatingFrame {
+        RotatingFrame::new(matrix, matrix * cross_matrix(omega) * -1.0)
+    }
+
+    pub fn matrix(&self) -> Mat3D {
+        self.matrix
+    }
+
+    pub fn derivative(&self) -> Mat3D {
+        self.derivative
+    }
+
+    ///
+    /// angular_velocity: Angular velocity of the rotating frame referred
+    /// to the original frame
+    ///
+    pub fn angular_velocity(&self) -> Vec3D {
+        let w = (self.matrix.t() * self.derivative * -1.0)
+            .iter()
+            .collect::<Vec<f64>>();
+
+        // Mean of the antisymmetric elements
+        Vec3D::cartesian(
+            (w[7] - w[5]) / 2.0,
+            (w[2] - w[6]) / 2.0,
+            (w[3] - w[1]) / 2.0
+        )
+    }
+
+    ///
+    /// inverse: Transformation from the rotating frame back to the original
+    /// one
+    ///
+    pub fn inverse(&self) -> RotatingFrame {
+        RotatingFrame::new(self.matrix.t(), self.derivative.t())
+    }
+
+    ///
+    /// position: Position in the rotating frame
+    ///
+    pub fn position(&self, r: Vec3D) -> Vec3D {
+        self.matrix * r
+    }
+
+    ///
+    /// velocity: Velocity in the rotating frame of the body with the
+    /// position and the velocity
+    ///
+    pub fn velocity(&self, r: Vec3D, v: Vec3D) -> Vec3D {
+        self.matrix * v + self.derivative * r
+    }
+
+    ///
+    /// transform: Position and velocity in the rotating frame
+    ///
+    pub fn transform(&self, r: Vec3D, v: Vec3D) -> (Vec3D, Vec3D) {
+        (self.position(r), self.velocity(r, v))
+    }
+}
+
+///
+/// Composition: the right transformation is applied first
+///
+impl ops::Mul for RotatingFrame {
+    type Output = Self;
+
+    fn mul(self, rhs: RotatingFrame) -> RotatingFrame {
+        RotatingFrame::new(
+            self.matrix * rhs.matrix,
+            self.derivative * rhs.matrix + self.matrix * rhs.derivative
+        )
+    }
+}
+
+impl ops::Mul<RotatingFrame> for Mat3D {
+    type Output = RotatingFrame;
+
+    fn mul(self, rhs: RotatingFrame) -> RotatingFrame {
+        RotatingFrame::fixed(self) * rhs
+    }
+}
+
+impl ops::Mul<Mat3D> for RotatingFrame {
+    type Output = RotatingFrame;
+
+    fn mul(self, rhs: Mat3D) -> RotatingFrame {
+        self * RotatingFrame::fixed(rhs)
+    }
+}
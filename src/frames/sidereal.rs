@@ -0,0 +1,43 @@
+use crate::base::Real;
+use crate::base::consts::{JD_J2000, PI2, RAD};
+use crate::frames::nutation::nutation;
+use crate::frames::precession::obliquity;
+use crate::time::JulianDate;
+
+///
+/// gmst: Greenwich mean sidereal time for the UT1 date [rad]
+///
+pub fn gmst(ut: JulianDate) -> f64 {
+    let d = ut.jd() - JD_J2000;
+    let t = ut.centuries();
+
+    let theta = 280.46061837 + 360.98564736629 * d +
+        t * t * (0.000387933 - t / 38710000.0);
+    (theta * RAD).fmod(PI2)
+}
+
+///
+/// earth_rotation_angle: Angle between the CIO and the TIO for the UT1
+/// date (IAU 2000) [rad]
+///
+pub fn earth_rotation_angle(ut: JulianDate) -> f64 {
+    let d = ut.jd() - JD_J2000;
+
+    (PI2 * (d.frac() + 0.7790572732640 + 0.00273781191135448 * d)).fmod(PI2)
+}
+
+///
+/// equation_of_equinoxes: Difference between the apparent and the mean
+/// sidereal time for the TT date [rad]
+///
+pub fn equation_of_equinoxes(jd: JulianDate) -> f64 {
+    let (dpsi, deps) = nutation(jd);
+    dpsi * (obliquity(jd) + deps).cos()
+}
+
+///
+/// gast: Greenwich apparent sidereal time for the UT1 date [rad]
+///
+pub fn gast(ut: JulianDate) -> f64 {
+    (gmst(ut) + equation_of_equinoxes(ut.ut_to_tt())).fmod(PI2)
+}
@@ -0,0 +1,532 @@
+use std::f64::consts::FRAC_PI_2;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops;
+
+use crate::base::Result;
+use crate::base::consts::AU;
+use crate::base::linalg::{Mat3D, Norm, Vec3D};
+use crate::ephemeris::{Body, Ephemeris};
+use crate::ephemeris::state::merge_tags;
+use crate::frames::cio::CelestialToTerrestrial;
+use crate::frames::precession::{
+    frame_bias_matrix, obliquity, precession_matrix
+};
+use crate::observer::Observer;
+use crate::time::JulianDate;
+
+///
+/// ReferenceFrame: Orientation of the axes of a tagged vector
+///
+pub trait ReferenceFrame: Copy + fmt::Debug {}
+
+///
+/// Origin: Centre of a tagged position
+///
+pub trait Origin: Copy + fmt::Debug {}
+
+macro_rules! impl_tags {
+    ($tag:ident: $($(#[$meta:meta])* $name:ident),*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+            pub struct $name;
+
+            impl $tag for $name {}
+        )*
+    };
+}
+
+impl_tags!(ReferenceFrame:
+    ///
+    /// Icrs: International celestial reference system
+    ///
+    Icrs,
+    ///
+    /// EclipticOfDate: Mean ecliptic and equinox of the date
+    ///
+    EclipticOfDate,
+    ///
+    /// Itrs: International terrestrial reference system
+    ///
+    Itrs,
+    ///
+    /// Horizontal: Local horizon of an observer (x to the south, y to the
+    /// east, z to the zenith)
+    ///
+    Horizontal
+);
+
+impl_tags!(Origin:
+    Barycentre,
+    Heliocentre,
+    Geocentre,
+    Topocentre
+);
+
+///
+/// Position: Position of a point relative to the origin O referred to the
+/// frame F, optionally tagged with the TT date of the point and of the
+/// frame. The frames and the origins are checked at compile time, the
+/// dates when the values are combined: the operations fail with
+/// Error::TagMismatchError if both operands are tagged with different dates.
+///
+/// ```
+/// use ephem::base::linalg::Vec3D;
+/// use ephem::frames::tagged::*;
+///
+/// let a = Position::<Icrs, Geocentre>::new(Vec3D::unit_x());
+/// let b = Position::<Icrs, Geocentre>::new(Vec3D::unit_y());
+/// let step: Displacement<Icrs> = (a - b).unwrap();
+/// let c: Position<Icrs, Geocentre> = (b + step).unwrap();
+/// ```
+///
+/// The positions relative to different origins cannot be added:
+///
+/// ```compile_fail
+/// use ephem::base::linalg::Vec3D;
+/// use ephem::frames::tagged::*;
+///
+/// let a = Position::<Icrs, Geocentre>::new(Vec3D::unit_x());
+/// let b = Position::<Icrs, Heliocentre>::new(Vec3D::unit_y());
+/// let sum = a + b;
+/// ```
+///
+/// nor the positions referred to different frames subtracted:
+///
+/// ```compile_fail
+/// use ephem::base::linalg::Vec3D;
+/// use ephem::frames::tagged::*;
+///
+/// let a = Position::<Icrs, Geocentre>::new(Vec3D::unit_x());
+/// let b = Position::<Itrs, Geocentre>::new(Vec3D::unit_y());
+/// let step = a - b;
+/// ```
+///
+#[derive(Debug, Copy, Clone)]
+pub struct Position<F: ReferenceFrame, O: Origin> {
+    vector: Vec3D,
+    epoch: Option<JulianDate>,
+    tags: PhantomData<(F, O)>
+}
+
+///
+/// Displacement: Free vector referred to the frame F, the difference of two
+/// positions with the same origin, optionally tagged with the TT date
+///
+#[derive(Debug, Copy, Clone)]
+pub struct Displacement<F: ReferenceFrame> {
+    vector: Vec3D,
+    epoch: Option<JulianDate>,
+    frame: PhantomData<F>
+}
+
+///
+/// OriginShift: Position of the origin A relative to the origin B referred
+/// to the frame F, which moves the positions from A to B, optionally tagged
+/// with the TT date
+///
+#[derive(Debug, Copy, Clone)]
+pub struct OriginShift<F: ReferenceFrame, A: Origin, B: Origin> {
+    vector: Vec3D,
+    epoch: Option<JulianDate>,
+    tags: PhantomData<(F, A, B)>
+}
+
+///
+/// Rotation: Transformation from the frame A to the frame B, tagged with
+/// the TT date of the frames of date. The rotations can only be created for
+/// the supported pairs of frames, their inverses and compositions, and only
+/// be applied to the vectors of the frame A:
+///
+/// ```compile_fail
+/// use ephem::base::angle::Angle;
+/// use ephem::base::linalg::Vec3D;
+/// use ephem::frames::tagged::*;
+/// use ephem::observer::Observer;
+///
+/// let observer = Observer::new(Angle::from_ad(2.35), Angle::from_ad(48.85),
+///                              35.0);
+/// let star = Position::<Icrs, Geocentre>::new(Vec3D::unit_x());
+/// let horizontal = Rotation::horizontal(&observer) * star;
+/// ```
+///
+#[derive(Debug, Copy, Clone)]
+pub struct Rotation<A: ReferenceFrame, B: ReferenceFrame> {
+    matrix: Mat3D,
+    epoch: Option<JulianDate>,
+    frames: PhantomData<(A, B)>
+}
+
+impl<F: ReferenceFrame, O: Origin> Position<F, O> {
+    ///
+    /// new: Unchecked entry point of the untagged vectors: the caller
+    /// asserts that the vector is relative to the origin O and referred to
+    /// the frame F
+    ///
+    pub fn new(vector: Vec3D) -> Position<F, O> {
+        Position {
+            vector,
+            epoch: None,
+            tags: PhantomData
+        }
+    }
+
+    pub fn with_epoch(mut self, epoch: JulianDate) -> Self {
+        self.epoch = Some(epoch);
+        self
+    }
+
+    pub fn vector(&self) -> Vec3D {
+        self.vector
+    }
+
+    pub fn epoch(&self) -> Option<JulianDate> {
+        self.epoch
+    }
+
+    ///
+    /// shift: Same point relative to the origin B
+    ///
+    pub fn shift<B: Origin>(self, shift: OriginShift<F, O, B>)
+        -> Result<Position<F, B>>
+    {
+        Ok(Position {
+            vector: self.vector + shift.vector,
+            epoch: merge_tags(self.epoch, shift.epoch)?,
+            tags: PhantomData
+        })
+    }
+}
+
+impl<F: ReferenceFrame> Displacement<F> {
+    ///
+    /// new: Unchecked entry point of the untagged vectors: the caller
+    /// asserts that the vector is referred to the frame F
+    ///
+    pub fn new(vector: Vec3D) -> Displacement<F> {
+        Displacement {
+            vector,
+            epoch: None,
+            frame: PhantomData
+        }
+    }
+
+    pub fn with_epoch(mut self, epoch: JulianDate) -> Self {
+        self.epoch = Some(epoch);
+        self
+    }
+
+    pub fn vector(&self) -> Vec3D {
+        self.vector
+    }
+
+    pub fn epoch(&self) -> Option<JulianDate> {
+        self.epoch
+    }
+}
+
+impl<F: ReferenceFrame, A: Origin, B: Origin> OriginShift<F, A, B> {
+    ///
+    /// new: Unchecked entry point of the untagged vectors: the caller
+    /// asserts that the vector is the position of the origin A relative to
+    /// the origin B referred to the frame F
+    ///
+    pub fn new(vector: Vec3D) -> OriginShift<F, A, B> {
+        OriginShift {
+            vector,
+            epoch: None,
+            tags: PhantomData
+        }
+    }
+
+    pub fn with_epoch(mut self, epoch: JulianDate) -> Self {
+        self.epoch = Some(epoch);
+        self
+    }
+
+    pub fn vector(&self) -> Vec3D {
+        self.vector
+    }
+
+    pub fn epoch(&self) -> Option<JulianDate> {
+        self.epoch
+    }
+
+    ///
+    /// inverse: Position of the origin B relative to the origin A
+    ///
+    pub fn inverse(self) -> OriginShift<F, B, A> {
+        OriginShift {
+            vector: self.vector * -1.0,
+            epoch: self.epoch,
+            tags: PhantomData
+        }
+    }
+
+    ///
+    /// then: Shift from the origin A to the origin C through B
+    ///
+    pub fn then<C: Origin>(self, next: OriginShift<F, B, C>)
+        -> Result<OriginShift<F, A, C>>
+    {
+        Ok(OriginShift {
+            vector: self.vector + next.vector,
+            epoch: merge_tags(self.epoch, next.epoch)?,
+            tags: PhantomData
+        })
+    }
+}
+
+impl OriginShift<Icrs, Geocentre, Barycentre> {
+    ///
+    /// earth: Barycentric position of the Earth for the TDB date [AU]
+    ///
+    pub fn earth<E>(ephemeris: &E, jd: JulianDate) -> Result<Self>
+        where E: Ephemeris + ?Sized
+    {
+        Ok(OriginShift::new(ephemeris.position(Body::Earth, jd)?)
+            .with_epoch(jd))
+    }
+}
+
+impl OriginShift<Icrs, Heliocentre, Barycentre> {
+    ///
+    /// sun: Barycentric position of the Sun for the TDB date [AU]
+    ///
+    pub fn sun<E>(ephemeris: &E, jd: JulianDate) -> Result<Self>
+        where E: Ephemeris + ?Sized
+    {
+        Ok(OriginShift::new(ephemeris.position(Body::Sun, jd)?)
+            .with_epoch(jd))
+    }
+}
+
+impl OriginShift<Itrs, Topocentre, Geocentre> {
+    ///
+    /// observer: Geocentric position of the observer [AU], fixed in the
+    /// ITRS and therefore untagged
+    ///
+    pub fn observer(observer: &Observer) -> Self {
+        OriginShift::new(observer.geocentric() * (1.0 / AU))
+    }
+}
+
+impl<A: ReferenceFrame, B: ReferenceFrame> Rotation<A, B> {
+    fn new(matrix: Mat3D, epoch: Option<JulianDate>) -> Rotation<A, B> {
+        Rotation {
+            matrix,
+            epoch,
+            frames: PhantomData
+        }
+    }
+
+    pub fn matrix(&self) -> Mat3D {
+        self.matrix
+    }
+
+    pub fn epoch(&self) -> Option<JulianDate> {
+        self.epoch
+    }
+
+    ///
+    /// inverse: Transformation from the frame B back to the frame A
+    ///
+    pub fn inverse(self) -> Rotation<B, A> {
+        Rotation::new(self.matrix.t(), self.epoch)
+    }
+}
+
+impl Rotation<Icrs, EclipticOfDate> {
+    ///
+    /// ecliptic: Transformation to the mean ecliptic and equinox of the TT
+    /// date
+    ///
+    pub fn ecliptic(jd: JulianDate) -> Self {
+        Rotation::new(
+            Mat3D::r_x(obliquity(jd)) * precession_matrix(jd) *
+                frame_bias_matrix(),
+            Some(jd)
+        )
+    }
+}
+
+impl Rotation<Icrs, Itrs> {
+    ///
+    /// terrestrial: Transformation to the ITRS of the celestial to
+    /// terrestrial transformation
+    ///
+    pub fn terrestrial(c2t: &CelestialToTerrestrial) -> Self {
+        Rotation::new(c2t.matrix(), Some(c2t.jd()))
+    }
+}
+
+impl Rotation<Itrs, Horizontal> {
+    ///
+    /// horizontal: Transformation to the local horizon of the observer,
+    /// which does not depend on the date
+    ///
+    pub fn horizontal(observer: &Observer) -> Self {
+        let longitude: f64 = observer.longitude().into();
+        let latitude: f64 = observer.latitude().into();
+
+        Rotation::new(
+            Mat3D::r_y(FRAC_PI_2 - latitude) * Mat3D::r_z(longitude), None
+        )
+    }
+}
+
+impl<F: ReferenceFrame, O: Origin> Norm for Position<F, O> {
+    fn norm(&self) -> f64 {
+        self.vector.norm()
+    }
+}
+
+impl<F: ReferenceFrame> Norm for Displacement<F> {
+    fn norm(&self) -> f64 {
+        self.vector.norm()
+    }
+}
+
+impl<F: ReferenceFrame, O: Origin> From<Position<F, O>> for Vec3D {
+    fn from(position: Position<F, O>) -> Self {
+        position.vector
+    }
+}
+
+impl<F: ReferenceFrame> From<Displacement<F>> for Vec3D {
+    fn from(displacement: Displacement<F>) -> Self {
+        displacement.vector
+    }
+}
+
+impl<F: ReferenceFrame, O: Origin> ops::Sub for Position<F, O> {
+    type Output = Result<Displacement<F>>;
+
+    fn sub(self, rhs: Position<F, O>) -> Self::Output {
+        Ok(Displacement {
+            vector: self.vector - rhs.vector,
+            epoch: merge_tags(self.epoch, rhs.epoch)?,
+            frame: PhantomData
+        })
+    }
+}
+
+impl<F, O> ops::Add<Displacement<F>> for Position<F, O>
+    where F: ReferenceFrame, O: Origin
+{
+    type Output = Result<Position<F, O>>;
+
+    fn add(self, rhs: Displacement<F>) -> Self::Output {
+        Ok(Position {
+            vector: self.vector + rhs.vector,
+            epoch: merge_tags(self.epoch, rhs.epoch)?,
+            tags: PhantomData
+        })
+    }
+}
+
+impl<F, O> ops::Sub<Displacement<F>> for Position<F, O>
+    where F: ReferenceFrame, O: Origin
+{
+    type Output = Result<Position<F, O>>;
+
+    fn sub(self, rhs: Displacement<F>) -> Self::Output {
+        self + rhs * -1.0
+    }
+}
+
+impl<F, A, B> ops::Add<OriginShift<F, A, B>> for Position<F, A>
+    where F: ReferenceFrame, A: Origin, B: Origin
+{
+    type Output = Result<Position<F, B>>;
+
+    fn add(self, rhs: OriginShift<F, A, B>) -> Self::Output {
+        self.shift(rhs)
+    }
+}
+
+impl<F: ReferenceFrame> ops::Add for Displacement<F> {
+    type Output = Result<Displacement<F>>;
+
+    fn add(self, rhs: Displacement<F>) -> Self::Output {
+        Ok(Displacement {
+            vector: self.vector + rhs.vector,
+            epoch: merge_tags(self.epoch, rhs.epoch)?,
+            frame: PhantomData
+        })
+    }
+}
+
+impl<F: ReferenceFrame> ops::Sub for Displacement<F> {
+    type Output = Result<Displacement<F>>;
+
+    fn sub(self, rhs: Displacement<F>) -> Self::Output {
+        self + rhs * -1.0
+    }
+}
+
+impl<F: ReferenceFrame> ops::Mul<f64> for Displacement<F> {
+    type Output = Displacement<F>;
+
+    fn mul(self, rhs: f64) -> Displacement<F> {
+        Displacement {
+            vector: self.vector * rhs,
+            ..self
+        }
+    }
+}
+
+impl<A, B, C> ops::Mul<Rotation<A, B>> for Rotation<B, C>
+    where A: ReferenceFrame, B: ReferenceFrame, C: ReferenceFrame
+{
+    type Output = Result<Rotation<A, C>>;
+
+    fn mul(self, rhs: Rotation<A, B>) -> Self::Output {
+        Ok(Rotation::new(
+            self.matrix * rhs.matrix, merge_tags(self.epoch, rhs.epoch)?
+        ))
+    }
+}
+
+impl<A, B, O> ops::Mul<Position<A, O>> for Rotation<A, B>
+    where A: ReferenceFrame, B: ReferenceFrame, O: Origin
+{
+    type Output = Result<Position<B, O>>;
+
+    fn mul(self, rhs: Position<A, O>) -> Self::Output {
+        Ok(Position {
+            vector: self.matrix * rhs.vector,
+            epoch: merge_tags(self.epoch, rhs.epoch)?,
+            tags: PhantomData
+        })
+    }
+}
+
+impl<A, B> ops::Mul<Displacement<A>> for Rotation<A, B>
+    where A: ReferenceFrame, B: ReferenceFrame
+{
+    type Output = Result<Displacement<B>>;
+
+    fn mul(self, rhs: Displacement<A>) -> Self::Output {
+        Ok(Displacement {
+            vector: self.matrix * rhs.vector,
+            epoch: merge_tags(self.epoch, rhs.epoch)?,
+            frame: PhantomData
+        })
+    }
+}
+
+impl<A, B, O1, O2> ops::Mul<OriginShift<A, O1, O2>> for Rotation<A, B>
+    where A: ReferenceFrame, B: ReferenceFrame, O1: Origin, O2: Origin
+{
+    type Output = Result<OriginShift<B, O1, O2>>;
+
+    fn mul(self, rhs: OriginShift<A, O1, O2>) -> Self::Output {
+        Ok(OriginShift {
+            vector: self.matrix * rhs.vector,
+            epoch: merge_tags(self.epoch, rhs.epoch)?,
+            tags: PhantomData
+        })
+    }
+}
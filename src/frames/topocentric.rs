@@ -0,0 +1,74 @@
+use crate::base::Real;
+use crate::base::angle::Angle;
+use crate::base::consts::{AU, PI2, R_EARTH};
+use crate::base::linalg::{Mat3D, Norm, SphericalVec3D, Vec3D};
+use crate::frames::sidereal::gast;
+use crate::observer::Observer;
+use crate::time::JulianDate;
+
+///
+/// observer_position: Geocentric position of the observer referred to the
+/// true equator and equinox of the TT date [AU]
+///
+pub fn observer_position(observer: &Observer, jd: JulianDate) -> Vec3D {
+    Mat3D::r_z(gast(jd.tt_to_ut())).t() * observer.geocentric() * (1.0 / AU)
+}
+
+///
+/// horizontal_parallax: Equatorial horizontal parallax of a body at the
+/// distance [AU] from the centre of the Earth [rad]
+///
+pub fn horizontal_parallax(distance: f64) -> f64 {
+    (R_EARTH / (distance * AU)).asin()
+}
+
+///
+/// Topocentric: Position of a body seen by an observer on the surface of
+/// the Earth referred to the true equator and equinox of the date with the
+/// topocentric right ascension and declination and the equatorial
+/// horizontal parallax of the body
+///
+#[derive(Debug, Copy, Clone)]
+pub struct Topocentric {
+    position: Vec3D,
+    right_ascension: Angle,
+    declination: Angle,
+    parallax: Angle
+}
+
+impl Topocentric {
+    ///
+    /// new: Topocentric place of a body from its geocentric position
+    /// referred to the true equator and equinox of the TT date [AU]
+    ///
+    pub fn new(observer: &Observer, r: Vec3D, jd: JulianDate) -> Topocentric {
+        let position = r - observer_position(observer, jd);
+        let s: SphericalVec3D = position.into();
+
+        Topocentric {
+            position,
+            right_ascension: Angle::from(s.phi().fmod(PI2)),
+            declination: Angle::from(s.theta()),
+            parallax: Angle::from(horizontal_parallax(r.norm()))
+        }
+    }
+
+    ///
+    /// position: Topocentric position [AU]
+    ///
+    pub fn position(&self) -> Vec3D {
+        self.position
+    }
+
+    pub fn right_ascension(&self) -> Angle {
+        self.right_ascension
+    }
+
+    pub fn declination(&self) -> Angle {
+        self.declination
+    }
+
+    pub fn parallax(&self) -> Angle {
+        self.parallax
+    }
+}
@@ -1,11 +1,19 @@
 #![allow(dead_code)]
 
+pub mod astrometry;
 pub mod base;
+pub mod eclipses;
+pub mod ephemeris;
+pub mod events;
+pub mod frames;
+pub mod observer;
+pub mod time;
 
 #[cfg(test)]
 #[macro_use]
 extern crate approx;
 
+extern crate chrono;
 #[macro_use]
 extern crate ephem_derive;
 extern crate num_traits;
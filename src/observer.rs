@@ -0,0 +1,152 @@
+use crate::base::Result;
+use crate::base::angle::Angle;
+use crate::base::consts::{F_EARTH, R_EARTH};
+use crate::base::error::Error;
+use crate::base::linalg::{CartesianVec3D, Vec3D};
+
+///
+/// Ellipsoid: Reference ellipsoid of the Earth
+///
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Ellipsoid {
+    #[default]
+    WGS84,
+    GRS80
+}
+
+impl Ellipsoid {
+    ///
+    /// semi_major_axis: Equatorial radius of the ellipsoid [km]
+    ///
+    pub fn semi_major_axis(&self) -> f64 {
+        match *self {
+            Ellipsoid::WGS84 => R_EARTH,
+            Ellipsoid::GRS80 => 6378.137
+        }
+    }
+
+    ///
+    /// flattening: Flattening of the ellipsoid
+    ///
+    pub fn flattening(&self) -> f64 {
+        match *self {
+            Ellipsoid::WGS84 => F_EARTH,
+            Ellipsoid::GRS80 => 1.0 / 298.257222101
+        }
+    }
+
+    ///
+    /// eccentricity_squared: Square of the first eccentricity
+    ///
+    pub fn eccentricity_squared(&self) -> f64 {
+        let f = self.flattening();
+        f * (2.0 - f)
+    }
+}
+
+///
+/// Observer: Geographic position of an observer on the Earth. The longitude
+/// is positive to the east, the height above the ellipsoid is given in
+/// metres.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Observer {
+    longitude: Angle,
+    latitude: Angle,
+    height: f64,
+    ellipsoid: Ellipsoid
+}
+
+impl Observer {
+    ///
+    /// new: Observer at the geodetic longitude and latitude referred to the
+    /// WGS84 ellipsoid
+    ///
+    pub fn new(longitude: Angle, latitude: Angle, height: f64) -> Observer {
+        Observer { longitude, latitude, height, ellipsoid: Ellipsoid::WGS84 }
+    }
+
+    ///
+    /// from_geocentric: Observer at the Earth-fixed geocentric position [km]
+    /// with the geodetic coordinates referred to the ellipsoid (closed form
+    /// of Vermeille, 2002)
+    ///
+    pub fn from_geocentric(r: Vec3D,
+                           ellipsoid: Ellipsoid) -> Result<Observer> {
+        let r: CartesianVec3D = r.into();
+        let a = ellipsoid.semi_major_axis();
+        let e2 = ellipsoid.eccentricity_squared();
+        let e4 = e2 * e2;
+        let rho = r.x().hypot(r.y());
+        let z = r.z();
+
+        let p = (rho / a).powi(2);
+        let q = (1.0 - e2) * (z / a).powi(2);
+        let r6 = (p + q - e4) / 6.0;
+        if r6 <= 0.0 {
+            return Err(Error::ConversionError);
+        }
+
+        let s = e4 * p * q / (4.0 * r6.powi(3));
+        let t = (1.0 + s + (s * (2.0 + s)).sqrt()).cbrt();
+        let u = r6 * (1.0 + t + 1.0 / t);
+        let v = (u * u + e4 * q).sqrt();
+        let w = e2 * (u + v - q) / (2.0 * v);
+        let k = (u + v + w * w).sqrt() - w;
+        let d = k * rho / (k + e2);
+        let dz = d.hypot(z);
+
+        Ok(Observer {
+            longitude: Angle::from(r.y().atan2(r.x())),
+            latitude: Angle::from(2.0 * z.atan2(d + dz)),
+            height: 1000.0 * (k + e2 - 1.0) / k * dz,
+            ellipsoid
+        })
+    }
+
+    ///
+    /// with_ellipsoid: Refers the geodetic coordinates to the ellipsoid
+    ///
+    pub fn with_ellipsoid(mut self, ellipsoid: Ellipsoid) -> Self {
+        self.ellipsoid = ellipsoid;
+        self
+    }
+
+    pub fn longitude(&self) -> Angle {
+        self.longitude
+    }
+
+    pub fn latitude(&self) -> Angle {
+        self.latitude
+    }
+
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    pub fn ellipsoid(&self) -> Ellipsoid {
+        self.ellipsoid
+    }
+
+    ///
+    /// geocentric: Earth-fixed geocentric position of the observer referred
+    /// to the Greenwich meridian [km]
+    ///
+    pub fn geocentric(&self) -> Vec3D {
+        let longitude: f64 = self.longitude.into();
+        let latitude: f64 = self.latitude.into();
+        let a = self.ellipsoid.semi_major_axis();
+        let e2 = self.ellipsoid.eccentricity_squared();
+        let h = self.height / 1000.0;
+
+        let (sp, cp) = latitude.sin_cos();
+        let (sl, cl) = longitude.sin_cos();
+        let n = a / (1.0 - e2 * sp * sp).sqrt();
+
+        Vec3D::cartesian(
+            (n + h) * cp * cl,
+            (n + h) * cp * sl,
+            (n * (1.0 - e2) + h) * sp
+        )
+    }
+}
@@ -0,0 +1,486 @@
+use std::{convert, fmt, ops, str};
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::base::Real;
+use crate::base::consts::{
+    DAY, JD_B1900, JD_J2000, JULIAN_CENTURY, JULIAN_YEAR, MJD_OFFSET,
+    TROPICAL_YEAR
+};
+use crate::base::error::Error;
+
+///
+/// Julian date of the Unix epoch 1970-01-01T00:00:00
+///
+const JD_UNIX_EPOCH: f64 = 2440587.5;
+
+///
+/// First Julian date of the Gregorian calendar (1582-10-15)
+///
+const JD_GREGORIAN: f64 = 2299160.5;
+
+///
+/// TT - TAI [s]
+///
+const TT_TAI: f64 = 32.184;
+
+///
+/// TAI - UTC [s] from the modified Julian dates of the changes: the offset
+/// and, before 1972, the reference date and the rate [s/d] of the drift
+///
+const TAI_UTC: [(f64, f64, f64, f64); 42] = [
+    (36934.0, 1.4178180, 37300.0, 0.0012960),
+    (37300.0, 1.4228180, 37300.0, 0.0012960),
+    (37512.0, 1.3728180, 37300.0, 0.0012960),
+    (37665.0, 1.8458580, 37665.0, 0.0011232),
+    (38334.0, 1.9458580, 37665.0, 0.0011232),
+    (38395.0, 3.2401300, 38761.0, 0.0012960),
+    (38486.0, 3.3401300, 38761.0, 0.0012960),
+    (38639.0, 3.4401300, 38761.0, 0.0012960),
+    (38761.0, 3.5401300, 38761.0, 0.0012960),
+    (38820.0, 3.6401300, 38761.0, 0.0012960),
+    (38942.0, 3.7401300, 38761.0, 0.0012960),
+    (39004.0, 3.8401300, 38761.0, 0.0012960),
+    (39126.0, 4.3131700, 39126.0, 0.0025920),
+    (39887.0, 4.2131700, 39126.0, 0.0025920),
+    (41317.0, 10.0, 0.0, 0.0),
+    (41499.0, 11.0, 0.0, 0.0),
+    (41683.0, 12.0, 0.0, 0.0),
+    (42048.0, 13.0, 0.0, 0.0),
+    (42413.0, 14.0, 0.0, 0.0),
+    (42778.0, 15.0, 0.0, 0.0),
+    (43144.0, 16.0, 0.0, 0.0),
+    (43509.0, 17.0, 0.0, 0.0),
+    (43874.0, 18.0, 0.0, 0.0),
+    (44239.0, 19.0, 0.0, 0.0),
+    (44786.0, 20.0, 0.0, 0.0),
+    (45151.0, 21.0, 0.0, 0.0),
+    (45516.0, 22.0, 0.0, 0.0),
+    (46247.0, 23.0, 0.0, 0.0),
+    (47161.0, 24.0, 0.0, 0.0),
+    (47892.0, 25.0, 0.0, 0.0),
+    (48257.0, 26.0, 0.0, 0.0),
+    (48804.0, 27.0, 0.0, 0.0),
+    (49169.0, 28.0, 0.0, 0.0),
+    (49534.0, 29.0, 0.0, 0.0),
+    (50083.0, 30.0, 0.0, 0.0),
+    (50630.0, 31.0, 0.0, 0.0),
+    (51179.0, 32.0, 0.0, 0.0),
+    (53736.0, 33.0, 0.0, 0.0),
+    (54832.0, 34.0, 0.0, 0.0),
+    (56109.0, 35.0, 0.0, 0.0),
+    (57204.0, 36.0, 0.0, 0.0),
+    (57754.0, 37.0, 0.0, 0.0)
+];
+
+///
+/// Year before which the epochs without the prefix are Besselian (IAU 1976)
+///
+const BESSELIAN_LIMIT: f64 = 1984.0;
+
+///
+/// JulianDate: A moment of time as a Julian date. The time scale is not
+/// stored in the value: dynamical quantities (ephemerides, precession) use
+/// TT, the Earth rotation uses UT.
+///
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct JulianDate(f64);
+
+impl convert::From<f64> for JulianDate {
+    fn from(jd: f64) -> Self {
+        JulianDate(jd)
+    }
+}
+
+impl convert::From<DateTime<Utc>> for JulianDate {
+    fn from(dt: DateTime<Utc>) -> Self {
+        let seconds = dt.timestamp() as f64 +
+            f64::from(dt.timestamp_subsec_nanos()) * 1e-9;
+        JulianDate(JD_UNIX_EPOCH + seconds / DAY)
+    }
+}
+
+impl ops::Add<f64> for JulianDate {
+    type Output = Self;
+
+    fn add(self, days: f64) -> Self {
+        JulianDate(self.0 + days)
+    }
+}
+
+impl ops::AddAssign<f64> for JulianDate {
+    fn add_assign(&mut self, days: f64) {
+        self.0 += days;
+    }
+}
+
+impl ops::Sub<f64> for JulianDate {
+    type Output = Self;
+
+    fn sub(self, days: f64) -> Self {
+        JulianDate(self.0 - days)
+    }
+}
+
+impl ops::SubAssign<f64> for JulianDate {
+    fn sub_assign(&mut self, days: f64) {
+        self.0 -= days;
+    }
+}
+
+impl ops::Sub for JulianDate {
+    type Output = f64;
+
+    fn sub(self, rhs: Self) -> f64 {
+        self.0 - rhs.0
+    }
+}
+
+impl JulianDate {
+    pub fn new(jd: f64) -> JulianDate {
+        JulianDate(jd)
+    }
+
+    pub fn from_mjd(mjd: f64) -> JulianDate {
+        JulianDate(mjd + MJD_OFFSET)
+    }
+
+    pub fn j2000() -> JulianDate {
+        JulianDate(JD_J2000)
+    }
+
+    ///
+    /// from_calendar: Creates the date from the calendar date. The Julian
+    /// calendar is used before 1582-10-15 and the Gregorian one after it.
+    ///
+    pub fn from_calendar(year: i32, month: u32, day: f64) -> JulianDate {
+        let (y, m) = if month <= 2 {
+            (f64::from(year - 1), f64::from(month + 12))
+        } else {
+            (f64::from(year), f64::from(month))
+        };
+
+        let mut jd = (365.25 * (y + 4716.0)).floor() +
+            (30.6001 * (m + 1.0)).floor() + day - 1524.5;
+
+        if jd >= JD_GREGORIAN {
+            let a = (y / 100.0).floor();
+            jd += 2.0 - a + (a / 4.0).floor();
+        }
+
+        JulianDate(jd)
+    }
+
+    ///
+    /// from_time: Creates the date from the calendar date and the time of day
+    ///
+    pub fn from_time(year: i32, month: u32, day: u32,
+                     hour: u32, minute: u32, second: f64) -> JulianDate {
+        let fraction = (f64::from(hour) + (f64::from(minute) +
+            second / 60.0) / 60.0) / 24.0;
+        JulianDate::from_calendar(year, month, f64::from(day) + fraction)
+    }
+
+    pub fn jd(&self) -> f64 {
+        self.0
+    }
+
+    pub fn mjd(&self) -> f64 {
+        self.0 - MJD_OFFSET
+    }
+
+    ///
+    /// centuries: Julian centuries since the epoch J2000
+    ///
+    pub fn centuries(&self) -> f64 {
+        (self.0 - JD_J2000) / JULIAN_CENTURY
+    }
+
+    ///
+    /// calendar: Gives the calendar year, month and the day with its fraction
+    ///
+    pub fn calendar(&self) -> (i32, u32, f64) {
+        let z = (self.0 + 0.5).floor();
+        let f = (self.0 + 0.5).frac();
+
+        let a = if z < JD_GREGORIAN + 0.5 {
+            z
+        } else {
+            let alpha = ((z - 1867216.25) / 36524.25).floor();
+            z + 1.0 + alpha - (alpha / 4.0).floor()
+        };
+
+        let b = a + 1524.0;
+        let c = ((b - 122.1) / 365.25).floor();
+        let d = (365.25 * c).floor();
+        let e = ((b - d) / 30.6001).floor();
+
+        let day = b - d - (30.6001 * e).floor() + f;
+        let month = if e < 14.0 { e - 1.0 } else { e - 13.0 };
+        let year = if month > 2.0 { c - 4716.0 } else { c - 4715.0 };
+
+        (year as i32, month as u32, day)
+    }
+
+    ///
+    /// year: Gives the decimal year
+    ///
+    pub fn year(&self) -> f64 {
+        2000.0 + (self.0 - JD_J2000) / JULIAN_YEAR
+    }
+
+    ///
+    /// to_datetime: Converts the date into the chrono UTC date and time
+    ///
+    pub fn to_datetime(&self) -> Option<DateTime<Utc>> {
+        let seconds = (self.0 - JD_UNIX_EPOCH) * DAY;
+        let whole = seconds.floor();
+        let nanos = ((seconds - whole) * 1e9).round().min(999_999_999.0);
+
+        Utc.timestamp_opt(whole as i64, nanos as u32).single()
+    }
+
+    ///
+    /// tt_to_ut: Interprets the date as TT and converts it into UT
+    ///
+    pub fn tt_to_ut(self) -> JulianDate {
+        let ut = self.0 - delta_t(self) / DAY;
+        JulianDate(self.0 - delta_t(JulianDate(ut)) / DAY)
+    }
+
+    ///
+    /// ut_to_tt: Interprets the date as UT and converts it into TT
+    ///
+    pub fn ut_to_tt(self) -> JulianDate {
+        JulianDate(self.0 + delta_t(self) / DAY)
+    }
+
+    ///
+    /// utc_to_tt: Interprets the date as UTC and converts it into TT
+    ///
+    pub fn utc_to_tt(self) -> JulianDate {
+        JulianDate(self.0 + (tai_utc(self) + TT_TAI) / DAY)
+    }
+
+    ///
+    /// tt_to_utc: Interprets the date as TT and converts it into UTC
+    ///
+    pub fn tt_to_utc(self) -> JulianDate {
+        let utc = self.0 - (tai_utc(self) + TT_TAI) / DAY;
+        JulianDate(
+            self.0 - (tai_utc(JulianDate(utc)) + TT_TAI) / DAY
+        )
+    }
+}
+
+///
+/// Epoch: Besselian (B1950.0) or Julian (J2000.0) epoch given by the
+/// decimal year. The Besselian years are tropical years counted from B1900,
+/// the Julian years have 365.25 days counted from J2000.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Epoch {
+    Besselian(f64),
+    Julian(f64)
+}
+
+impl convert::From<Epoch> for JulianDate {
+    fn from(epoch: Epoch) -> Self {
+        epoch.jd()
+    }
+}
+
+impl str::FromStr for Epoch {
+    type Err = Error;
+
+    ///
+    /// Parses the epochs as "B1950.0" or "J2000.0". The years without the
+    /// prefix are Besselian before 1984 and Julian after it.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (prefix, year) = match s.chars().next() {
+            Some(c) if c.is_ascii_alphabetic() => {
+                (Some(c.to_ascii_uppercase()), &s[1..])
+            },
+            _ => (None, s)
+        };
+
+        let year: f64 = year.trim().parse()
+            .map_err(|_| Error::ConversionError)?;
+        if !year.is_finite() {
+            return Err(Error::ConversionError);
+        }
+
+        match prefix {
+            Some('B') => Ok(Epoch::Besselian(year)),
+            Some('J') => Ok(Epoch::Julian(year)),
+            Some(_) => Err(Error::ConversionError),
+            None if year < BESSELIAN_LIMIT => Ok(Epoch::Besselian(year)),
+            None => Ok(Epoch::Julian(year))
+        }
+    }
+}
+
+impl fmt::Display for Epoch {
+    ///
+    /// Prints the epochs as "B1950.0" or "J2000.0", the precision of the
+    /// formatter gives the decimals of the year
+    ///
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (prefix, year) = match *self {
+            Epoch::Besselian(year) => ('B', year),
+            Epoch::Julian(year) => ('J', year)
+        };
+
+        match f.precision() {
+            Some(precision) => write!(f, "{}{:.*}", prefix, precision, year),
+            None if year.fract() == 0.0 => write!(f, "{}{:.1}", prefix, year),
+            None => write!(f, "{}{}", prefix, year)
+        }
+    }
+}
+
+impl Epoch {
+    ///
+    /// besselian: Besselian epoch of the date
+    ///
+    pub fn besselian(jd: JulianDate) -> Epoch {
+        Epoch::Besselian(1900.0 + (jd.jd() - JD_B1900) / TROPICAL_YEAR)
+    }
+
+    ///
+    /// julian: Julian epoch of the date
+    ///
+    pub fn julian(jd: JulianDate) -> Epoch {
+        Epoch::Julian(2000.0 + (jd.jd() - JD_J2000) / JULIAN_YEAR)
+    }
+
+    pub fn b1950() -> Epoch {
+        Epoch::Besselian(1950.0)
+    }
+
+    pub fn j2000() -> Epoch {
+        Epoch::Julian(2000.0)
+    }
+
+    ///
+    /// year: Decimal year of the epoch
+    ///
+    pub fn year(&self) -> f64 {
+        match *self {
+            Epoch::Besselian(year) | Epoch::Julian(year) => year
+        }
+    }
+
+    pub fn is_besselian(&self) -> bool {
+        matches!(*self, Epoch::Besselian(_))
+    }
+
+    pub fn is_julian(&self) -> bool {
+        matches!(*self, Epoch::Julian(_))
+    }
+
+    ///
+    /// jd: Julian date of the epoch
+    ///
+    pub fn jd(&self) -> JulianDate {
+        match *self {
+            Epoch::Besselian(year) => {
+                JulianDate(JD_B1900 + (year - 1900.0) * TROPICAL_YEAR)
+            },
+            Epoch::Julian(year) => {
+                JulianDate(JD_J2000 + (year - 2000.0) * JULIAN_YEAR)
+            }
+        }
+    }
+
+    ///
+    /// to_besselian: The same moment as a Besselian epoch
+    ///
+    pub fn to_besselian(self) -> Epoch {
+        Epoch::besselian(self.jd())
+    }
+
+    ///
+    /// to_julian: The same moment as a Julian epoch
+    ///
+    pub fn to_julian(self) -> Epoch {
+        Epoch::julian(self.jd())
+    }
+}
+
+///
+/// tai_utc: Gives the difference TAI - UTC [s] at the UTC date from 1960 on
+/// including the leap seconds announced up to now, zero before 1960
+///
+pub fn tai_utc(utc: JulianDate) -> f64 {
+    let mjd = utc.mjd();
+
+    TAI_UTC.iter()
+        .rev()
+        .find(|&&(start, _, _, _)| mjd >= start)
+        .map_or(0.0, |&(_, offset, reference, rate)| {
+            offset + (mjd - reference) * rate
+        })
+}
+
+///
+/// delta_t: Gives the difference TT - UT [s] according to the polynomial
+/// expressions of Espenak and Meeus
+///
+pub fn delta_t(jd: JulianDate) -> f64 {
+    let y = jd.year();
+
+    if !(-500.0..2150.0).contains(&y) {
+        let u = (y - 1820.0) / 100.0;
+        -20.0 + 32.0 * u * u
+    } else if y < 500.0 {
+        let u = y / 100.0;
+        10583.6 + u * (-1014.41 + u * (33.78311 + u * (-5.952053 +
+            u * (-0.1798452 + u * (0.022174192 + u * 0.0090316521)))))
+    } else if y < 1600.0 {
+        let u = (y - 1000.0) / 100.0;
+        1574.2 + u * (-556.01 + u * (71.23472 + u * (0.319781 +
+            u * (-0.8503463 + u * (-0.005050998 + u * 0.0083572073)))))
+    } else if y < 1700.0 {
+        let t = y - 1600.0;
+        120.0 + t * (-0.9808 + t * (-0.01532 + t / 7129.0))
+    } else if y < 1800.0 {
+        let t = y - 1700.0;
+        8.83 + t * (0.1603 + t * (-0.0059285 + t * (0.00013336 -
+            t / 1174000.0)))
+    } else if y < 1860.0 {
+        let t = y - 1800.0;
+        13.72 + t * (-0.332447 + t * (0.0068612 + t * (0.0041116 +
+            t * (-0.00037436 + t * (0.0000121272 + t * (-0.0000001699 +
+            t * 0.000000000875))))))
+    } else if y < 1900.0 {
+        let t = y - 1860.0;
+        7.62 + t * (0.5737 + t * (-0.251754 + t * (0.01680668 +
+            t * (-0.0004473624 + t / 233174.0))))
+    } else if y < 1920.0 {
+        let t = y - 1900.0;
+        -2.79 + t * (1.494119 + t * (-0.0598939 + t * (0.0061966 -
+            t * 0.000197)))
+    } else if y < 1941.0 {
+        let t = y - 1920.0;
+        21.20 + t * (0.84493 + t * (-0.076100 + t * 0.0020936))
+    } else if y < 1961.0 {
+        let t = y - 1950.0;
+        29.07 + t * (0.407 + t * (-1.0 / 233.0 + t / 2547.0))
+    } else if y < 1986.0 {
+        let t = y - 1975.0;
+        45.45 + t * (1.067 + t * (-1.0 / 260.0 - t / 718.0))
+    } else if y < 2005.0 {
+        let t = y - 2000.0;
+        63.86 + t * (0.3345 + t * (-0.060374 + t * (0.0017275 +
+            t * (0.000651814 + t * 0.00002373599))))
+    } else if y < 2050.0 {
+        let t = y - 2000.0;
+        62.92 + t * (0.32217 + t * 0.005589)
+    } else {
+        let u = (y - 1820.0) / 100.0;
+        -20.0 + 32.0 * u * u - 0.5628 * (2150.0 - y)
+    }
+}
@@ -0,0 +1,314 @@
+#![allow(dead_code)]
+
+mod common;
+
+#[macro_use]
+extern crate approx;
+
+use ephem::astrometry::aberration::*;
+use ephem::astrometry::deflection::*;
+use ephem::astrometry::light_time::*;
+use ephem::astrometry::refraction::*;
+use ephem::astrometry::star::*;
+use ephem::base::angle::Angle;
+use ephem::base::consts::{ARCS, AU, C_LIGHT, DEG};
+use ephem::base::linalg::{Norm, SphericalVec3D, Vec3D};
+use ephem::ephemeris::{AnalyticEphemeris, Body, Ephemeris};
+use ephem::observer::Observer;
+use ephem::time::JulianDate;
+
+
+fn separation(a: Vec3D, b: Vec3D) -> f64 {
+    a.cross(b).norm().atan2(a.dot(b))
+}
+
+#[test]
+fn aberration_test() {
+    // Direction perpendicular to the velocity of 30 km/s
+    let v = Vec3D::cartesian(30.0 / AU * 86400.0, 0.0, 0.0);
+    let beta = v.norm() / C_LIGHT;
+    let u = Vec3D::unit_y();
+
+    let p = classical(u, v).unwrap();
+    assert_relative_eq!(p.norm(), 1.0, epsilon = common::EPS);
+    assert_relative_eq!(separation(u, p), beta.atan(), epsilon = 1e-15);
+
+    let p = relativistic(u, v).unwrap();
+    assert_relative_eq!(p.norm(), 1.0, epsilon = common::EPS);
+    assert_relative_eq!(separation(u, p), beta.asin(), epsilon = 1e-15);
+
+    // No displacement along the velocity
+    let p = relativistic(Vec3D::unit_x(), v).unwrap();
+    assert_relative_eq!(separation(Vec3D::unit_x(), p), 0.0, epsilon = 1e-15);
+
+    // The annual aberration is at most about 20.5"
+    let ephemeris = AnalyticEphemeris::new();
+    let jd = JulianDate::from_calendar(2024, 3, 20.0);
+    let v = annual_velocity(&ephemeris, jd).unwrap();
+    let u = Vec3D::cartesian(-v.dot(Vec3D::unit_y()), v.dot(Vec3D::unit_x()),
+                             0.0);
+    let u = (u / u.norm()).unwrap();
+    let a = separation(u, classical(u, v).unwrap()) * ARCS;
+    let b = separation(u, relativistic(u, v).unwrap()) * ARCS;
+    assert!(a > 20.0 && a < 21.0);
+    assert_relative_eq!(a, b, epsilon = 1e-3);
+
+    // The diurnal aberration on the equator is about 0.32"
+    let equator = Observer::new(Angle::from_ad(0.0), Angle::from_ad(0.0),
+                                0.0);
+    let v = diurnal_velocity(&equator, jd);
+    assert_relative_eq!(v.dot(Vec3D::unit_z()), 0.0, epsilon = 1e-15);
+    let u = Vec3D::unit_z();
+    assert_relative_eq!(
+        separation(u, classical(u, v).unwrap()) * ARCS, 0.3200,
+        epsilon = 1e-3
+    );
+}
+
+#[test]
+fn deflection_test() {
+    let ephemeris = AnalyticEphemeris::new();
+    let jd = JulianDate::from_calendar(2024, 6, 1.0);
+    let earth = ephemeris.position(Body::Earth, jd).unwrap();
+    let sun = ephemeris.position(Body::Sun, jd).unwrap();
+    let s = sun - earth;
+    let s = (s / s.norm()).unwrap();
+
+    // Star at the elongation 90 deg: 4.07 mas at 1 AU
+    let star = s.cross(Vec3D::unit_z());
+    let star = (star / star.norm()).unwrap();
+    let u = light_deflection(&ephemeris, earth, star, None, &[], jd).unwrap();
+    let expected = 1.97412574336e-8 * ARCS / (sun - earth).norm();
+    assert_relative_eq!(separation(star, u) * ARCS, expected, epsilon = 1e-5);
+    assert!(separation(s, u) > separation(s, star));
+
+    // Star at the limb of the Sun: about 1.75"
+    let limb = s + star * (959.63 / ARCS / (sun - earth).norm());
+    let limb = (limb / limb.norm()).unwrap();
+    let elongation = separation(s, limb);
+    let u = light_deflection(&ephemeris, earth, limb, None, &[], jd).unwrap();
+    assert_relative_eq!(
+        separation(limb, u), expected / ARCS / (elongation / 2.0).tan(),
+        epsilon = 1e-9
+    );
+    assert_relative_eq!(separation(limb, u) * ARCS, 1.75, epsilon = 0.01);
+
+    // Jupiter and Saturn add less than a milliarcsecond far from them
+    let v = light_deflection(
+        &ephemeris, earth, star, None, &[Body::Jupiter, Body::Saturn], jd
+    ).unwrap();
+    let w = light_deflection(&ephemeris, earth, star, None, &[], jd).unwrap();
+    assert!(separation(v, w) * ARCS < 1e-3);
+
+    // A nearby source is deflected less than a star
+    let near = light_deflection(
+        &ephemeris, earth, star, Some(0.5), &[], jd
+    ).unwrap();
+    assert!(separation(star, near) < separation(star, w));
+}
+
+#[test]
+fn light_time_test() {
+    let ephemeris = AnalyticEphemeris::new();
+    let jd = JulianDate::from_calendar(2024, 6, 1.0);
+    let earth = ephemeris.position(Body::Earth, jd).unwrap();
+
+    for body in [Body::Sun, Body::Moon, Body::Venus, Body::Jupiter] {
+        let lt = LightTime::new(&ephemeris, earth, body, jd).unwrap();
+        assert_relative_eq!(lt.position().norm(), C_LIGHT * lt.light_time(),
+                            epsilon = 1e-9);
+        assert!(lt.iterations() <= 4);
+
+        let r = ephemeris.position(body, jd - lt.light_time()).unwrap();
+        assert_relative_eq!((r - earth - lt.position()).norm(), 0.0,
+                            epsilon = 1e-12);
+    }
+
+    // The Sun at 1.014 AU
+    let sun = LightTime::new(&ephemeris, earth, Body::Sun, jd).unwrap();
+    assert_relative_eq!(sun.light_time() * 86400.0, 506.0, epsilon = 0.5);
+}
+
+#[test]
+fn refraction_test() {
+    let minutes = |angle: Angle| {
+        let radians: f64 = angle.into();
+        radians * ARCS / 60.0
+    };
+
+    // Meeus, chapter 16
+    let atmosphere = Atmosphere::default();
+    assert_relative_eq!(minutes(atmosphere.bennett(Angle::from_ad(0.0))),
+                        34.478, epsilon = 1e-3);
+    assert_relative_eq!(
+        minutes(atmosphere.saemundsson(Angle::from_ad(0.0))), 28.982,
+        epsilon = 1e-3
+    );
+
+    // The formulas agree within 0.1' at the true altitude h - R
+    for &h in [5.0, 10.0, 30.0, 60.0].iter() {
+        let a = atmosphere.bennett(Angle::from_ad(h));
+        let radians: f64 = a.into();
+        let t = atmosphere.saemundsson(Angle::from(
+            h.to_radians() - radians
+        ));
+        assert!((minutes(a) - minutes(t)).abs() < 0.1);
+
+        let r = atmosphere.ray_traced(Angle::from_ad(h));
+        assert!((minutes(a) - minutes(r)).abs() < 0.15);
+    }
+
+    // The altitudes below the valid range of the formulas are limited
+    let limit = minutes(atmosphere.bennett(Angle::from_ad(-1.0)));
+    assert!(limit > 34.478 && limit < 60.0);
+    let limit = minutes(atmosphere.saemundsson(Angle::from_ad(-1.0)));
+    assert!(limit > 28.982 && limit < 60.0);
+    for &h in [-2.0, -4.4, -5.11, -10.0, -90.0].iter() {
+        assert_relative_eq!(
+            minutes(atmosphere.bennett(Angle::from_ad(h))),
+            minutes(atmosphere.bennett(Angle::from_ad(-1.0)))
+        );
+        assert_relative_eq!(
+            minutes(atmosphere.saemundsson(Angle::from_ad(h))),
+            minutes(atmosphere.saemundsson(Angle::from_ad(-1.0)))
+        );
+    }
+
+    // Denser and colder air refracts more
+    let cold = Atmosphere::new(1030.0, -20.0);
+    assert!(minutes(cold.bennett(Angle::from_ad(10.0))) >
+            minutes(atmosphere.bennett(Angle::from_ad(10.0))));
+
+    // SLALIB test case of sla_REFRO
+    let atmosphere = Atmosphere::new(678.9, 280.0 - 273.15)
+        .with_height(3456.7)
+        .with_humidity(0.9)
+        .with_wavelength(0.55)
+        .with_latitude(Angle::from(-0.3))
+        .with_lapse_rate(0.006);
+    let r: f64 = atmosphere.ray_traced(
+        Angle::from(std::f64::consts::FRAC_PI_2 - 1.4)
+    ).into();
+    assert_relative_eq!(r, 0.00106715763537, epsilon = 1e-11);
+
+    // Zero at the zenith
+    let zenith: f64 = atmosphere.ray_traced(Angle::from_ad(90.0)).into();
+    assert_relative_eq!(zenith, 0.0, epsilon = 1e-15);
+}
+
+#[test]
+fn star_test() {
+    // Meeus, example 23.a: theta Persei at 2028 Nov 13.19 TD
+    let ephemeris = AnalyticEphemeris::new();
+    let star = Star::new(
+        Angle::from_thms(2, 44, 11.986), Angle::from_adms(49, 13, 42.48)
+    ).with_proper_motion(0.03425 * 15.0e3 * (49.228467f64).to_radians().cos(),
+                         -89.5);
+    let jd = JulianDate::new(2462088.69);
+    let observer = Observer::new(Angle::from_ad(0.0), Angle::from_ad(51.5),
+                                 0.0);
+
+    let reduction = Reduction::new(&ephemeris, observer, jd);
+    let places = reduction.places(&star).unwrap();
+    let s: SphericalVec3D = places.apparent().into();
+    assert_relative_eq!(s.phi() * DEG, 41.5599646, epsilon = 3e-5);
+    assert_relative_eq!(s.theta() * DEG, 49.3520685, epsilon = 3e-5);
+
+    // The diurnal aberration is below 0.32"
+    assert!(separation(places.apparent(), places.topocentric()) * ARCS <
+            0.32);
+    assert!(separation(places.apparent(), places.astrometric()) * ARCS >
+            20.0);
+
+    // The refraction raises the star
+    let refracted = Reduction::new(&ephemeris, observer, jd)
+        .with_atmosphere(Atmosphere::default())
+        .places(&star)
+        .unwrap();
+    let (h0, h): (f64, f64) = (places.altitude().into(),
+                               refracted.altitude().into());
+    assert!(h0 > 0.0);
+    let r: f64 = Atmosphere::default().ray_traced(refracted.altitude()).into();
+    assert_relative_eq!(h - h0, r, epsilon = 1e-9);
+    let (a0, a): (f64, f64) = (places.azimuth().into(),
+                               refracted.azimuth().into());
+    assert_relative_eq!(a0, a, epsilon = 1e-12);
+
+    // The altitude from the hour angle and the declination
+    let s: SphericalVec3D = places.topocentric().into();
+    let latitude = 51.5f64.to_radians();
+    let tau = ephem::frames::sidereal::gast(jd.tt_to_ut()) - s.phi();
+    let sh = latitude.sin() * s.theta().sin() +
+        latitude.cos() * s.theta().cos() * tau.cos();
+    assert_relative_eq!(h0, sh.asin(), epsilon = 1e-12);
+    let (azimuth, _) = reduction.observed(&star).unwrap();
+    let azimuth: f64 = azimuth.into();
+    assert_relative_eq!(
+        azimuth, tau.sin().atan2(
+            tau.cos() * latitude.sin() - s.theta().tan() * latitude.cos()
+        ) + std::f64::consts::PI, epsilon = 1e-12
+    );
+}
+
+#[test]
+fn space_motion_test() {
+    // Barnard's star (Hipparcos)
+    let barnard = Star::new(Angle::from_ad(269.45402305),
+                            Angle::from_ad(4.66828815))
+        .with_proper_motion(-797.84, 10326.93)
+        .with_parallax(549.01)
+        .with_radial_velocity(-110.51);
+
+    let (r, v) = barnard.state();
+    let back = Star::from_state(r, v, barnard.epoch()).unwrap();
+    let (ra, ra0): (f64, f64) = (back.right_ascension().into(),
+                                 barnard.right_ascension().into());
+    assert_relative_eq!(ra, ra0, epsilon = 1e-14);
+    assert_relative_eq!(back.pm_ra(), barnard.pm_ra(), epsilon = 1e-9);
+    assert_relative_eq!(back.pm_dec(), barnard.pm_dec(), epsilon = 1e-9);
+    assert_relative_eq!(back.parallax(), barnard.parallax(), epsilon = 1e-9);
+    assert_relative_eq!(back.radial_velocity(), barnard.radial_velocity(),
+                        epsilon = 1e-9);
+
+    // The proper motion grows by 1.29 mas/yr^2, the parallax by 0.034
+    // mas/yr as the star approaches
+    let jd = JulianDate::from_calendar(2050, 1, 1.5);
+    let later = barnard.propagate(jd).unwrap();
+    let years = (jd - barnard.epoch()) / 365.25;
+    let pm = |star: &Star| star.pm_ra().hypot(star.pm_dec());
+    assert_relative_eq!((pm(&later) - pm(&barnard)) / years, 1.29,
+                        epsilon = 0.02);
+    assert_relative_eq!((later.parallax() - barnard.parallax()) / years,
+                        0.0336, epsilon = 1e-3);
+
+    // The linear proper motion misses by about 1.8" after 50 years
+    let linear = barnard.direction() + Vec3D::cartesian(
+        -barnard.pm_ra() * 269.454f64.to_radians().sin(),
+        barnard.pm_ra() * 269.454f64.to_radians().cos(), 0.0
+    ) * (years * 1e-3 / ARCS) + Vec3D::cartesian(
+        -barnard.pm_dec() * 4.668f64.to_radians().sin() *
+            269.454f64.to_radians().cos(),
+        -barnard.pm_dec() * 4.668f64.to_radians().sin() *
+            269.454f64.to_radians().sin(),
+        barnard.pm_dec() * 4.668f64.to_radians().cos()
+    ) * (years * 1e-3 / ARCS);
+    let miss = separation(later.direction(), linear) * ARCS;
+    assert!(miss > 1.0 && miss < 3.0);
+
+    // Propagating back restores the catalogue place
+    let back = later.propagate(barnard.epoch()).unwrap();
+    let (dec, dec0): (f64, f64) = (back.declination().into(),
+                                   barnard.declination().into());
+    assert_relative_eq!(dec, dec0, epsilon = 1e-13);
+    assert_relative_eq!(back.parallax(), barnard.parallax(), epsilon = 1e-9);
+
+    // A star without a parallax moves along the great circle
+    let distant = Star::new(Angle::from_ad(10.0), Angle::from_ad(20.0))
+        .with_proper_motion(100.0, 0.0);
+    let later = distant.propagate(jd).unwrap();
+    assert_eq!(later.parallax(), 0.0);
+    assert_relative_eq!(
+        separation(distant.direction(), later.direction()) * ARCS,
+        0.1 * years, epsilon = 1e-6
+    );
+}
@@ -33,6 +33,21 @@ fn func_test() {
     assert_relative_eq!(m.fmod(n), 45.0);
 }
 
+#[test]
+fn wrap_test() {
+    use std::f64::consts::PI;
+
+    assert_relative_eq!(angle::wrap(0.5), 0.5);
+    assert_relative_eq!(angle::wrap(PI + 0.5), 0.5 - PI);
+    assert_relative_eq!(angle::wrap(-PI - 0.5), PI - 0.5);
+    assert_relative_eq!(angle::wrap(PI), -PI);
+    assert_relative_eq!(angle::wrap(10.0 * PI + 0.5), 0.5, epsilon = 1e-12);
+
+    // The Sun seen from the Earth
+    let radius = angle::angular_radius(696_000.0, 1.0);
+    assert_relative_eq!(radius * 180.0 / PI * 60.0, 15.99, epsilon = 0.01);
+}
+
 #[test]
 fn pertpair_test() {
     let frac_sqrt2_2: f64 = 2_f64.sqrt() / 2.0;
@@ -0,0 +1,493 @@
+#![allow(dead_code)]
+
+mod common;
+
+#[macro_use]
+extern crate approx;
+
+use ephem::base::angle::Angle;
+use ephem::base::consts::DEG;
+use ephem::base::error::Error;
+use ephem::base::linalg::Vec3D;
+use ephem::eclipses::lunar::*;
+use ephem::eclipses::occultation::*;
+use ephem::eclipses::solar::*;
+use ephem::eclipses::transit::*;
+use ephem::ephemeris::{AnalyticEphemeris, Body};
+use ephem::frames::cio::CelestialToTerrestrial;
+use ephem::observer::Observer;
+use ephem::time::JulianDate;
+
+const MINUTE: f64 = 1.0 / 1440.0;
+
+
+fn degrees(angle: Angle) -> f64 {
+    let radians: f64 = angle.into();
+    radians * DEG
+}
+
+fn ut(year: i32, month: u32, day: u32, hour: u32, minute: u32,
+      second: f64) -> f64 {
+    JulianDate::from_time(year, month, day, hour, minute, second).jd()
+}
+
+#[test]
+fn besselian_elements_test() {
+    // Total eclipse of 2024 April 8
+    let ephemeris = AnalyticEphemeris::new();
+    let elements = BesselianElements::new(
+        &ephemeris, JulianDate::from_calendar(2024, 4, 8.76)
+    ).unwrap();
+
+    let t0 = elements.t0();
+    assert_relative_eq!(t0.jd(),
+                        JulianDate::from_time(2024, 4, 8, 18, 0, 0.0).jd());
+    assert_relative_eq!(elements.x(t0), -0.318157, epsilon = 2e-3);
+    assert_relative_eq!(elements.y(t0), 0.219747, epsilon = 2e-3);
+    assert_relative_eq!(elements.d(t0) * DEG, 7.5862, epsilon = 1e-3);
+    assert_relative_eq!(elements.l1(t0), 0.535813, epsilon = 1e-4);
+    assert_relative_eq!(elements.l2(t0), -0.010274, epsilon = 1e-4);
+    assert_relative_eq!(elements.tan_f1(), 0.0046683, epsilon = 1e-6);
+    assert_relative_eq!(elements.tan_f2(), 0.0046450, epsilon = 1e-6);
+
+    let t1 = t0 + 2.0 / 24.0;
+    assert_relative_eq!(
+        elements.mu(t1) - elements.mu(t0), 30.0 / DEG, epsilon = 1e-3
+    );
+}
+
+#[test]
+fn global_circumstances_test() {
+    let ephemeris = AnalyticEphemeris::new();
+    let global = |year, month, day| {
+        BesselianElements::new(
+            &ephemeris, JulianDate::from_calendar(year, month, day)
+        ).unwrap().global().unwrap().unwrap()
+    };
+
+    let total = global(2024, 4, 8.76);
+    assert_eq!(total.kind(), SolarEclipseType::Total);
+    assert_relative_eq!(
+        total.greatest().tt_to_ut().jd(), ut(2024, 4, 8, 18, 17, 16.0),
+        epsilon = MINUTE
+    );
+    assert_relative_eq!(total.gamma(), 0.3431, epsilon = 1e-3);
+    assert_relative_eq!(total.magnitude(), 1.0566, epsilon = 1e-3);
+    assert_relative_eq!(degrees(total.longitude()), -104.1, epsilon = 0.2);
+    assert_relative_eq!(degrees(total.latitude()), 25.3, epsilon = 0.1);
+    assert_relative_eq!(total.duration().unwrap(), 268.0, epsilon = 3.0);
+
+    let annular = global(2023, 10, 14.75);
+    assert_eq!(annular.kind(), SolarEclipseType::Annular);
+    assert_relative_eq!(annular.gamma(), 0.3753, epsilon = 1e-3);
+    assert_relative_eq!(annular.magnitude(), 0.952, epsilon = 1e-3);
+
+    let hybrid = global(2023, 4, 20.17);
+    assert_eq!(hybrid.kind(), SolarEclipseType::Hybrid);
+    assert_relative_eq!(hybrid.magnitude(), 1.0132, epsilon = 1e-3);
+
+    let partial = global(2022, 10, 25.45);
+    assert_eq!(partial.kind(), SolarEclipseType::Partial);
+    assert_relative_eq!(partial.gamma(), 1.0701, epsilon = 1e-3);
+    assert_relative_eq!(partial.magnitude(), 0.8619, epsilon = 2e-3);
+    assert!(partial.duration().is_none());
+
+    // Non-central eclipses: the shadow axis misses the Earth
+    let total = global(2043, 4, 9.79);
+    assert_eq!(total.kind(), SolarEclipseType::Total);
+    assert_relative_eq!(total.gamma(), 1.0031, epsilon = 1e-3);
+    assert!(total.duration().is_none());
+
+    let annular = global(2043, 10, 3.13);
+    assert_eq!(annular.kind(), SolarEclipseType::Annular);
+    assert_relative_eq!(annular.gamma(), 1.0102, epsilon = 1e-3);
+    assert_relative_eq!(annular.magnitude(), 0.9497, epsilon = 1e-3);
+    assert!(annular.duration().is_none());
+
+    // No eclipse at the new moon of 2024 May 8
+    let none = BesselianElements::new(
+        &ephemeris, JulianDate::from_calendar(2024, 5, 8.16)
+    ).unwrap();
+    assert!(none.global().unwrap().is_none());
+    assert!(none.central_path(MINUTE).unwrap().is_none());
+}
+
+#[test]
+fn central_line_test() {
+    let ephemeris = AnalyticEphemeris::new();
+    let elements = BesselianElements::new(
+        &ephemeris, JulianDate::from_calendar(2024, 4, 8.76)
+    ).unwrap();
+
+    let path = elements.central_path(10.0 * MINUTE).unwrap().unwrap();
+    assert!(path.len() > 15);
+    for pair in path.windows(2) {
+        assert!(pair[0].jd() < pair[1].jd());
+        assert!(degrees(pair[0].longitude()) < degrees(pair[1].longitude()));
+    }
+    assert!(matches!(elements.central_path(0.0),
+                     Err(Error::InvalidArgumentError(_))));
+
+    // The path begins in the Pacific and ends in the Atlantic
+    assert!(degrees(path[0].longitude()) < -130.0);
+    assert!(degrees(path[path.len() - 1].longitude()) > -30.0);
+
+    // The central line crosses Texas
+    let point = path.iter()
+        .find(|p| (degrees(p.longitude()) + 97.0).abs() < 3.0)
+        .unwrap();
+    assert!(degrees(point.latitude()) > 29.0);
+    assert!(degrees(point.latitude()) < 36.0);
+    assert!(point.duration() > 250.0);
+
+    let before = elements.t0() - 3.0 / 24.0;
+    assert!(elements.central_line(before).is_none());
+}
+
+#[test]
+fn local_circumstances_test() {
+    let ephemeris = AnalyticEphemeris::new();
+    let elements = BesselianElements::new(
+        &ephemeris, JulianDate::from_calendar(2024, 4, 8.76)
+    ).unwrap();
+
+    let dallas = Observer::new(
+        Angle::from_ad(-96.797), Angle::from_ad(32.7767), 139.0
+    );
+    let local = elements.local(&dallas).unwrap().unwrap();
+    assert_eq!(local.kind(), SolarEclipseType::Total);
+
+    let contact = |jd: JulianDate| jd.tt_to_ut().jd();
+    assert_relative_eq!(
+        contact(local.first().jd()), ut(2024, 4, 8, 17, 23, 20.0),
+        epsilon = MINUTE
+    );
+    assert_relative_eq!(
+        contact(local.second().unwrap().jd()), ut(2024, 4, 8, 18, 40, 43.0),
+        epsilon = 0.5 * MINUTE
+    );
+    assert_relative_eq!(
+        contact(local.third().unwrap().jd()), ut(2024, 4, 8, 18, 44, 35.0),
+        epsilon = 0.5 * MINUTE
+    );
+    assert_relative_eq!(
+        contact(local.fourth().jd()), ut(2024, 4, 8, 20, 2, 40.0),
+        epsilon = MINUTE
+    );
+    assert!(local.magnitude() > 1.0);
+    assert_relative_eq!(local.obscuration(), 1.0);
+    assert_relative_eq!(degrees(local.altitude()), 64.6, epsilon = 0.5);
+
+    // The Moon enters from the west (lower right) and leaves to the east
+    let p1 = degrees(local.first().position_angle());
+    let p4 = degrees(local.fourth().position_angle());
+    assert!(p1 > 180.0 && p1 < 270.0);
+    assert!(p4 > 0.0 && p4 < 90.0);
+
+    let new_york = Observer::new(
+        Angle::from_ad(-74.006), Angle::from_ad(40.7128), 10.0
+    );
+    let local = elements.local(&new_york).unwrap().unwrap();
+    assert_eq!(local.kind(), SolarEclipseType::Partial);
+    assert!(local.second().is_none() && local.third().is_none());
+    assert_relative_eq!(local.magnitude(), 0.91, epsilon = 0.01);
+    assert!(local.obscuration() < local.magnitude());
+
+    let buenos_aires = Observer::new(
+        Angle::from_ad(-58.3816), Angle::from_ad(-34.6037), 25.0
+    );
+    assert!(elements.local(&buenos_aires).unwrap().is_none());
+}
+
+#[test]
+fn lunar_eclipse_test() {
+    // Total eclipse of 2022 November 8
+    let ephemeris = AnalyticEphemeris::new();
+    let full_moon = JulianDate::from_calendar(2022, 11, 8.46);
+    let eclipse = LunarEclipse::new(&ephemeris, full_moon, ShadowRule::Danjon)
+        .unwrap()
+        .unwrap();
+    assert_eq!(eclipse.kind(), LunarEclipseType::Total);
+
+    let contacts = [
+        (Some(eclipse.p1()), 8, 2, 15.0),
+        (eclipse.u1(), 9, 9, 12.0),
+        (eclipse.u2(), 10, 16, 39.0),
+        (Some(eclipse.greatest()), 10, 59, 11.0),
+        (eclipse.u3(), 11, 41, 38.0),
+        (eclipse.u4(), 12, 49, 3.0),
+        (Some(eclipse.p4()), 13, 56, 9.0)
+    ];
+    for &(jd, hour, minute, second) in contacts.iter() {
+        assert_relative_eq!(
+            jd.unwrap().tt_to_ut().jd(),
+            ut(2022, 11, 8, hour, minute, second),
+            epsilon = 0.5 * MINUTE
+        );
+    }
+    assert_relative_eq!(eclipse.umbral_magnitude(), 1.3589, epsilon = 2e-3);
+    assert_relative_eq!(eclipse.penumbral_magnitude(), 2.4120, epsilon = 5e-3);
+
+    // Chauvenet's rule gives the larger shadow
+    let chauvenet = LunarEclipse::new(
+        &ephemeris, full_moon, ShadowRule::Chauvenet
+    ).unwrap().unwrap();
+    assert!(chauvenet.p1() < eclipse.p1());
+    assert!(chauvenet.p4() > eclipse.p4());
+    assert!(chauvenet.penumbral_magnitude() > eclipse.penumbral_magnitude());
+    assert_relative_eq!(chauvenet.greatest().jd(), eclipse.greatest().jd(),
+                        epsilon = 1e-6);
+
+    // No eclipse at the full moon of 2022 December 8
+    let full_moon = JulianDate::from_calendar(2022, 12, 8.18);
+    assert!(
+        LunarEclipse::new(&ephemeris, full_moon, ShadowRule::default())
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn lunar_eclipses_test() {
+    let ephemeris = AnalyticEphemeris::new();
+    let eclipses = lunar_eclipses(
+        &ephemeris,
+        JulianDate::from_calendar(2024, 3, 1.0),
+        JulianDate::from_calendar(2024, 10, 1.0),
+        ShadowRule::Danjon
+    ).unwrap();
+    assert_eq!(eclipses.len(), 2);
+
+    let penumbral = &eclipses[0];
+    assert_eq!(penumbral.kind(), LunarEclipseType::Penumbral);
+    assert!(penumbral.u1().is_none() && penumbral.u2().is_none());
+    assert_relative_eq!(
+        penumbral.greatest().tt_to_ut().jd(), ut(2024, 3, 25, 7, 12, 51.0),
+        epsilon = 0.5 * MINUTE
+    );
+    assert_relative_eq!(penumbral.penumbral_magnitude(), 0.9557,
+                        epsilon = 2e-3);
+    assert!(penumbral.umbral_magnitude() < 0.0);
+
+    let partial = &eclipses[1];
+    assert_eq!(partial.kind(), LunarEclipseType::Partial);
+    assert!(partial.u1().is_some() && partial.u2().is_none());
+    assert_relative_eq!(
+        partial.greatest().tt_to_ut().jd(), ut(2024, 9, 18, 2, 44, 17.0),
+        epsilon = 0.5 * MINUTE
+    );
+    assert_relative_eq!(partial.umbral_magnitude(), 0.0848, epsilon = 2e-3);
+}
+
+#[test]
+fn planet_occultation_test() {
+    // Occultation of Venus on 2023 November 9 seen from Paris
+    let ephemeris = AnalyticEphemeris::new();
+    let paris = Observer::new(
+        Angle::from_ad(2.35), Angle::from_ad(48.85), 35.0
+    );
+    let found = occultations(
+        &ephemeris, &paris, Target::Planet(Body::Venus),
+        JulianDate::from_calendar(2023, 11, 8.5),
+        JulianDate::from_calendar(2023, 11, 9.75)
+    ).unwrap();
+    assert_eq!(found.len(), 1);
+
+    // The waning Moon covers Venus with its bright limb
+    let disappearance = found[0].disappearance();
+    assert_relative_eq!(
+        disappearance.jd().tt_to_ut().jd(), ut(2023, 11, 9, 9, 55, 0.0),
+        epsilon = 5.0 * MINUTE
+    );
+    assert!(degrees(disappearance.cusp_angle()) < 0.0);
+    assert!(degrees(disappearance.altitude()) > 30.0);
+
+    let reappearance = found[0].reappearance();
+    assert!(reappearance.jd().jd() - disappearance.jd().jd() > 0.035);
+    assert!(degrees(reappearance.cusp_angle()) > 0.0);
+    let pa = degrees(reappearance.position_angle());
+    assert!(pa > 180.0 && pa < 360.0);
+
+    // A range shorter than the search step around the greatest occultation
+    let middle = (disappearance.jd().jd() + reappearance.jd().jd()) / 2.0;
+    let found = occultations(
+        &ephemeris, &paris, Target::Planet(Body::Venus),
+        JulianDate::new(middle - 0.01), JulianDate::new(middle + 0.01)
+    ).unwrap();
+    assert_eq!(found.len(), 1);
+    let found = occultations(
+        &ephemeris, &paris, Target::Planet(Body::Venus),
+        JulianDate::new(middle + 0.01),
+        JulianDate::from_calendar(2023, 11, 9.75)
+    ).unwrap();
+    assert!(found.is_empty());
+
+    assert!(
+        occultations(
+            &ephemeris, &paris, Target::Planet(Body::Moon),
+            JulianDate::from_calendar(2023, 11, 8.5),
+            JulianDate::from_calendar(2023, 11, 9.75)
+        ).is_err()
+    );
+}
+
+#[test]
+fn star_occultation_test() {
+    // Antares (ICRS)
+    let antares = Vec3D::unit(
+        (16.0 + 29.0 / 60.0 + 24.46 / 3600.0) * 15.0 / DEG,
+        -(26.0 + 25.0 / 60.0 + 55.2 / 3600.0) / DEG
+    ).unwrap();
+    let target = Target::Star(antares);
+
+    let ephemeris = AnalyticEphemeris::new();
+    let start = JulianDate::from_calendar(2024, 9, 10.0);
+    let end = start + 1.0;
+
+    let (north, south) = grazing_limits(
+        &ephemeris, target, start, end, 1.0 / 24.0
+    ).unwrap();
+    assert!(!north.is_empty() && !south.is_empty());
+    for step in [0.0, -1.0] {
+        assert!(matches!(grazing_limits(&ephemeris, target, start, end, step),
+                         Err(Error::InvalidArgumentError(_))));
+    }
+
+    // The star is above the horizon of every point of the limits
+    for point in north.iter().chain(south.iter()) {
+        let up = Vec3D::unit(
+            point.longitude().into(), point.latitude().into()
+        ).unwrap();
+        let c2t = CelestialToTerrestrial::new(point.jd());
+        assert!(up.dot(c2t.matrix() * antares) > -1e-3);
+    }
+
+    // Observers on both sides of the northern limit
+    let limit = north[north.len() / 2];
+    let observer = |offset: f64| Observer::new(
+        limit.longitude(),
+        Angle::from_ad(degrees(limit.latitude()) + offset),
+        0.0
+    );
+    let count = |offset: f64| occultations(
+        &ephemeris, &observer(offset), target, start, end
+    ).unwrap().len();
+    assert_ne!(count(-0.3), count(0.3));
+
+    // The events close to the graze do not fail the search
+    for offset in [-0.02, -0.01, 0.0, 0.01, 0.02] {
+        assert!(occultations(
+            &ephemeris, &observer(offset), target, start, end
+        ).is_ok());
+    }
+
+    // Disappearance at the eastern and reappearance at the western limb
+    let other = south.iter().find(|p| p.jd() == limit.jd()).unwrap();
+    let central = Observer::new(
+        Angle::from_ad(
+            (degrees(limit.longitude()) + degrees(other.longitude())) / 2.0
+        ),
+        Angle::from_ad(
+            (degrees(limit.latitude()) + degrees(other.latitude())) / 2.0
+        ),
+        0.0
+    );
+    let found = occultations(&ephemeris, &central, target, start, end)
+        .unwrap();
+    assert_eq!(found.len(), 1);
+
+    let pa = degrees(found[0].disappearance().position_angle());
+    assert!(pa > 0.0 && pa < 180.0);
+    let pa = degrees(found[0].reappearance().position_angle());
+    assert!(pa > 180.0 && pa < 360.0);
+
+    let ca = degrees(found[0].disappearance().cusp_angle());
+    assert!(ca.abs() <= 90.0);
+}
+
+#[test]
+fn venus_transit_test() {
+    // Transit of 2004 June 8 (NASA)
+    let ephemeris = AnalyticEphemeris::new();
+    let transit = Transit::new(
+        &ephemeris, Body::Venus, JulianDate::from_calendar(2004, 6, 8.35)
+    ).unwrap().unwrap();
+
+    let ut_of = |jd: JulianDate| jd.tt_to_ut().jd();
+    assert_relative_eq!(ut_of(transit.first()), ut(2004, 6, 8, 5, 13, 29.0),
+                        epsilon = MINUTE);
+    assert_relative_eq!(ut_of(transit.second().unwrap()),
+                        ut(2004, 6, 8, 5, 32, 55.0), epsilon = MINUTE);
+    assert_relative_eq!(ut_of(transit.greatest()), ut(2004, 6, 8, 8, 19, 44.0),
+                        epsilon = MINUTE);
+    assert_relative_eq!(ut_of(transit.third().unwrap()),
+                        ut(2004, 6, 8, 11, 6, 33.0), epsilon = MINUTE);
+    assert_relative_eq!(ut_of(transit.fourth()), ut(2004, 6, 8, 11, 26, 1.0),
+                        epsilon = MINUTE);
+    assert_relative_eq!(degrees(transit.separation()) * 3600.0, 626.9,
+                        epsilon = 1.0);
+
+    // Transit of 2012 June 5-6 (NASA)
+    let transits = transits(
+        &ephemeris, Body::Venus, JulianDate::from_calendar(2012, 6, 4.0),
+        JulianDate::from_calendar(2012, 6, 8.0)
+    ).unwrap();
+    assert_eq!(transits.len(), 1);
+    assert_relative_eq!(ut_of(transits[0].first()),
+                        ut(2012, 6, 5, 22, 9, 38.0), epsilon = MINUTE);
+    assert_relative_eq!(ut_of(transits[0].greatest()),
+                        ut(2012, 6, 6, 1, 29, 28.0), epsilon = MINUTE);
+    assert_relative_eq!(ut_of(transits[0].fourth()),
+                        ut(2012, 6, 6, 4, 49, 35.0), epsilon = MINUTE);
+    assert_relative_eq!(degrees(transits[0].separation()) * 3600.0, 554.4,
+                        epsilon = 2.0);
+
+    // Transit of 1882 December 6, the times depend on Delta T
+    let transit = Transit::new(
+        &ephemeris, Body::Venus, JulianDate::from_calendar(1882, 12, 6.7)
+    ).unwrap().unwrap();
+    assert_relative_eq!(ut_of(transit.greatest()), ut(1882, 12, 6, 17, 6, 0.0),
+                        epsilon = 2.0 * MINUTE);
+    assert_relative_eq!(degrees(transit.separation()) * 3600.0, 637.0,
+                        epsilon = 2.0);
+
+    // No transit at the inferior conjunction of 2010 October 29
+    assert!(Transit::new(
+        &ephemeris, Body::Venus, JulianDate::from_calendar(2010, 10, 29.0)
+    ).unwrap().is_none());
+}
+
+#[test]
+fn mercury_transit_test() {
+    // Transit of 2019 November 11 (NASA)
+    let ephemeris = AnalyticEphemeris::new();
+    let transit = Transit::new(
+        &ephemeris, Body::Mercury, JulianDate::from_calendar(2019, 11, 11.6)
+    ).unwrap().unwrap();
+
+    let ut_of = |jd: JulianDate| jd.tt_to_ut().jd();
+    assert_relative_eq!(ut_of(transit.first()), ut(2019, 11, 11, 12, 35, 27.0),
+                        epsilon = MINUTE);
+    assert_relative_eq!(ut_of(transit.greatest()),
+                        ut(2019, 11, 11, 15, 19, 48.0), epsilon = MINUTE);
+    assert_relative_eq!(ut_of(transit.fourth()), ut(2019, 11, 11, 18, 4, 5.0),
+                        epsilon = MINUTE);
+    assert_relative_eq!(degrees(transit.separation()) * 3600.0, 75.9,
+                        epsilon = 1.0);
+
+    // Washington, the whole transit is visible
+    let observer = Observer::new(Angle::from_ad(-77.0), Angle::from_ad(38.9),
+                                 0.0);
+    let local = transit.local(&ephemeris, &observer).unwrap().unwrap();
+    assert!((local.first().jd() - transit.first()).abs() < 2.0 * MINUTE);
+    assert!(degrees(local.first().altitude()) > 0.0);
+    assert_relative_eq!(degrees(local.first().position_angle()), 110.0,
+                        epsilon = 1.0);
+    assert!(degrees(local.fourth().altitude()) > 0.0);
+
+    assert!(matches!(
+        Transit::new(&ephemeris, Body::Mars,
+                     JulianDate::from_calendar(2019, 11, 11.6)),
+        Err(ephem::base::error::Error::InvalidBodyError)
+    ));
+}
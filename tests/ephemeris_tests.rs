@@ -0,0 +1,191 @@
+#![allow(dead_code)]
+
+mod common;
+
+#[macro_use]
+extern crate approx;
+
+use ephem::base::angle::Angle;
+use ephem::base::consts::{ARCS, AU, C_LIGHT, DEG, GM_SUN};
+use ephem::base::error::Error;
+use ephem::base::linalg::{Mat3D, Norm, SphericalVec3D, Vec3D};
+use ephem::ephemeris::*;
+use ephem::ephemeris::kepler::OrbitalElements;
+use ephem::ephemeris::state::{Frame, StateVector};
+use ephem::frames::rotating::RotatingFrame;
+use ephem::time::JulianDate;
+
+
+#[test]
+fn moon_position_test() {
+    // Meeus, example 47.a
+    let (l, b, r) = moon::position(JulianDate::new(2448724.5));
+    assert_relative_eq!(l * DEG, 133.162655, epsilon = 1e-6);
+    assert_relative_eq!(b * DEG, -3.229126, epsilon = 1e-6);
+    assert_relative_eq!(r, 368409.7, epsilon = 0.1);
+}
+
+#[test]
+fn earth_position_test() {
+    // Meeus, example 25.b
+    let (l, b, r) = vsop87::EARTH.position(JulianDate::new(2448908.5));
+    assert_relative_eq!(l * DEG, 19.907372, epsilon = 1e-6);
+    assert_relative_eq!(b * ARCS, -0.644, epsilon = 1e-3);
+    assert_relative_eq!(r, 0.99760775, epsilon = 1e-8);
+}
+
+#[test]
+fn venus_position_test() {
+    // Meeus, example 32.a
+    let (l, b, r) = vsop87::VENUS.position(JulianDate::new(2448976.5));
+    assert_relative_eq!(l * DEG, 26.11428, epsilon = 1e-5);
+    assert_relative_eq!(b * DEG, -2.62070, epsilon = 1e-5);
+    assert_relative_eq!(r, 0.724603, epsilon = 1e-6);
+}
+
+#[test]
+fn apparent_position_test() {
+    let ephemeris = AnalyticEphemeris::new();
+
+    // Meeus, example 25.b (without the FK5 correction of -0.09")
+    let jd = JulianDate::new(2448908.5);
+    let s: SphericalVec3D =
+        apparent_ecliptic(&ephemeris, Body::Sun, jd).unwrap().into();
+    assert_relative_eq!(s.phi() * ARCS, 199.906060 * 3600.0, epsilon = 0.5);
+    assert_relative_eq!(s.r(), 0.99760775, epsilon = 1e-6);
+
+    // Meeus, example 33.a
+    let jd = JulianDate::new(2448976.5);
+    let s: SphericalVec3D =
+        apparent(&ephemeris, Body::Venus, jd).unwrap().into();
+    assert_relative_eq!(s.phi() * ARCS, 316.172698 * 3600.0, epsilon = 5.0);
+    assert_relative_eq!(s.theta() * ARCS, -18.888010 * 3600.0, epsilon = 5.0);
+
+    assert!(apparent(&ephemeris, Body::Earth, jd).is_err());
+}
+
+#[test]
+fn analytic_state_test() {
+    let ephemeris = AnalyticEphemeris::new();
+    let jd = JulianDate::new(2448908.5);
+
+    let (sun, _) = ephemeris.state(Body::Sun, jd).unwrap();
+    assert!(sun.norm() < 0.01);
+
+    let (earth, velocity) = ephemeris.state(Body::Earth, jd).unwrap();
+    assert_relative_eq!(earth.norm(), 1.0, epsilon = 0.02);
+    assert_relative_eq!(velocity.norm() / C_LIGHT * ARCS, 20.5, epsilon = 0.5);
+
+    let moon = ephemeris.position(Body::Moon, jd).unwrap();
+    let distance = (moon - earth).norm() * AU;
+    assert!(distance > 356000.0 && distance < 407000.0);
+
+    for body in Body::PLANETS.iter() {
+        let (r, v) = ephemeris.state(*body, jd).unwrap();
+        let h = 0.01;
+        let r1 = ephemeris.position(*body, jd + h).unwrap();
+        let r0 = ephemeris.position(*body, jd - h).unwrap();
+        let dv = (r1 - r0) * (0.5 / h) - v;
+
+        assert!(r.norm() > 0.25);
+        assert!(dv.norm() < 1e-4 * v.norm());
+    }
+}
+
+#[test]
+fn state_vector_test() {
+    let jd = JulianDate::new(2448976.5);
+    let state = StateVector::from(kepler::MARS.state(jd))
+        .with_epoch(jd)
+        .with_frame(Frame::EclipticJ2000);
+    let other = StateVector::new(
+        Vec3D::cartesian(0.1, 0.2, -0.3), Vec3D::cartesian(1e-3, 0.0, 2e-3)
+    );
+
+    let sum = (state + other).unwrap();
+    assert_eq!(sum.epoch(), Some(jd));
+    assert_eq!(sum.frame(), Some(Frame::EclipticJ2000));
+    assert_eq!((other + state).unwrap().frame(), Some(Frame::EclipticJ2000));
+    let difference = (sum - other).unwrap();
+    assert_relative_eq!((difference.position() - state.position()).norm(),
+                        0.0, epsilon = common::EPS);
+    assert_relative_eq!((difference.velocity() - state.velocity()).norm(),
+                        0.0, epsilon = common::EPS);
+
+    // The states of different epochs or frames cannot be combined
+    let later = state.with_epoch(jd + 1.0);
+    assert!(matches!(state + later, Err(Error::TagMismatchError)));
+    assert!(matches!(state - later, Err(Error::TagMismatchError)));
+    let icrs = other.with_frame(Frame::Icrs);
+    assert!(matches!(state - icrs, Err(Error::TagMismatchError)));
+    assert!((state - state).is_ok());
+
+    let scaled = 2.0 * state * 0.5;
+    let (r, v): (Vec3D, Vec3D) = scaled.into();
+    assert_relative_eq!((r - state.position()).norm(), 0.0);
+    assert_relative_eq!((v - state.velocity()).norm(), 0.0);
+
+    // Rotations change the frame and keep the epoch
+    let obliquity = 84381.406 / ARCS;
+    let equatorial = state.rotate(Mat3D::r_x(-obliquity), Frame::Icrs);
+    assert_eq!(equatorial.frame(), Some(Frame::Icrs));
+    assert_eq!(equatorial.epoch(), Some(jd));
+    assert_eq!((Mat3D::identity() * state).frame(), None);
+    assert_relative_eq!(equatorial.velocity().norm(), state.velocity().norm(),
+                        epsilon = common::EPS);
+
+    let omega = Vec3D::unit_z() * 0.01;
+    let rotating = RotatingFrame::from_angular_velocity(Mat3D::identity(),
+                                                        omega);
+    let corotating = state.transform(&rotating, Frame::EclipticOfDate);
+    let expected = state.velocity() - omega.cross(state.position());
+    assert_relative_eq!((corotating.velocity() - expected).norm(), 0.0,
+                        epsilon = common::EPS);
+}
+
+#[test]
+fn orbital_elements_test() {
+    let elements = OrbitalElements::new(
+        1.523679, 0.0934, Angle::from_ad(1.85), Angle::from_ad(49.56),
+        Angle::from_ad(286.5), Angle::from_ad(19.4)
+    );
+    let state = StateVector::from_elements(&elements, GM_SUN);
+    let back = state.to_elements(GM_SUN).unwrap();
+
+    assert_relative_eq!(back.semi_major_axis(), 1.523679, epsilon = 1e-12);
+    assert_relative_eq!(back.eccentricity(), 0.0934, epsilon = 1e-12);
+    let angles = [
+        (back.inclination(), elements.inclination()),
+        (back.node(), elements.node()),
+        (back.argument_of_periapsis(), elements.argument_of_periapsis()),
+        (back.mean_anomaly(), elements.mean_anomaly())
+    ];
+    for (a1, a2) in angles {
+        let (a1, a2): (f64, f64) = (a1.into(), a2.into());
+        assert_relative_eq!(a1, a2, epsilon = 1e-10);
+    }
+    assert_relative_eq!(back.period(GM_SUN), 686.98, epsilon = 0.01);
+
+    // The Standish elements of the Earth-Moon barycentre
+    let jd = JulianDate::new(2451545.0);
+    let earth = StateVector::from(kepler::EARTH_MOON.state(jd))
+        .to_elements(GM_SUN)
+        .unwrap();
+    assert_relative_eq!(earth.semi_major_axis(), 1.0, epsilon = 1e-4);
+    assert_relative_eq!(earth.eccentricity(), 0.0167, epsilon = 1e-4);
+
+    // Circular equatorial orbit
+    let circular = StateVector::new(
+        Vec3D::cartesian(0.0, 2.0, 0.0),
+        Vec3D::cartesian(-(GM_SUN / 2.0).sqrt(), 0.0, 0.0)
+    ).to_elements(GM_SUN).unwrap();
+    assert_relative_eq!(circular.eccentricity(), 0.0, epsilon = 1e-12);
+    let m: f64 = circular.mean_anomaly().into();
+    assert_relative_eq!(m * DEG, 90.0, epsilon = 1e-9);
+
+    let escape = StateVector::new(
+        Vec3D::cartesian(1.0, 0.0, 0.0), Vec3D::cartesian(0.0, 0.03, 0.0)
+    );
+    assert!(escape.to_elements(GM_SUN).is_err());
+    assert!(StateVector::zero().to_elements(GM_SUN).is_err());
+}
@@ -0,0 +1,162 @@
+#![allow(dead_code)]
+
+mod common;
+
+#[macro_use]
+extern crate approx;
+
+use ephem::base::angle::Angle;
+use ephem::eclipses::lunar::LunarEclipseType;
+use ephem::eclipses::solar::SolarEclipseType;
+use ephem::ephemeris::{AnalyticEphemeris, Body};
+use ephem::events::*;
+use ephem::observer::Observer;
+use ephem::time::JulianDate;
+
+const MINUTE: f64 = 1.0 / 1440.0;
+
+
+fn collect(iterator: impl Iterator<Item = ephem::base::Result<Event>>)
+    -> Vec<Event>
+{
+    iterator.map(|e| e.unwrap()).collect()
+}
+
+#[test]
+fn phases_and_seasons_test() {
+    let ephemeris = AnalyticEphemeris::new();
+    let start = JulianDate::from_calendar(2024, 3, 1.0);
+    let end = JulianDate::from_calendar(2024, 4, 1.0);
+
+    let events = collect(
+        EventIterator::new(&ephemeris, start, end)
+            .with_categories(&[Category::Phases, Category::Seasons])
+    );
+
+    let expected = [
+        (EventKind::Phase(Phase::LastQuarter), 3, 15, 23),
+        (EventKind::Phase(Phase::NewMoon), 10, 9, 0),
+        (EventKind::Phase(Phase::FirstQuarter), 17, 4, 11),
+        (EventKind::Season(Season::MarchEquinox), 20, 3, 6),
+        (EventKind::Phase(Phase::FullMoon), 25, 7, 0)
+    ];
+
+    assert_eq!(events.len(), expected.len());
+    for (event, &(kind, day, hour, minute)) in events.iter().zip(expected.iter()) {
+        let ut = JulianDate::from_time(2024, 3, day, hour, minute, 0.0);
+        assert_eq!(event.kind(), kind);
+        assert_relative_eq!(
+            event.jd().tt_to_ut().jd(), ut.jd(), epsilon = 2.0 * MINUTE
+        );
+    }
+}
+
+#[test]
+fn eclipses_and_apsides_test() {
+    let ephemeris = AnalyticEphemeris::new();
+    let start = JulianDate::from_calendar(2024, 3, 20.0);
+    let end = JulianDate::from_calendar(2024, 4, 20.0);
+
+    let events = collect(
+        EventIterator::new(&ephemeris, start, end)
+            .with_categories(&[Category::Eclipses, Category::Apsides])
+    );
+    let kinds: Vec<EventKind> = events.iter().map(|e| e.kind()).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            EventKind::Apogee,
+            EventKind::LunarEclipse(LunarEclipseType::Penumbral),
+            EventKind::Perigee,
+            EventKind::SolarEclipse(SolarEclipseType::Total)
+        ]
+    );
+
+    let lunar = JulianDate::from_time(2024, 3, 25, 7, 13, 0.0);
+    assert_relative_eq!(
+        events[1].jd().tt_to_ut().jd(), lunar.jd(), epsilon = 5.0 * MINUTE
+    );
+
+    let solar = JulianDate::from_time(2024, 4, 8, 18, 17, 0.0);
+    assert_relative_eq!(
+        events[3].jd().tt_to_ut().jd(), solar.jd(), epsilon = 5.0 * MINUTE
+    );
+
+    for window in events.windows(2) {
+        assert!(window[0].jd() <= window[1].jd());
+    }
+}
+
+#[test]
+fn conjunctions_test() {
+    let ephemeris = AnalyticEphemeris::new();
+    let start = JulianDate::from_calendar(2024, 6, 1.0);
+    let end = JulianDate::from_calendar(2024, 6, 10.0);
+
+    let events = collect(
+        EventIterator::new(&ephemeris, start, end)
+            .with_categories(&[Category::Conjunctions])
+            .with_bodies(&[Body::Sun, Body::Venus, Body::Earth])
+    );
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].kind(), EventKind::Conjunction(Body::Sun, Body::Venus)
+    );
+
+    let (_, month, day) = events[0].jd().calendar();
+    assert_eq!(month, 6);
+    assert_eq!(day.floor() as i32, 4);
+}
+
+#[test]
+fn rises_sets_test() {
+    let ephemeris = AnalyticEphemeris::new();
+    let greenwich = Observer::new(
+        Angle::from_ad(0.0), Angle::from_ad(51.4769), 0.0
+    );
+    let start = JulianDate::from_calendar(2024, 6, 21.0).ut_to_tt();
+
+    let events = collect(
+        EventIterator::new(&ephemeris, start, start + 1.0)
+            .with_categories(&[Category::RisesSets])
+            .with_bodies(&[Body::Sun])
+            .with_observer(greenwich)
+    );
+    assert_eq!(events.len(), 2);
+
+    assert_eq!(events[0].kind(), EventKind::Rise(Body::Sun));
+    let rise = JulianDate::from_time(2024, 6, 21, 3, 43, 0.0);
+    assert_relative_eq!(
+        events[0].jd().tt_to_ut().jd(), rise.jd(), epsilon = 2.0 * MINUTE
+    );
+
+    assert_eq!(events[1].kind(), EventKind::Set(Body::Sun));
+    let set = JulianDate::from_time(2024, 6, 21, 20, 21, 0.0);
+    assert_relative_eq!(
+        events[1].jd().tt_to_ut().jd(), set.jd(), epsilon = 2.0 * MINUTE
+    );
+
+    let events = collect(
+        EventIterator::new(&ephemeris, start, start + 1.0)
+            .with_categories(&[Category::RisesSets])
+            .with_bodies(&[Body::Sun])
+    );
+    assert!(events.is_empty());
+}
+
+#[test]
+fn lazy_iteration_test() {
+    let ephemeris = AnalyticEphemeris::new();
+    let start = JulianDate::from_calendar(2024, 1, 1.0);
+    let end = JulianDate::from_calendar(2100, 1, 1.0);
+
+    let limit = JulianDate::from_calendar(2024, 2, 1.0);
+    let phases: Vec<Event> = EventIterator::new(&ephemeris, start, end)
+        .with_categories(&[Category::Phases])
+        .map(|e| e.unwrap())
+        .take_while(|e| e.jd() < limit)
+        .collect();
+
+    assert_eq!(phases.len(), 4);
+    assert_eq!(phases[1].kind(), EventKind::Phase(Phase::NewMoon));
+}
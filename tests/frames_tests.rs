@@ -0,0 +1,651 @@
+#![allow(dead_code)]
+
+mod common;
+
+#[macro_use]
+extern crate approx;
+
+use ephem::base::angle::Angle;
+use ephem::base::consts::{ARCS, AU, DEG};
+use ephem::base::error::Error;
+use ephem::base::linalg::{CartesianVec3D, Mat3D, Norm, SphericalVec3D, Vec3D};
+use ephem::astrometry::star::Star;
+use ephem::ephemeris::{AnalyticEphemeris, Body, Ephemeris};
+use ephem::frames::cio::*;
+use ephem::frames::eop::*;
+use ephem::frames::fk::*;
+use ephem::frames::nutation::*;
+use ephem::frames::precession::*;
+use ephem::frames::rotating::RotatingFrame;
+use ephem::frames::sidereal::*;
+use ephem::frames::tagged::*;
+use ephem::frames::topocentric::*;
+use ephem::observer::Observer;
+use ephem::time::{tai_utc, Epoch, JulianDate};
+
+
+#[test]
+fn precession_test() {
+    let jd = JulianDate::j2000();
+    assert_relative_eq!(obliquity(jd) * ARCS, 84381.406);
+
+    let p = precession_matrix(jd);
+    for (v1, v2) in p.iter().zip(Mat3D::identity().iter()) {
+        assert_relative_eq!(v1, v2, epsilon = 1e-7);
+    }
+
+    // Meeus, example 21.b: theta Persei, J2000 -> 2028 Nov 13.19
+    let jd = JulianDate::new(2462088.69);
+    let r = Vec3D::unit(41.054063 / DEG, 49.227750 / DEG).unwrap();
+    let s: SphericalVec3D = (precession_matrix(jd) * r).into();
+    assert_relative_eq!(s.phi() * DEG, 41.547214, epsilon = 1e-4);
+    assert_relative_eq!(s.theta() * DEG, 49.348483, epsilon = 1e-4);
+
+    let m = ecliptic_to_equatorial(jd) * equatorial_to_ecliptic(jd);
+    for (v1, v2) in m.iter().zip(Mat3D::identity().iter()) {
+        assert_relative_eq!(v1, v2, epsilon = common::EPS);
+    }
+}
+
+#[test]
+fn nutation_test() {
+    // Meeus, example 22.a
+    let jd = JulianDate::new(2446895.5);
+    let (dpsi, deps) = nutation(jd);
+    assert_relative_eq!(dpsi * ARCS, -3.788, epsilon = 1e-3);
+    assert_relative_eq!(deps * ARCS, 9.443, epsilon = 1e-3);
+
+    let n = nutation_matrix(jd);
+    assert_relative_eq!(n.det(), 1.0, epsilon = common::EPS);
+}
+
+#[test]
+fn sidereal_test() {
+    // Meeus, examples 12.a and 12.b
+    let jd = JulianDate::new(2446895.5);
+    assert_relative_eq!(gmst(jd) * DEG, 197.693195, epsilon = 1e-6);
+    assert_relative_eq!(
+        equation_of_equinoxes(jd) * ARCS, -3.4752, epsilon = 1e-3
+    );
+
+    let jd = JulianDate::from_time(1987, 4, 10, 19, 21, 0.0);
+    assert_relative_eq!(gmst(jd) * DEG, 128.7378734, epsilon = 1e-6);
+}
+
+#[test]
+fn topocentric_test() {
+    // Meeus, example 40.a: Mars from Palomar, 2003 Aug 28 3h17m UT
+    let palomar = Observer::new(
+        Angle::from_ad(-116.8625), Angle::from_ad(33.356111), 1706.0
+    );
+    let jd = JulianDate::from_time(2003, 8, 28, 3, 17, 0.0).ut_to_tt();
+    let r = Vec3D::spherical(
+        0.37276, 339.530208 / DEG, -15.771083 / DEG
+    ).unwrap();
+
+    let topocentric = Topocentric::new(&palomar, r, jd);
+    let alpha: f64 = topocentric.right_ascension().into();
+    let delta: f64 = topocentric.declination().into();
+    let parallax: f64 = topocentric.parallax().into();
+    assert_relative_eq!(alpha * DEG, 339.535583, epsilon = 5e-5);
+    assert_relative_eq!(delta * DEG, -15.775, epsilon = 5e-5);
+    assert_relative_eq!(parallax * ARCS, 23.592, epsilon = 1e-3);
+
+    let distance = (topocentric.position() - r).norm() * AU;
+    assert_relative_eq!(distance, palomar.geocentric().norm(),
+                        epsilon = 1e-6);
+    assert_relative_eq!(
+        horizontal_parallax(r.norm()), parallax, epsilon = common::EPS
+    );
+}
+
+#[test]
+#[allow(clippy::excessive_precision)]
+fn frame_bias_test() {
+    // SOFA iauBp00 test case
+    let expected = [
+        0.9999999999999942498, -0.7078279744199196626e-7,
+        0.8056217146976134152e-7, 0.7078279477857337206e-7,
+        0.9999999999999969484, 0.3306041454222136517e-7,
+        -0.8056217380986972157e-7, -0.3306040883980552500e-7,
+        0.9999999999999962084
+    ];
+    for (v1, v2) in frame_bias_matrix().iter().zip(expected.iter()) {
+        assert_relative_eq!(v1, v2, epsilon = 1e-14);
+    }
+}
+
+///
+/// Star from the SOFA catalogue form: the proper motions are the rates of
+/// the right ascension and of the declination [rad per year of the length
+/// in days] and the parallax is in arcseconds
+///
+fn sofa_star(alpha: f64, delta: f64, (dr, dd): (f64, f64), parallax: f64,
+             radial_velocity: f64, year: f64) -> Star {
+    let scale = 1e3 * ARCS * 365.25 / year;
+
+    Star::new(Angle::from(alpha), Angle::from(delta))
+        .with_proper_motion(dr * delta.cos() * scale, dd * scale)
+        .with_parallax(parallax * 1e3)
+        .with_radial_velocity(radial_velocity)
+}
+
+fn assert_star(star: &Star, expected: &Star) {
+    let (a1, d1): (f64, f64) = (star.right_ascension().into(),
+                                star.declination().into());
+    let (a2, d2): (f64, f64) = (expected.right_ascension().into(),
+                                expected.declination().into());
+    assert_relative_eq!(a1, a2, epsilon = 1e-10);
+    assert_relative_eq!(d1, d2, epsilon = 1e-10);
+    assert_relative_eq!(star.pm_ra(), expected.pm_ra(), epsilon = 1e-4);
+    assert_relative_eq!(star.pm_dec(), expected.pm_dec(), epsilon = 1e-4);
+    assert_relative_eq!(star.parallax(), expected.parallax(),
+                        epsilon = 1e-6);
+    assert_relative_eq!(star.radial_velocity(), expected.radial_velocity(),
+                        epsilon = 1e-6);
+}
+
+#[test]
+#[allow(clippy::excessive_precision)]
+fn fk4_fk5_test() {
+    let tropical = 365.242198781;
+
+    // SOFA iauFk425 and iauFk524 test cases
+    let fk4 = sofa_star(
+        0.07626899753879587532, -1.137405378399605780,
+        (0.1973749217849087460e-4, 0.5659714913272723189e-5), 0.134, 8.7,
+        tropical
+    ).with_epoch(Epoch::b1950().jd());
+    let fk5 = sofa_star(
+        0.08757989933556446040, -1.132279113042091895,
+        (0.1953670614474396139e-4, 0.5637686678659640164e-5),
+        0.1339919950582767871, 8.736999669183529069, 365.25
+    );
+    assert_star(&fk4_to_fk5(&fk4).unwrap(), &fk5);
+    assert_eq!(fk4_to_fk5(&fk4).unwrap().epoch(), JulianDate::j2000());
+
+    let fk5 = sofa_star(
+        0.8723503576487275595, -0.7517076365138887672,
+        (0.2019447755430472323e-4, 0.3541563940505160433e-5), 0.1559, 86.87,
+        365.25
+    );
+    let fk4 = sofa_star(
+        0.8636359659799603487, -0.7550281733160843059,
+        (0.2023628192747172486e-4, 0.3624459754935334718e-5),
+        0.1560079963299390241, 86.79606353469163751, tropical
+    ).with_epoch(Epoch::b1950().jd());
+    assert_star(&fk5_to_fk4(&fk5).unwrap(), &fk4);
+    assert_star(&fk4_to_fk5(&fk5_to_fk4(&fk5).unwrap()).unwrap(), &fk5);
+
+    // iauFk45z and iauFk54z: places without the proper motions
+    let epoch = |year: f64| Epoch::Besselian(year).jd();
+    let u = Vec3D::unit(0.01602284975382960982, -0.1164347929099906024)
+        .unwrap();
+    let s: SphericalVec3D =
+        fk4_to_fk5_direction(u, epoch(1954.677617625256806)).unwrap().into();
+    assert_relative_eq!(s.phi(), 0.02719295911606862303, epsilon = 1e-12);
+    assert_relative_eq!(s.theta(), -0.1115766001565926892, epsilon = 1e-11);
+
+    let u = Vec3D::unit(0.02719026625066316119, -0.1115815170738754813)
+        .unwrap();
+    let s: SphericalVec3D =
+        fk5_to_fk4_direction(u, epoch(1954.677308160316374)).unwrap().into();
+    assert_relative_eq!(s.phi(), 0.01602015588390065476, epsilon = 1e-12);
+    assert_relative_eq!(s.theta(), -0.1164397101110765346, epsilon = 1e-11);
+}
+
+#[test]
+#[allow(clippy::excessive_precision)]
+fn fk5_icrs_test() {
+    // SOFA iauFk52h and iauFk5hz test cases
+    let fk5 = sofa_star(1.76779433, -0.2917517103,
+                        (-1.91851572e-7, -5.8468475e-6), 0.379210, -7.6,
+                        365.25);
+    let icrs = sofa_star(
+        1.767794226299947632, -0.2917516070530391757,
+        (-0.1961874125605721270e-6, -0.58459905176693911e-5), 0.37921,
+        -7.6000000940000254, 365.25
+    );
+    assert_star(&fk5_to_icrs(&fk5).unwrap(), &icrs);
+    assert_star(&icrs_to_fk5(&icrs).unwrap(), &fk5);
+
+    let u = Vec3D::unit(1.76779433, -0.2917517103).unwrap();
+    let m = fk5_to_icrs_matrix(JulianDate::new(2454479.5));
+    let s: SphericalVec3D = (m * u).into();
+    assert_relative_eq!(s.phi(), 1.767794191464423978, epsilon = 1e-12);
+    assert_relative_eq!(s.theta(), -0.2917516001679884419, epsilon = 1e-12);
+
+    // The stars without a parallax keep it
+    let star = Star::new(Angle::from(1.0), Angle::from(0.5))
+        .with_proper_motion(10.0, -5.0)
+        .with_radial_velocity(20.0);
+    let icrs = fk5_to_icrs(&star).unwrap();
+    assert_eq!(icrs.parallax(), 0.0);
+    assert_eq!(icrs.radial_velocity(), 20.0);
+    assert_star(&icrs_to_fk5(&icrs).unwrap(), &star);
+}
+
+///
+/// Line of a finals2000A file with the coordinates of the pole [arcsec],
+/// UT1 - UTC [s] and the celestial pole offsets [mas]
+///
+fn finals_line(mjd: f64, x_p: f64, y_p: f64, ut1_utc: f64,
+               dx: f64, dy: f64) -> String {
+    let (year, month, day) = JulianDate::from_mjd(mjd).calendar();
+    format!(
+        "{:2}{:2}{:2} {:8.2} I {:9.6}{:9.6} {:9.6}{:9.6}  I{:10.7}{:10.7} \
+         {:7.4}{:7.4}  I {:9.3}{:9.3} {:9.3}{:9.3}",
+        year % 100, month, day as u32, mjd, x_p, 0.00003, y_p, 0.00003,
+        ut1_utc, 0.00001, 1.0, 0.01, dx, 0.3, dy, 0.3
+    )
+}
+
+///
+/// Smooth model of the parameters around the leap second of 2017
+///
+fn model(mjd: f64) -> (f64, f64, f64, f64, f64) {
+    let t = mjd - 57750.0;
+    let ut1_tai = -36.4 - 0.0012 * t + 1e-5 * t * t;
+    (
+        0.07 + 0.001 * t - 2e-5 * t * t + 1e-7 * t * t * t,
+        0.28 + 0.002 * t,
+        ut1_tai + tai_utc(JulianDate::from_mjd(mjd)),
+        -0.1 + 0.01 * t,
+        0.3 - 0.002 * t * t
+    )
+}
+
+#[test]
+fn eop_test() {
+    let mut text = String::new();
+    for day in 0..10 {
+        let mjd = 57750.0 + day as f64;
+        let (x_p, y_p, ut1_utc, dx, dy) = model(mjd);
+        text.push_str(&finals_line(mjd, x_p, y_p, ut1_utc, dx, dy));
+        text.push('\n');
+    }
+    // A prediction without UT1 - UTC is skipped
+    text.push_str(&finals_line(57760.0, 0.1, 0.3, 0.0, 0.0, 0.0)[..58]);
+    text.push('\n');
+
+    let table = EopTable::from_finals(&text).unwrap();
+    assert_eq!(table.len(), 10);
+    let (first, last) = table.range().unwrap();
+    assert_relative_eq!(first.mjd(), 57750.0);
+    assert_relative_eq!(last.mjd(), 57759.0);
+
+    // The cubic model is interpolated exactly, UT1 - UTC over the leap
+    // second too
+    for &mjd in [57750.0, 57752.25, 57753.999, 57754.0, 57754.5, 57759.0]
+        .iter() {
+        let eop = table.at(JulianDate::from_mjd(mjd)).unwrap();
+        let (x_p, y_p, ut1_utc, dx, dy) = model(mjd);
+        let x: f64 = eop.x_p().into();
+        let y: f64 = eop.y_p().into();
+        let ddx: f64 = eop.dx().into();
+        let ddy: f64 = eop.dy().into();
+        assert_relative_eq!(x * ARCS, x_p, epsilon = 1e-6);
+        assert_relative_eq!(y * ARCS, y_p, epsilon = 1e-6);
+        assert_relative_eq!(eop.ut1_utc(), ut1_utc, epsilon = 1e-6);
+        assert_relative_eq!(ddx * ARCS * 1e3, dx, epsilon = 1e-3);
+        assert_relative_eq!(ddy * ARCS * 1e3, dy, epsilon = 1e-3);
+        assert_relative_eq!((eop.ut1() - eop.utc()) * 86400.0, ut1_utc,
+                            epsilon = 1e-4);
+    }
+    assert!(table.at(JulianDate::from_mjd(57749.9)).is_err());
+    assert!(table.at(JulianDate::from_mjd(57759.1)).is_err());
+
+    let tt = JulianDate::from_mjd(57755.5).utc_to_tt();
+    let ut1 = table.tt_to_ut1(tt).unwrap();
+    assert_relative_eq!((ut1.mjd() - 57755.5) * 86400.0, model(57755.5).2,
+                        epsilon = 1e-4);
+
+    // The polar motion moves the CIP to (x_p, -y_p) in the ITRS
+    let eop = table.at(JulianDate::from_mjd(57755.0)).unwrap();
+    let pole: CartesianVec3D =
+        (eop.polar_motion_matrix() * Vec3D::unit_z()).into();
+    let x: f64 = eop.x_p().into();
+    let y: f64 = eop.y_p().into();
+    assert_relative_eq!(pole.x(), x, epsilon = 1e-12);
+    assert_relative_eq!(pole.y(), -y, epsilon = 1e-12);
+
+    // The same matrix as the CIO based transformation, TIO locator included
+    let c2t = CelestialToTerrestrial::new(eop.utc().utc_to_tt())
+        .with_earth_orientation(&eop);
+    let w = eop.polar_motion_matrix();
+    for (v1, v2) in w.iter().zip(c2t.polar_motion().iter()) {
+        assert_relative_eq!(v1, v2, epsilon = 1e-15);
+    }
+
+    assert!(EopTable::from_finals("17 1 1 57754.x0").is_err());
+    assert!(EopTable::from_finals("").unwrap()
+        .at(JulianDate::j2000()).is_err());
+}
+
+#[test]
+fn eop_c04_test() {
+    let c04_14 = "\
+ EARTH ORIENTATION PARAMETER (EOP) PRODUCT CENTER
+      Date      MJD      x          y        UT1-UTC       LOD         dX        dY
+                         \"          \"           s           s          \"         \"
+
+2016  12  30  57752   0.075016   0.281620  -0.4060573   0.0014180   0.000052  -0.000118
+2016  12  31  57753   0.076168   0.282403  -0.4074659   0.0014121   0.000078  -0.000147
+2017   1   1  57754   0.077373   0.283190   0.5911384   0.0014065   0.000095  -0.000167
+2017   1   2  57755   0.078536   0.284047   0.5897411   0.0013851   0.000099  -0.000174
+";
+    let c04_20 = "\
+# YR  MM  DD  HH       MJD        x(\")        y(\")  UT1-UTC(s)       dX(\")      dY(\")
+2016  12  30   0  57752.00   0.075016   0.281620  -0.4060573   0.000052  -0.000118
+2016  12  31   0  57753.00   0.076168   0.282403  -0.4074659   0.000078  -0.000147
+2017   1   1   0  57754.00   0.077373   0.283190   0.5911384   0.000095  -0.000167
+2017   1   2   0  57755.00   0.078536   0.284047   0.5897411   0.000099  -0.000174
+";
+
+    let path = std::env::temp_dir().join("ephem_eop_c04_test.txt");
+    std::fs::write(&path, c04_14).unwrap();
+    let from_file = EopTable::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    for table in [EopTable::from_c04(c04_20).unwrap(), from_file] {
+        assert_eq!(table.len(), 4);
+
+        // UT1 - UTC jumps by the leap second, UT1 itself is continuous
+        let before = table.at(JulianDate::from_mjd(57753.999999)).unwrap();
+        let after = table.at(JulianDate::from_mjd(57754.0)).unwrap();
+        assert_relative_eq!(after.ut1_utc() - before.ut1_utc(), 1.0,
+                            epsilon = 1e-3);
+        assert_relative_eq!(after.ut1_utc(), 0.5911384, epsilon = 1e-9);
+
+        let eop = table.at(JulianDate::from_mjd(57753.5)).unwrap();
+        assert_relative_eq!(eop.ut1_utc(), -0.4081, epsilon = 1e-3);
+        let x: f64 = eop.x_p().into();
+        assert_relative_eq!(x * ARCS, 0.07677, epsilon = 1e-4);
+        let dy: f64 = eop.dy().into();
+        assert_relative_eq!(dy * ARCS, -0.000158, epsilon = 1e-5);
+    }
+
+    assert!(EopTable::from_file("/nonexistent/finals2000A.all").is_err());
+    assert!(EopTable::from_c04("2017 1 1 57754 0.07").is_err());
+}
+
+#[test]
+#[allow(clippy::excessive_precision)]
+fn cio_test() {
+    // SOFA iauNut00b, iauXy06, iauS06, iauEra00 and iauSp00 test cases
+    let jd = JulianDate::from_mjd(53736.0);
+    let (dpsi, deps) = nutation_2000b(jd);
+    assert_relative_eq!(dpsi, -0.9632552291148362783e-5, epsilon = 1e-13);
+    assert_relative_eq!(deps, 0.4063197106621159367e-4, epsilon = 1e-13);
+
+    // The IAU 2000B pole is within 1 mas of the IAU 2006/2000A pole
+    let (x, y) = cip_2000b(jd);
+    assert_relative_eq!(x, 0.5791308486706011000e-3, epsilon = 5e-9);
+    assert_relative_eq!(y, 0.4020579816732961219e-4, epsilon = 5e-9);
+
+    let s = cio_locator(
+        jd, 0.5791308486706011000e-3, 0.4020579816732961219e-4
+    );
+    assert_relative_eq!(s, -0.1220032213076463117e-7, epsilon = 1e-11);
+
+    let era = earth_rotation_angle(JulianDate::from_mjd(54388.0));
+    assert_relative_eq!(era, 0.4022837240028158102, epsilon = 1e-12);
+    let s_prime = tio_locator(JulianDate::from_mjd(52541.0));
+    assert_relative_eq!(s_prime, -0.6216698469981019309e-11,
+                        epsilon = 1e-16);
+
+    // SOFA iauC2ixys test case
+    let expected = [
+        0.9999998323037157138, 0.5581984869168499149e-9,
+        -0.5791308491611282180e-3, -0.2384261642670440317e-7,
+        0.9999999991917468964, -0.4020579110169668931e-4,
+        0.5791308486706011000e-3, 0.4020579816732961219e-4,
+        0.9999998314954627590
+    ];
+    let m = celestial_to_intermediate_matrix(
+        0.5791308486706011000e-3, 0.4020579816732961219e-4,
+        -0.1220040848472271978e-7
+    );
+    for (v1, v2) in m.iter().zip(expected.iter()) {
+        assert_relative_eq!(v1, v2, epsilon = 1e-12);
+    }
+}
+
+#[test]
+#[allow(clippy::excessive_precision)]
+fn celestial_to_terrestrial_test() {
+    // SOFA iauC2t06a test case
+    let jd = JulianDate::from_mjd(53736.0);
+    let c2t = CelestialToTerrestrial::new(jd)
+        .with_ut1(jd)
+        .with_polar_motion(Angle::from(2.55060238e-7),
+                           Angle::from(1.860359247e-6));
+    let expected = [
+        -0.1810332128305897282, 0.9834769806938592296,
+        0.6555552006548146380e-4, -0.9834768134135996657,
+        -0.1810332203649448367, 0.5749801116141106224e-3,
+        0.5773474014081407076e-3, 0.3961832391770163647e-4,
+        0.9999998325501691969
+    ];
+    let m = c2t.matrix();
+    for (v1, v2) in m.iter().zip(expected.iter()) {
+        assert_relative_eq!(v1, v2, epsilon = 1e-8);
+    }
+    assert_relative_eq!(m.det(), 1.0, epsilon = common::EPS);
+
+    // Derivative against the difference over a second
+    let dt = 1.0 / 86400.0;
+    let later = CelestialToTerrestrial::new(jd + dt).with_ut1(jd + dt);
+    let earlier = CelestialToTerrestrial::new(jd - dt).with_ut1(jd - dt);
+    let difference = (later.matrix() - earlier.matrix()) / 2.0;
+    for (v1, v2) in difference.unwrap().iter()
+        .zip(c2t.derivative().iter()) {
+        assert_relative_eq!(v1, v2, epsilon = 1e-9);
+    }
+
+    let r = Vec3D::cartesian(0.3, -1.2, 0.8);
+    let back = c2t.to_celestial(c2t.to_terrestrial(r));
+    assert_relative_eq!((back - r).norm(), 0.0, epsilon = common::EPS);
+
+    // The pole offsets move the CIP
+    let eop = CelestialToTerrestrial::new(jd)
+        .with_pole_offsets(Angle::from(1e-9), Angle::from(-2e-9));
+    let (x0, y0) = c2t.cip();
+    let (x, y) = eop.cip();
+    assert_relative_eq!(x - x0, 1e-9, epsilon = 1e-15);
+    assert_relative_eq!(y - y0, -2e-9, epsilon = 1e-15);
+}
+
+#[test]
+fn cip_series_test() {
+    // Excerpts in the layout of the tables 5.2a and 5.2b
+    let x_table = "\
+Table 5.2a: X coordinate of the CIP
+----------------------------------------------------------------------
+ j = 0  Number of terms = 1
+----------------------------------------------------------------------
+    i    (a_{s,j})_i    (a_{c,j})_i    l    l'   F    D   Om L_Me L_Ve  \
+L_E L_Ma  L_J L_Sa  L_U L_Ne  p_A
+    1    1000.00    -200.00    0    0    0    0    1    0    0    0    0    \
+0    0    0    0    0
+----------------------------------------------------------------------
+ j = 1  Number of terms = 1
+----------------------------------------------------------------------
+    1      50.00       0.00    0    0    0    0    0    0    0    0    0    \
+0    0    0    0    1
+";
+    let y_table = "\
+ j = 0  Number of terms = 1
+    1       0.00     300.00    0    0    0    0    0    0    0    1    0    \
+0    0    0    0    0
+";
+    let series = CipSeries::from_tables(x_table, y_table).unwrap();
+    assert_eq!(series.len(), 3);
+
+    let uas = 1e-6 / ARCS;
+    let om = |t: f64| (450160.398036 + t * (-6962890.5431 + t * (7.4722 +
+        t * (0.007702 - t * 0.00005939)))) / ARCS;
+
+    // Only the terms of the power 0 at J2000
+    let (x, y) = series.cip(JulianDate::j2000());
+    let expected = -16617.0 + 1000.0 * om(0.0).sin() - 200.0 * om(0.0).cos();
+    assert_relative_eq!(x, expected * uas, epsilon = 1e-15);
+    let expected = -6951.0 + 300.0 * 1.753470314_f64.cos();
+    assert_relative_eq!(y, expected * uas, epsilon = 1e-15);
+
+    // One century later
+    let jd = JulianDate::new(2488070.0);
+    let (x, _) = series.cip(jd);
+    let polynomial = -16617.0 + 2004191898.0 - 429782.9 - 198618.34 + 7.578 +
+        5.9285;
+    let expected = polynomial + 1000.0 * om(1.0).sin() -
+        200.0 * om(1.0).cos() + 50.0 * (0.02438175_f64 + 0.00000538691).sin();
+    assert_relative_eq!(x, expected * uas, epsilon = 1e-12);
+
+    // The series replace the IAU 2000B pole below the pole offsets
+    let c2t = CelestialToTerrestrial::new(jd)
+        .with_cip_series(&series)
+        .with_pole_offsets(Angle::from(1e-9), Angle::from(-2e-9));
+    let (cx, cy) = c2t.cip();
+    let (x, y) = series.cip(jd);
+    assert_relative_eq!(cx - x, 1e-9, epsilon = 1e-15);
+    assert_relative_eq!(cy - y, -2e-9, epsilon = 1e-15);
+
+    assert!(CipSeries::from_tables("", "").unwrap().is_empty());
+    let terms = "1 1.0 0.0 0 0 0 0 1 0 0 0 0 0 0 0 0 0";
+    assert!(matches!(CipSeries::from_tables(terms, ""),
+                     Err(Error::ParseError(1))));
+    assert!(matches!(CipSeries::from_tables("j = 0\n1 1.0 0.0 0 0 1", ""),
+                     Err(Error::ParseError(2))));
+    assert!(matches!(CipSeries::from_tables("", "j = x"),
+                     Err(Error::ParseError(1))));
+    assert!(CipSeries::from_files("/nonexistent/tab5.2a.txt",
+                                  "/nonexistent/tab5.2b.txt").is_err());
+}
+
+#[test]
+fn rotating_frame_test() {
+    let omega = 7.292115e-5;
+    let rotation = |t: f64| Mat3D::r_z(omega * t);
+    let frame = RotatingFrame::from_angular_velocity(
+        rotation(1000.0), Vec3D::unit_z() * omega
+    );
+
+    // Derivative against the difference over a second
+    let difference = ((rotation(1001.0) - rotation(999.0)) / 2.0).unwrap();
+    for (v1, v2) in difference.iter().zip(frame.derivative().iter()) {
+        assert_relative_eq!(v1, v2, epsilon = 1e-12);
+    }
+    let w = frame.angular_velocity();
+    assert_relative_eq!((w - Vec3D::unit_z() * omega).norm(), 0.0,
+                        epsilon = 1e-18);
+
+    // A geostationary satellite is at rest in the rotating frame
+    let fixed = Vec3D::cartesian(42164.0, 0.0, 0.0);
+    let r = frame.matrix().t() * fixed;
+    let v = (Vec3D::unit_z() * omega).cross(r);
+    let (r1, v1) = frame.transform(r, v);
+    assert_relative_eq!((r1 - fixed).norm(), 0.0, epsilon = 1e-9);
+    assert_relative_eq!(v1.norm(), 0.0, epsilon = 1e-12);
+    assert_relative_eq!(
+        (frame.matrix() * v).norm(), omega * 42164.0, epsilon = 1e-12
+    );
+
+    let (r2, v2) = frame.inverse().transform(r1, v1);
+    assert_relative_eq!((r2 - r).norm(), 0.0, epsilon = 1e-9);
+    assert_relative_eq!((v2 - v).norm(), 0.0, epsilon = 1e-12);
+
+    // Composition follows the product rule
+    let tilt = Mat3D::r_x(0.4);
+    let composed = RotatingFrame::fixed(tilt) * frame * Mat3D::r_y(-0.2);
+    let difference = ((tilt * rotation(1001.0) * Mat3D::r_y(-0.2) -
+        tilt * rotation(999.0) * Mat3D::r_y(-0.2)) / 2.0).unwrap();
+    for (v1, v2) in difference.iter().zip(composed.derivative().iter()) {
+        assert_relative_eq!(v1, v2, epsilon = 1e-12);
+    }
+
+    // The ITRS rotates about the CIP
+    let c2t = CelestialToTerrestrial::new(JulianDate::from_mjd(53736.0));
+    let w = c2t.rotating_frame().angular_velocity();
+    assert_relative_eq!(w.norm(), 7.2921150e-5, epsilon = 1e-11);
+    let (x, y) = c2t.cip();
+    let pole = Vec3D::cartesian(x, y, (1.0 - x * x - y * y).sqrt());
+    assert_relative_eq!(w.cross(pole).norm(), 0.0, epsilon = 1e-15);
+}
+
+#[test]
+fn tagged_vectors_test() {
+    let ephemeris = AnalyticEphemeris::new();
+    let jd = JulianDate::from_calendar(2024, 4, 8.75);
+
+    // Barycentric Mars moved to the geocentre and to the heliocentre
+    let mars = Position::<Icrs, Barycentre>::new(
+        ephemeris.position(Body::Mars, jd).unwrap()
+    ).with_epoch(jd);
+    let earth = OriginShift::earth(&ephemeris, jd).unwrap();
+    let sun = OriginShift::sun(&ephemeris, jd).unwrap();
+    let geocentric: Position<Icrs, Geocentre> =
+        (mars + earth.inverse()).unwrap();
+    let heliocentric: Position<Icrs, Heliocentre> =
+        mars.shift(sun.inverse()).unwrap();
+    assert_eq!(geocentric.epoch(), Some(jd));
+
+    let expected = ephemeris.position(Body::Mars, jd).unwrap() -
+        ephemeris.position(Body::Earth, jd).unwrap();
+    assert_relative_eq!((geocentric.vector() - expected).norm(), 0.0,
+                        epsilon = common::EPS);
+
+    // The same point seen from two origins differs by the shift of origins
+    let sun_from_earth = sun.then(earth.inverse()).unwrap();
+    let back: Position<Icrs, Geocentre> =
+        (heliocentric + sun_from_earth).unwrap();
+    assert_relative_eq!((back - geocentric).unwrap().norm(), 0.0,
+                        epsilon = common::EPS);
+    let step = (geocentric - back).unwrap();
+    let moved = (back + step).unwrap();
+    assert_relative_eq!((moved - geocentric).unwrap().norm(), 0.0,
+                        epsilon = common::EPS);
+
+    // Rotations compose along matching frames
+    let ecliptic = Rotation::ecliptic(jd);
+    let rotated = (ecliptic * geocentric).unwrap();
+    assert_relative_eq!(rotated.norm(), geocentric.norm(),
+                        epsilon = common::EPS);
+    let identity = (ecliptic.inverse() * ecliptic).unwrap();
+    for (v1, v2) in identity.matrix().iter().zip(Mat3D::identity().iter()) {
+        assert_relative_eq!(v1, v2, epsilon = common::EPS);
+    }
+
+    // The values of different dates cannot be combined
+    let later = jd + 1.0;
+    let mars_later = Position::<Icrs, Barycentre>::new(
+        ephemeris.position(Body::Mars, later).unwrap()
+    ).with_epoch(later);
+    assert!(matches!(mars_later + earth.inverse(),
+                     Err(Error::TagMismatchError)));
+    assert!(matches!(Rotation::ecliptic(later) * geocentric,
+                     Err(Error::TagMismatchError)));
+    assert!(matches!(geocentric - geocentric.with_epoch(later),
+                     Err(Error::TagMismatchError)));
+    assert!(matches!(Rotation::ecliptic(later).inverse() * ecliptic,
+                     Err(Error::TagMismatchError)));
+    assert!((Position::<Icrs, Barycentre>::new(Vec3D::unit_x()) +
+             earth.inverse()).is_ok());
+
+    // A geocentric direction in the horizon of the observer
+    let observer = Observer::new(
+        Angle::from_ad(-116.8625), Angle::from_ad(33.356111), 1706.0
+    );
+    let c2t = CelestialToTerrestrial::new(jd);
+    let to_horizon = (Rotation::horizontal(&observer) *
+        Rotation::terrestrial(&c2t)).unwrap();
+    let zenith = OriginShift::observer(&observer);
+    let topocentric = (Rotation::terrestrial(&c2t) * geocentric).unwrap()
+        .shift(zenith.inverse()).unwrap();
+    let h = (Rotation::horizontal(&observer) * topocentric).unwrap().vector();
+    let direct = (to_horizon * geocentric).unwrap().vector();
+    let up = Rotation::horizontal(&observer).matrix() * zenith.vector();
+    assert_relative_eq!((h - direct + up).norm(), 0.0, epsilon = common::EPS);
+    let up: CartesianVec3D = up.into();
+    assert_relative_eq!(up.z() * AU, 6373.39, epsilon = 0.01);
+}
@@ -9,7 +9,11 @@ use std::f64::consts::{PI, FRAC_PI_2};
 use rand::Rng;
 
 use ephem::base::linalg;
+use ephem::base::angle::Angle;
+use ephem::base::linalg::{EulerSequence, Norm};
+use ephem::base::quaternion::Quaternion;
 use ephem::base::consts::PI2;
+use ephem::base::error::Error;
 
 
 fn new_random_vec3d<R: Rng + ?Sized>(rng: &mut R) -> linalg::Vec3D {
@@ -432,6 +436,19 @@ fn vec3d_operation_dot_test() {
     }
 }
 
+#[test]
+fn vec3d_separation_test() {
+    let x = linalg::Vec3D::unit_x();
+    let y = linalg::Vec3D::unit_y();
+    assert_relative_eq!(x.separation(y), std::f64::consts::FRAC_PI_2);
+    assert_relative_eq!(x.separation(-x), std::f64::consts::PI);
+    assert_relative_eq!(x.separation(x * 3.0), 0.0);
+
+    // Accurate for small angles
+    let v = linalg::Vec3D::cartesian(1.0, 1e-10, 0.0);
+    assert_relative_eq!(x.separation(v), 1e-10, max_relative = 1e-12);
+}
+
 #[test]
 fn vec3d_operation_cross_test() {
     let v = linalg::Vec3D::unit_x().cross(linalg::Vec3D::unit_y());
@@ -608,3 +625,405 @@ fn mat3d_rotation_test() {
         }
     }
 }
+
+fn assert_mat3d_eq(m1: &linalg::Mat3D, m2: &linalg::Mat3D, eps: f64) {
+    for (v1, v2) in m1.iter().zip(m2.iter()) {
+        assert_relative_eq!(v1, v2, epsilon = eps);
+    }
+}
+
+fn new_random_quaternion<R: Rng + ?Sized>(rng: &mut R) -> Quaternion {
+    Quaternion::new(
+        2.0 * rng.gen::<f64>() - 1.0,
+        2.0 * rng.gen::<f64>() - 1.0,
+        2.0 * rng.gen::<f64>() - 1.0,
+        2.0 * rng.gen::<f64>() - 1.0
+    ).normalize().unwrap()
+}
+
+#[test]
+fn quaternion_rotation_test() {
+    let axis = linalg::Vec3D::unit_z();
+    let q = Quaternion::from_axis_angle(axis, Angle::from(FRAC_PI_2))
+        .unwrap();
+    let v: linalg::CartesianVec3D = (q * linalg::Vec3D::unit_x()).into();
+    assert_relative_eq!(v.x(), 0.0, epsilon = common::EPS);
+    assert_relative_eq!(v.y(), -1.0, epsilon = common::EPS);
+
+    // The quaternions rotate the frame like the matrices of the crate
+    let m: linalg::Mat3D = q.into();
+    assert_mat3d_eq(&m, &linalg::Mat3D::r_z(FRAC_PI_2), common::EPS);
+
+    let (axis, angle) = Quaternion::from_axis_angle(
+        linalg::Vec3D::cartesian(1.0, -2.0, 2.0), Angle::from(-2.5)
+    ).unwrap().axis_angle();
+    let axis: linalg::CartesianVec3D = axis.into();
+    assert_relative_eq!(radians(angle), 2.5, epsilon = common::EPS);
+    assert_relative_eq!(axis.x(), -1.0 / 3.0, epsilon = common::EPS);
+    assert_relative_eq!(axis.z(), -2.0 / 3.0, epsilon = common::EPS);
+    assert!(
+        Quaternion::from_axis_angle(linalg::Vec3D::zero(), Angle::from(1.0))
+            .is_err()
+    );
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..200 {
+        let q1 = new_random_quaternion(&mut rng);
+        let q2 = new_random_quaternion(&mut rng);
+        let v = new_random_vec3d(&mut rng);
+        let m1: linalg::Mat3D = q1.into();
+        let m2: linalg::Mat3D = q2.into();
+
+        assert_relative_eq!((q1 * v - m1 * v).norm(), 0.0, epsilon = 1e-10);
+        let q12: linalg::Mat3D = (q1 * q2).into();
+        assert_mat3d_eq(&q12, &(m1 * m2), 1e-12);
+        assert_relative_eq!((q1.inv().unwrap() * (q1 * v) - v).norm(), 0.0,
+                            epsilon = 1e-10);
+
+        // Shepperd's method recovers the quaternion up to the sign
+        let back = Quaternion::from(m1);
+        let sign = if q1.w() < 0.0 { -1.0 } else { 1.0 };
+        assert_relative_eq!((back - q1 * sign).norm(), 0.0, epsilon = 1e-15);
+    }
+
+    // Rotations by pi about the axes use the other branches
+    for m in [linalg::Mat3D::r_x(PI), linalg::Mat3D::r_y(PI),
+              linalg::Mat3D::r_z(PI)] {
+        let back: linalg::Mat3D = Quaternion::from(m).into();
+        assert_mat3d_eq(&back, &m, 1e-15);
+    }
+}
+
+#[test]
+fn quaternion_slerp_test() {
+    let axis = linalg::Vec3D::cartesian(0.3, 0.4, -1.2);
+    let rotation = |angle: f64| {
+        Quaternion::from_axis_angle(axis, Angle::from(angle)).unwrap()
+    };
+    let q1 = rotation(0.2);
+    let q2 = rotation(1.4);
+
+    for &t in [0.0, 0.25, 0.5, 1.0].iter() {
+        let q = q1.slerp(&q2, t);
+        let expected = rotation(0.2 + 1.2 * t);
+        assert_relative_eq!(q.norm(), 1.0, epsilon = common::EPS);
+        assert_relative_eq!((q - expected).norm(), 0.0, epsilon = 1e-12);
+    }
+
+    // The shortest arc for the opposite representation of the target
+    let q = q1.slerp(&-q2, 0.5);
+    let expected = rotation(0.8);
+    assert_relative_eq!((q - expected).norm(), 0.0, epsilon = 1e-12);
+
+    let near = rotation(0.2 + 1e-4);
+    let q = q1.slerp(&near, 0.5);
+    assert_relative_eq!(q.norm(), 1.0, epsilon = common::EPS);
+    let (_, angle) = q.axis_angle();
+    assert_relative_eq!(radians(angle), 0.2 + 5e-5, epsilon = 1e-10);
+}
+
+fn radians(angle: Angle) -> f64 {
+    angle.into()
+}
+
+#[test]
+fn mat3d_axis_angle_test() {
+    let angle = Angle::from(0.7);
+    let axes = [
+        (linalg::Vec3D::unit_x(), linalg::Mat3D::r_x(0.7)),
+        (linalg::Vec3D::unit_y(), linalg::Mat3D::r_y(0.7)),
+        (linalg::Vec3D::unit_z() * 3.0, linalg::Mat3D::r_z(0.7))
+    ];
+    for (axis, expected) in axes.iter() {
+        let m = linalg::Mat3D::from_axis_angle(*axis, angle).unwrap();
+        assert_mat3d_eq(&m, expected, common::EPS);
+    }
+    assert!(linalg::Mat3D::from_axis_angle(linalg::Vec3D::zero(), angle)
+        .is_err());
+
+    let (axis, angle) = linalg::Mat3D::identity().axis_angle();
+    assert_relative_eq!(axis.norm(), 1.0);
+    assert_eq!(radians(angle), 0.0);
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..200 {
+        let axis = new_random_vec3d(&mut rng);
+        let unit = (axis / axis.norm()).unwrap();
+        let value = PI * rng.gen::<f64>();
+        let m = linalg::Mat3D::from_axis_angle(axis, Angle::from(value))
+            .unwrap();
+        assert_relative_eq!(m.det(), 1.0, epsilon = 1e-12);
+
+        let (n, angle) = m.axis_angle();
+        assert_relative_eq!(radians(angle), value, epsilon = 1e-9);
+        assert_relative_eq!((n - unit).norm(), 0.0, epsilon = 1e-7);
+
+        // The quaternion of the axis and the angle gives the same matrix
+        let q = Quaternion::from_axis_angle(axis, Angle::from(value))
+            .unwrap();
+        assert_mat3d_eq(&linalg::Mat3D::from(q), &m, 1e-12);
+        let (n, angle) = q.axis_angle();
+        assert_relative_eq!(radians(angle), value, epsilon = 1e-12);
+        assert_relative_eq!((n - unit).norm(), 0.0, epsilon = 1e-7);
+    }
+
+    // The axis of a half turn is defined up to the sign
+    let axis = linalg::Vec3D::cartesian(1.0, 2.0, -2.0);
+    let m = linalg::Mat3D::from_axis_angle(axis, Angle::from(PI)).unwrap();
+    let (n, angle) = m.axis_angle();
+    assert_relative_eq!(radians(angle), PI, epsilon = common::EPS);
+    assert_relative_eq!(n.cross(axis).norm(), 0.0, epsilon = 1e-12);
+}
+
+#[test]
+fn mat3d_euler_angles_test() {
+    // The IAU 1976 precession angles in the sequence ZYZ
+    let (zeta, z, theta) = (0.0110, 0.0111, 0.0097);
+    let m = linalg::Mat3D::r_z(-z) * linalg::Mat3D::r_y(theta) *
+        linalg::Mat3D::r_z(-zeta);
+    let euler = linalg::Mat3D::from_euler(
+        EulerSequence::ZYZ, Angle::from(-zeta), Angle::from(theta),
+        Angle::from(-z)
+    );
+    assert_mat3d_eq(&m, &euler, common::EPS);
+
+    let mut rng = rand::thread_rng();
+    for sequence in EulerSequence::ALL {
+        for _ in 0..50 {
+            let a1 = PI2 * rng.gen::<f64>() - PI;
+            let a2 = if sequence.is_proper() {
+                PI * rng.gen::<f64>()
+            } else {
+                PI * rng.gen::<f64>() - FRAC_PI_2
+            };
+            let a3 = PI2 * rng.gen::<f64>() - PI;
+
+            let m = linalg::Mat3D::from_euler(
+                sequence, Angle::from(a1), Angle::from(a2), Angle::from(a3)
+            );
+            let (b1, b2, b3) = m.euler_angles(sequence);
+            assert_relative_eq!(radians(b1), a1, epsilon = 1e-8);
+            assert_relative_eq!(radians(b2), a2, epsilon = 1e-8);
+            assert_relative_eq!(radians(b3), a3, epsilon = 1e-8);
+        }
+
+        // Gimbal lock: only the sum or the difference of the outer angles
+        // is defined
+        let locks = if sequence.is_proper() {
+            [0.0, PI]
+        } else {
+            [FRAC_PI_2, -FRAC_PI_2]
+        };
+        for a2 in locks {
+            let m = linalg::Mat3D::from_euler(
+                sequence, Angle::from(0.4), Angle::from(a2), Angle::from(-1.1)
+            );
+            let (b1, b2, b3) = m.euler_angles(sequence);
+            assert_eq!(radians(b3), 0.0);
+            let back = linalg::Mat3D::from_euler(sequence, b1, b2, b3);
+            assert_mat3d_eq(&back, &m, 1e-12);
+        }
+    }
+}
+
+fn new_random_mat3d<R: Rng + ?Sized>(rng: &mut R) -> linalg::Mat3D {
+    linalg::Mat3D::from_rows(
+        new_random_vec3d(rng) * 0.01,
+        new_random_vec3d(rng) * 0.01,
+        new_random_vec3d(rng) * 0.01
+    )
+}
+
+fn diagonal(values: [f64; 3]) -> linalg::Mat3D {
+    linalg::Mat3D::from_rows(
+        linalg::Vec3D::cartesian(values[0], 0.0, 0.0),
+        linalg::Vec3D::cartesian(0.0, values[1], 0.0),
+        linalg::Vec3D::cartesian(0.0, 0.0, values[2])
+    )
+}
+
+fn assert_orthogonal(m: &linalg::Mat3D) {
+    assert_mat3d_eq(&(m.t() * *m), &linalg::Mat3D::identity(), 1e-14);
+}
+
+#[test]
+fn mat3d_symmetric_eigen_test() {
+    let m = linalg::Mat3D::from_rows(
+        linalg::Vec3D::cartesian(2.0, 1.0, 0.0),
+        linalg::Vec3D::cartesian(1.0, 2.0, 0.0),
+        linalg::Vec3D::cartesian(0.0, 0.0, 3.0)
+    );
+    let (values, vectors) = m.symmetric_eigen().unwrap();
+    assert_relative_eq!(values[0], 3.0, epsilon = 1e-14);
+    assert_relative_eq!(values[1], 3.0, epsilon = 1e-14);
+    assert_relative_eq!(values[2], 1.0, epsilon = 1e-14);
+    assert_mat3d_eq(&(vectors * diagonal(values) * vectors.t()), &m, 1e-14);
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..200 {
+        let a = new_random_mat3d(&mut rng);
+        let m = a + a.t();
+        let (values, vectors) = m.symmetric_eigen().unwrap();
+
+        assert!(values[0] >= values[1] && values[1] >= values[2]);
+        assert_orthogonal(&vectors);
+        assert_relative_eq!(vectors.det(), 1.0, epsilon = 1e-14);
+        assert_mat3d_eq(&(vectors * diagonal(values) * vectors.t()), &m,
+                        1e-14);
+    }
+}
+
+#[test]
+fn mat3d_svd_test() {
+    let mut rng = rand::thread_rng();
+    for _ in 0..200 {
+        let m = new_random_mat3d(&mut rng);
+        let (u, s, v) = m.svd().unwrap();
+
+        assert!(s[0] >= s[1] && s[1] >= s[2] && s[2] >= 0.0);
+        assert_orthogonal(&u);
+        assert_orthogonal(&v);
+        assert_mat3d_eq(&(u * diagonal(s) * v.t()), &m, 1e-14);
+
+        let (r, p) = m.polar().unwrap();
+        assert_orthogonal(&r);
+        assert_mat3d_eq(&p, &p.t(), 1e-14);
+        assert_mat3d_eq(&(r * p), &m, 1e-14);
+        let (values, _) = p.symmetric_eigen().unwrap();
+        assert!(values[2] > -1e-14);
+    }
+
+    // Rank one and null matrices
+    let a = linalg::Vec3D::cartesian(1.0, 2.0, 2.0);
+    let b = linalg::Vec3D::cartesian(0.0, 3.0, 4.0);
+    let m = linalg::Mat3D::from_columns(a * 0.0, a * 3.0, a * 4.0);
+    let (u, s, v) = m.svd().unwrap();
+    assert_relative_eq!(s[0], a.norm() * b.norm(), epsilon = 1e-13);
+    assert_eq!(s[1], 0.0);
+    assert_eq!(s[2], 0.0);
+    assert_orthogonal(&u);
+    assert_mat3d_eq(&(u * diagonal(s) * v.t()), &m, 1e-13);
+
+    let (u, s, v) = linalg::Mat3D::zeros().svd().unwrap();
+    assert_eq!(s, [0.0; 3]);
+    assert_orthogonal(&u);
+    assert_orthogonal(&v);
+}
+
+#[test]
+fn mat3d_orthonormalize_test() {
+    let mut rng = rand::thread_rng();
+    let rotation = linalg::Mat3D::r_z(0.3) * linalg::Mat3D::r_x(-1.2) *
+        linalg::Mat3D::r_y(2.1);
+
+    let noisy = rotation + new_random_mat3d(&mut rng) * 1e-6;
+    let cleaned = noisy.orthonormalize().unwrap();
+    assert_orthogonal(&cleaned);
+    assert_relative_eq!(cleaned.det(), 1.0, epsilon = 1e-14);
+    assert_mat3d_eq(&cleaned, &rotation, 1e-5);
+
+    // Fit of the rotation between two sets of directions
+    let from = (0..20)
+        .map(|_| {
+            let v = new_random_vec3d(&mut rng);
+            (v / v.norm()).unwrap()
+        })
+        .collect::<Vec<_>>();
+    let to = from.iter()
+        .map(|&v| rotation * v + new_random_vec3d(&mut rng) * 1e-13)
+        .collect::<Vec<_>>();
+    let fitted = linalg::Mat3D::kabsch(&from, &to, None).unwrap();
+    assert_mat3d_eq(&fitted, &rotation, 1e-10);
+    let weights = vec![2.0; 20];
+    let fitted = linalg::Mat3D::kabsch(&from, &to, Some(&weights)).unwrap();
+    assert_relative_eq!(fitted.det(), 1.0, epsilon = 1e-14);
+    assert_mat3d_eq(&fitted, &rotation, 1e-10);
+
+    // A reflection is not fitted by a rotation
+    let mirrored = from.iter()
+        .map(|&v| diagonal([1.0, 1.0, -1.0]) * v)
+        .collect::<Vec<_>>();
+    let fitted = linalg::Mat3D::kabsch(&from, &mirrored, None).unwrap();
+    assert_relative_eq!(fitted.det(), 1.0, epsilon = 1e-14);
+
+    assert!(matches!(linalg::Mat3D::kabsch(&from, &to[1..], None),
+                     Err(Error::DimensionMismatchError)));
+    assert!(matches!(linalg::Mat3D::kabsch(&[], &[], None),
+                     Err(Error::DimensionMismatchError)));
+    assert!(matches!(linalg::Mat3D::kabsch(&from, &to, Some(&weights[1..])),
+                     Err(Error::DimensionMismatchError)));
+    let mut negative = weights.clone();
+    negative[3] = -1.0;
+    assert!(matches!(linalg::Mat3D::kabsch(&from, &to, Some(&negative)),
+                     Err(Error::InvalidArgumentError(_))));
+    negative[3] = 0.0;
+    assert!(linalg::Mat3D::kabsch(&from, &to, Some(&negative)).is_ok());
+}
+
+#[test]
+fn mat3d_solve_test() {
+    // The first pivot is zero without the row interchanges
+    let a = linalg::Mat3D::from_rows(
+        linalg::Vec3D::cartesian(0.0, 2.0, 1.0),
+        linalg::Vec3D::cartesian(1.0, 1.0, 1.0),
+        linalg::Vec3D::cartesian(2.0, 1.0, 0.0)
+    );
+    let x = linalg::Vec3D::cartesian(1.5, -2.0, 0.25);
+
+    let (solution, condition) = a.solve(a * x).unwrap();
+    assert_relative_eq!((solution - x).norm(), 0.0, epsilon = 1e-14);
+    assert_relative_eq!(condition, a.condition());
+    assert!(condition > 1.0 && condition < 100.0);
+    assert_relative_eq!(((a * x / a).unwrap() - x).norm(), 0.0,
+                        epsilon = 1e-14);
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..100 {
+        let a = new_random_mat3d(&mut rng);
+        let x = new_random_vec3d(&mut rng);
+        match a.solve(a * x) {
+            Ok((solution, condition)) => {
+                assert!(condition <= linalg::MAX_CONDITION);
+                assert_relative_eq!((solution - x).norm(), 0.0,
+                                    epsilon = 1e-14 * condition * x.norm());
+            },
+            Err(Error::IllConditionedMatrixError(c)) => {
+                assert!(c > linalg::MAX_CONDITION);
+            },
+            Err(err) => panic!("unexpected error: {}", err)
+        }
+
+        // The diagonally dominant matrices are well-conditioned
+        let a = a + linalg::Mat3D::identity() * 1000.0;
+        let (solution, condition) = a.solve(a * x).unwrap();
+        assert!(condition < 10.0);
+        assert_relative_eq!((solution - x).norm(), 0.0,
+                            epsilon = 1e-14 * x.norm());
+    }
+
+    // Nearly singular matrix
+    let a = linalg::Mat3D::from_rows(
+        linalg::Vec3D::cartesian(1.0, 2.0, 3.0),
+        linalg::Vec3D::cartesian(4.0, 5.0, 6.0),
+        linalg::Vec3D::cartesian(7.0, 8.0, 9.0 + 1e-10)
+    );
+    assert!(a.condition() > linalg::MAX_CONDITION);
+    assert!(matches!(a.solve(x),
+                     Err(Error::IllConditionedMatrixError(c)) if c > 1e12));
+    assert!(matches!(x / a, Err(Error::IllConditionedMatrixError(_))));
+    assert!(matches!(a.inv(), Err(Error::IllConditionedMatrixError(_))));
+    assert!(matches!(1.0 / a, Err(Error::IllConditionedMatrixError(_))));
+
+    let (solution, condition) = a.solve_with_threshold(a * x, 1e14).unwrap();
+    assert_relative_eq!(condition, a.condition());
+    assert_relative_eq!((a * solution - a * x).norm(), 0.0, epsilon = 1e-12);
+    let inverse = a.inv_with_threshold(1e14).unwrap();
+    assert_mat3d_eq(&(a * inverse), &linalg::Mat3D::identity(), 1e-4);
+    assert!(a.solve_with_threshold(x, 1e11).is_err());
+
+    // Singular matrix
+    let a = diagonal([1.0, 0.0, 2.0]);
+    assert_eq!(a.condition(), f64::INFINITY);
+    assert!(matches!(a.solve(x), Err(Error::SingularMatrixError)));
+    assert!(matches!(a.inv(), Err(Error::SingularMatrixError)));
+}
@@ -0,0 +1,227 @@
+#![allow(dead_code)]
+
+mod common;
+
+#[macro_use]
+extern crate approx;
+
+use std::convert::TryFrom;
+
+use rand::Rng;
+
+use ephem::base::error::Error;
+use ephem::base::linalg::{Mat3D, Norm, Vec3D};
+use ephem::base::matrix::*;
+
+
+fn assert_matrix_eq(m1: &Matrix, m2: &Matrix, eps: f64) {
+    assert_eq!((m1.rows(), m1.cols()), (m2.rows(), m2.cols()));
+    for i in 0..m1.rows() {
+        for j in 0..m1.cols() {
+            assert_relative_eq!(m1[(i, j)], m2[(i, j)], epsilon = eps);
+        }
+    }
+}
+
+fn new_random_matrix<R: Rng + ?Sized>(rng: &mut R, rows: usize,
+                                      cols: usize) -> Matrix {
+    let data = (0..rows * cols).map(|_| 2.0 * rng.gen::<f64>() - 1.0);
+    Matrix::from_vec(rows, cols, data.collect()).unwrap()
+}
+
+#[test]
+fn matrix_operations_test() {
+    let a = Matrix::from_rows(&[
+        vec![1.0, 2.0, 3.0],
+        vec![4.0, 5.0, 6.0]
+    ]).unwrap();
+    assert_eq!((a.rows(), a.cols()), (2, 3));
+    assert_eq!(a[(1, 2)], 6.0);
+    assert_eq!(a.row(1), &[4.0, 5.0, 6.0]);
+    assert_eq!(a.column(1), vec![2.0, 5.0]);
+    assert_eq!(a.t().row(2), &[3.0, 6.0]);
+
+    let p = (&a * &a.t()).unwrap();
+    assert_matrix_eq(&p, &Matrix::from_rows(&[
+        vec![14.0, 32.0],
+        vec![32.0, 77.0]
+    ]).unwrap(), 0.0);
+    assert_eq!(a.mul_vec(&[1.0, 0.0, -1.0]).unwrap(), vec![-2.0, -2.0]);
+    assert_matrix_eq(&(&a - &a).unwrap(), &Matrix::zeros(2, 3), 0.0);
+    assert_matrix_eq(&(&a + &(-a.clone())).unwrap(), &Matrix::zeros(2, 3),
+                     0.0);
+
+    assert!(matches!(&a * &a, Err(Error::DimensionMismatchError)));
+    assert!((&a + &a.t()).is_err());
+    assert!(a.mul_vec(&[1.0]).is_err());
+    assert!(Matrix::from_rows(&[vec![1.0], vec![1.0, 2.0]]).is_err());
+    assert!(Matrix::from_vec(2, 2, vec![1.0; 3]).is_err());
+
+    // Interoperability with Mat3D and Vec3D
+    let m = Mat3D::r_x(0.3) * Mat3D::r_z(-1.1);
+    let v = Vec3D::cartesian(1.0, -2.0, 0.5);
+    let mv = (&Matrix::from(m) * &Matrix::from(v)).unwrap();
+    let back = Vec3D::try_from(&mv).unwrap();
+    assert_relative_eq!((back - m * v).norm(), 0.0, epsilon = common::EPS);
+    let m3 = Mat3D::try_from(&Matrix::from(m)).unwrap();
+    assert_eq!(m3, m);
+    assert!(Mat3D::try_from(&a).is_err());
+    assert!(Vec3D::try_from(&a).is_err());
+}
+
+#[test]
+fn lu_test() {
+    let a = Matrix::from_rows(&[
+        vec![0.0, 2.0, 1.0, -1.0],
+        vec![1.0, 1.0, 0.0, 2.0],
+        vec![2.0, -1.0, 3.0, 0.0],
+        vec![1.0, 0.0, -2.0, 1.0]
+    ]).unwrap();
+    let x = [1.0, -2.0, 0.5, 3.0];
+    let b = a.mul_vec(&x).unwrap();
+    let solution = a.solve(&b).unwrap();
+    for (v1, v2) in solution.iter().zip(x.iter()) {
+        assert_relative_eq!(v1, v2, epsilon = common::EPS);
+    }
+
+    let lu = a.lu().unwrap();
+    assert_relative_eq!(lu.det(), a.det().unwrap(), epsilon = common::EPS);
+    let m = Mat3D::r_y(0.4) * 2.0;
+    assert_relative_eq!(Matrix::from(m).det().unwrap(), m.det(),
+                        epsilon = common::EPS);
+    assert_matrix_eq(&(&a * &a.inv().unwrap()).unwrap(), &Matrix::identity(4),
+                     common::EPS);
+
+    let singular = Matrix::from_rows(&[
+        vec![1.0, 2.0],
+        vec![2.0, 4.0]
+    ]).unwrap();
+    assert!(matches!(singular.lu(), Err(Error::SingularMatrixError)));
+    assert_eq!(singular.det().unwrap(), 0.0);
+    assert!(Matrix::zeros(2, 3).lu().is_err());
+    assert!(lu.solve(&[1.0]).is_err());
+}
+
+#[test]
+fn qr_test() {
+    let mut rng = rand::thread_rng();
+    let a = new_random_matrix(&mut rng, 7, 4);
+    let qr = a.qr().unwrap();
+    let q = qr.q();
+    assert!(qr.is_full_rank());
+    assert_matrix_eq(&(&q * &qr.r()).unwrap(), &a, common::EPS);
+    assert_matrix_eq(&(&q.t() * &q).unwrap(), &Matrix::identity(4),
+                     common::EPS);
+
+    // The least squares solution of a consistent system is exact
+    let x = [0.5, -1.5, 2.0, 0.25];
+    let solution = qr.solve(&a.mul_vec(&x).unwrap()).unwrap();
+    for (v1, v2) in solution.iter().zip(x.iter()) {
+        assert_relative_eq!(v1, v2, epsilon = common::EPS);
+    }
+
+    let dependent = Matrix::from_rows(&[
+        vec![1.0, 2.0],
+        vec![2.0, 4.0],
+        vec![3.0, 6.0]
+    ]).unwrap();
+    let qr = dependent.qr().unwrap();
+    assert!(!qr.is_full_rank());
+    assert!(matches!(qr.solve(&[1.0, 2.0, 3.0]),
+                     Err(Error::SingularMatrixError)));
+    assert!(Matrix::zeros(2, 3).qr().is_err());
+}
+
+#[test]
+fn cholesky_test() {
+    let mut rng = rand::thread_rng();
+    let b = new_random_matrix(&mut rng, 5, 5);
+    let a = (&(&b * &b.t()).unwrap() + &Matrix::identity(5)).unwrap();
+
+    let cholesky = a.cholesky().unwrap();
+    let l = cholesky.l();
+    assert_eq!(l[(0, 4)], 0.0);
+    assert_matrix_eq(&(l * &l.t()).unwrap(), &a, common::EPS);
+
+    let x = [1.0, 2.0, 3.0, 4.0, 5.0];
+    let solution = cholesky.solve(&a.mul_vec(&x).unwrap()).unwrap();
+    for (v1, v2) in solution.iter().zip(x.iter()) {
+        assert_relative_eq!(v1, v2, epsilon = common::EPS);
+    }
+
+    let indefinite = Matrix::diagonal(&[1.0, -1.0]);
+    assert!(matches!(indefinite.cholesky(),
+                     Err(Error::NotPositiveDefiniteError)));
+}
+
+#[test]
+fn least_squares_test() {
+    // Straight line y = a + b x through weighted points
+    let xs = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+    let ys = [1.1, 2.9, 5.2, 6.8, 9.1, 11.0];
+    let weights = [1.0, 4.0, 1.0, 2.0, 1.0, 0.5];
+    let a = Matrix::from_rows(
+        &xs.iter().map(|&x| vec![1.0, x]).collect::<Vec<_>>()
+    ).unwrap();
+
+    let fit = LeastSquares::new(&a, &ys, Some(&weights)).unwrap();
+
+    // Normal equations
+    let s = |f: &dyn Fn(usize) -> f64| {
+        (0..xs.len()).map(|i| weights[i] * f(i)).sum::<f64>()
+    };
+    let (sw, sx, sxx) = (s(&|_| 1.0), s(&|i| xs[i]), s(&|i| xs[i] * xs[i]));
+    let (sy, sxy) = (s(&|i| ys[i]), s(&|i| xs[i] * ys[i]));
+    let det = sw * sxx - sx * sx;
+    let slope = (sw * sxy - sx * sy) / det;
+    let intercept = (sxx * sy - sx * sxy) / det;
+
+    assert_relative_eq!(fit.solution()[0], intercept, epsilon = common::EPS);
+    assert_relative_eq!(fit.solution()[1], slope, epsilon = common::EPS);
+    let covariance = fit.covariance();
+    assert_relative_eq!(covariance[(0, 0)], sxx / det, epsilon = common::EPS);
+    assert_relative_eq!(covariance[(0, 1)], -sx / det, epsilon = common::EPS);
+    assert_relative_eq!(covariance[(1, 0)], -sx / det, epsilon = common::EPS);
+    assert_relative_eq!(fit.sigma()[1], (sw / det).sqrt(),
+                        epsilon = common::EPS);
+
+    let chi2 = (0..xs.len())
+        .map(|i| weights[i] * (ys[i] - intercept - slope * xs[i]).powi(2))
+        .sum::<f64>();
+    assert_relative_eq!(fit.chi2(), chi2, epsilon = common::EPS);
+    assert_relative_eq!(fit.residuals()[2], ys[2] - intercept - 2.0 * slope,
+                        epsilon = common::EPS);
+    assert_relative_eq!(fit.unit_weight_variance().unwrap(), chi2 / 4.0,
+                        epsilon = common::EPS);
+
+    // Unit weights by default, no degrees of freedom for an exact fit
+    let fit = LeastSquares::new(&a, &ys, None).unwrap();
+    assert!(fit.chi2() > 0.0);
+    let square = Matrix::identity(2);
+    let exact = LeastSquares::new(&square, &[1.0, 2.0], None).unwrap();
+    assert_eq!(exact.unit_weight_variance(), None);
+
+    assert!(LeastSquares::new(&a, &ys[1..], None).is_err());
+    assert!(LeastSquares::new(&a, &ys, Some(&weights[1..])).is_err());
+    assert!(matches!(
+        LeastSquares::new(&a, &ys, Some(&[1.0, 1.0, -1.0, 1.0, 1.0, 1.0])),
+        Err(Error::InvalidArgumentError(_))
+    ));
+
+    // A zero weight drops the observation
+    let dropped = LeastSquares::new(
+        &Matrix::from_rows(&[vec![1.0, 0.0], vec![1.0, 1.0], vec![1.0, 3.0],
+                             vec![1.0, 4.0], vec![1.0, 5.0]]).unwrap(),
+        &[1.1, 2.9, 6.8, 9.1, 11.0], Some(&[1.0, 4.0, 2.0, 1.0, 0.5])
+    ).unwrap();
+    let zero = LeastSquares::new(
+        &a, &ys, Some(&[1.0, 4.0, 0.0, 2.0, 1.0, 0.5])
+    ).unwrap();
+    for (v1, v2) in zero.solution().iter().zip(dropped.solution()) {
+        assert_relative_eq!(v1, v2, epsilon = common::EPS);
+    }
+    assert_relative_eq!(zero.chi2(), dropped.chi2(), epsilon = common::EPS);
+    assert_relative_eq!(zero.unit_weight_variance().unwrap(),
+                        dropped.unit_weight_variance().unwrap(),
+                        epsilon = common::EPS);
+}
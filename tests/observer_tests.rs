@@ -0,0 +1,69 @@
+#![allow(dead_code)]
+
+mod common;
+
+#[macro_use]
+extern crate approx;
+
+use ephem::base::angle::Angle;
+use ephem::base::consts::DEG;
+use ephem::base::linalg::{CartesianVec3D, Vec3D};
+use ephem::observer::*;
+
+
+fn degrees(angle: Angle) -> f64 {
+    let radians: f64 = angle.into();
+    radians * DEG
+}
+
+#[test]
+fn geocentric_test() {
+    let observer = Observer::new(Angle::from_ad(0.0), Angle::from_ad(45.0),
+                                 0.0);
+    let r: CartesianVec3D = observer.geocentric().into();
+    assert_relative_eq!(r.x(), 4517.590879, epsilon = 1e-6);
+    assert_relative_eq!(r.y(), 0.0, epsilon = 1e-9);
+    assert_relative_eq!(r.z(), 4487.348409, epsilon = 1e-6);
+
+    // The ellipsoids differ by less than a millimetre
+    let grs80 = observer.with_ellipsoid(Ellipsoid::GRS80);
+    assert_eq!(grs80.ellipsoid(), Ellipsoid::GRS80);
+    let s: CartesianVec3D = grs80.geocentric().into();
+    assert!((s.z() - r.z()).abs() < 1e-6);
+    assert!((s.z() - r.z()).abs() > 0.0);
+}
+
+#[test]
+fn geodetic_test() {
+    let cases = [
+        (-77.0, 38.9, 100.0),
+        (139.7, -35.7, -50.0),
+        (0.0, 0.0, 0.0),
+        (10.0, 90.0, 2000.0),
+        (-170.0, -89.9, 8848.0),
+        (45.0, 60.0, 400_000.0)
+    ];
+
+    for ellipsoid in [Ellipsoid::WGS84, Ellipsoid::GRS80] {
+        for &(longitude, latitude, height) in cases.iter() {
+            let observer = Observer::new(
+                Angle::from_ad(longitude), Angle::from_ad(latitude), height
+            ).with_ellipsoid(ellipsoid);
+            let back = Observer::from_geocentric(
+                observer.geocentric(), ellipsoid
+            ).unwrap();
+
+            assert_eq!(back.ellipsoid(), ellipsoid);
+            if latitude.abs() < 90.0 {
+                assert_relative_eq!(degrees(back.longitude()), longitude,
+                                    epsilon = 1e-10);
+            }
+            assert_relative_eq!(degrees(back.latitude()), latitude,
+                                epsilon = 1e-10);
+            assert_relative_eq!(back.height(), height, epsilon = 1e-6);
+        }
+    }
+
+    assert!(Observer::from_geocentric(Vec3D::zero(), Ellipsoid::WGS84)
+        .is_err());
+}
@@ -0,0 +1,166 @@
+#![allow(dead_code)]
+
+mod common;
+
+#[macro_use]
+extern crate approx;
+
+use chrono::{TimeZone, Utc};
+
+use ephem::time::*;
+
+
+#[test]
+fn julian_date_calendar_test() {
+    let jd = JulianDate::from_calendar(1957, 10, 4.81);
+    assert_relative_eq!(jd.jd(), 2436116.31, epsilon = common::EPS);
+
+    let jd = JulianDate::from_calendar(333, 1, 27.5);
+    assert_relative_eq!(jd.jd(), 1842713.0, epsilon = common::EPS);
+
+    let jd = JulianDate::from_calendar(2000, 1, 1.5);
+    assert_relative_eq!(jd.jd(), JulianDate::j2000().jd());
+    assert_relative_eq!(jd.centuries(), 0.0);
+
+    let jd = JulianDate::from_time(1987, 4, 10, 19, 21, 0.0);
+    assert_relative_eq!(jd.jd(), 2446896.30625, epsilon = common::EPS);
+    assert_relative_eq!(jd.mjd(), 46895.80625, epsilon = common::EPS);
+
+    let samples = [
+        (2436116.31, 1957, 10, 4.81),
+        (1842713.0, 333, 1, 27.5),
+        (1507900.13, -584, 5, 28.63),
+        (2299160.5, 1582, 10, 15.0),
+        (2299159.5, 1582, 10, 4.0)
+    ];
+    for &(value, year, month, day) in samples.iter() {
+        let (y, m, d) = JulianDate::new(value).calendar();
+        assert_eq!(y, year);
+        assert_eq!(m, month);
+        assert_relative_eq!(d, day, epsilon = common::EPS);
+    }
+}
+
+#[test]
+fn julian_date_datetime_test() {
+    let dt = Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap();
+    let jd = JulianDate::from(dt);
+    assert_relative_eq!(jd.jd(), 2451545.0);
+
+    let back = jd.to_datetime().unwrap();
+    assert_eq!(back, dt);
+
+    let jd = JulianDate::new(2436116.31);
+    let dt = jd.to_datetime().unwrap();
+    assert_relative_eq!(
+        JulianDate::from(dt).jd(), jd.jd(), epsilon = common::EPS
+    );
+}
+
+#[test]
+fn julian_date_arithmetic_test() {
+    let jd = JulianDate::j2000();
+
+    assert_relative_eq!((jd + 1.5).jd(), 2451546.5);
+    assert_relative_eq!((jd - 1.5).jd(), 2451543.5);
+    assert_relative_eq!((jd + 10.0) - jd, 10.0);
+
+    let mut t = jd;
+    t += 2.0;
+    t -= 0.5;
+    assert_relative_eq!(t.jd(), 2451546.5);
+    assert!(jd < t);
+}
+
+#[test]
+fn delta_t_test() {
+    let samples = [
+        (1900, -2.79),
+        (1950, 29.07),
+        (1975, 45.45),
+        (2000, 63.86),
+        (2010, 66.9)
+    ];
+    for &(year, value) in samples.iter() {
+        let jd = JulianDate::from_calendar(year, 1, 1.0);
+        assert_relative_eq!(delta_t(jd), value, epsilon = 0.5);
+    }
+
+    let ut = JulianDate::from_calendar(2024, 4, 8.75);
+    let tt = ut.ut_to_tt();
+    assert_relative_eq!((tt - ut) * 86400.0, delta_t(ut), epsilon = 1e-3);
+    assert_relative_eq!(tt.tt_to_ut().jd(), ut.jd(), epsilon = 1e-9);
+}
+
+#[test]
+#[allow(clippy::excessive_precision)]
+fn epoch_test() {
+    assert_relative_eq!(Epoch::b1950().jd().jd(), 2433282.42345905,
+                        epsilon = 1e-8);
+    assert_eq!(Epoch::j2000().jd(), JulianDate::j2000());
+    let jd: JulianDate = Epoch::Julian(2000.0).into();
+    assert_eq!(jd, JulianDate::j2000());
+
+    // SOFA iauEpb and iauEpj test cases
+    let b = Epoch::besselian(JulianDate::new(2415019.8135 + 30103.18648));
+    assert!(b.is_besselian());
+    assert_relative_eq!(b.year(), 1982.418424159278580, epsilon = 1e-9);
+    let j = Epoch::julian(JulianDate::new(2451545.0 - 7392.5));
+    assert!(j.is_julian());
+    assert_relative_eq!(j.year(), 1979.760438056125941, epsilon = 1e-9);
+
+    // The epochs B1950.0 and J1950.0 differ by about a day
+    let b1950 = Epoch::b1950().to_julian();
+    assert_relative_eq!(b1950.year(), 1949.999790442, epsilon = 1e-9);
+    assert_relative_eq!(b1950.to_besselian().year(), 1950.0,
+                        epsilon = 1e-10);
+    assert_relative_eq!(Epoch::j2000().to_besselian().year(),
+                        2000.001277513665, epsilon = 1e-9);
+}
+
+#[test]
+fn epoch_parse_test() {
+    let samples = [
+        ("B1950.0", Epoch::Besselian(1950.0)),
+        ("J2000.0", Epoch::Julian(2000.0)),
+        ("B1875", Epoch::Besselian(1875.0)),
+        (" j2015.5 ", Epoch::Julian(2015.5)),
+        ("1950.0", Epoch::Besselian(1950.0)),
+        ("2000", Epoch::Julian(2000.0))
+    ];
+    for &(s, epoch) in samples.iter() {
+        assert_eq!(s.parse::<Epoch>().unwrap(), epoch);
+    }
+
+    for s in ["", "B", "X2000.0", "J20x0", "Jinf", "B1950.0.0"] {
+        assert!(s.parse::<Epoch>().is_err());
+    }
+
+    assert_eq!(Epoch::b1950().to_string(), "B1950.0");
+    assert_eq!(Epoch::j2000().to_string(), "J2000.0");
+    assert_eq!(Epoch::Julian(2015.5).to_string(), "J2015.5");
+    assert_eq!(format!("{:.3}", Epoch::Besselian(1950.0)), "B1950.000");
+    assert_eq!(format!("{:.1}", Epoch::j2000().to_besselian()), "B2000.0");
+
+    let epoch: Epoch = Epoch::Julian(1991.25).to_string().parse().unwrap();
+    assert_eq!(epoch, Epoch::Julian(1991.25));
+}
+
+#[test]
+fn tai_utc_test() {
+    assert_eq!(tai_utc(JulianDate::from_calendar(1950, 1, 1.0)), 0.0);
+    assert_eq!(tai_utc(JulianDate::from_calendar(1972, 1, 1.0)), 10.0);
+    assert_eq!(tai_utc(JulianDate::from_calendar(2016, 12, 31.99)), 36.0);
+    assert_eq!(tai_utc(JulianDate::from_calendar(2017, 1, 1.0)), 37.0);
+
+    // SOFA iauDat: the drift of UTC before 1972
+    let jd = JulianDate::from_mjd(38761.0 + 0.5);
+    assert_relative_eq!(tai_utc(JulianDate::from_calendar(1965, 1, 1.5)),
+                        3.5401300 + 0.5 * 0.0012960, epsilon = 1e-9);
+    assert_relative_eq!(tai_utc(jd), 3.5407780, epsilon = 1e-9);
+
+    let utc = JulianDate::from_calendar(2024, 4, 8.75);
+    let tt = utc.utc_to_tt();
+    assert_relative_eq!((tt - utc) * 86400.0, 69.184, epsilon = 1e-4);
+    assert_relative_eq!(tt.tt_to_utc().jd(), utc.jd(), epsilon = 1e-12);
+}